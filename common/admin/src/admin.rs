@@ -3,6 +3,29 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
+/// The named roles that can be granted by the admin. Each privileged endpoint requires the narrowest role that allows
+/// performing it, such that a compromised key only exposes the endpoints of its roles.
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Role {
+    /// Can pause and unpause operations
+    Pauser,
+
+    /// Can set fees and other economic parameters
+    FeeManager,
+
+    /// Can set the parameters used for scoring and sampling Delegation smart contracts
+    ScoreModelManager,
+
+    /// Can whitelist and blacklist Delegation smart contracts and manage the migration whitelist
+    WhitelistManager,
+
+    /// Can withdraw the protocol reserves
+    ReserveTreasurer,
+
+    /// Can pause operations, penalize misbehaving Staking Providers and restore emergency modes
+    Guardian,
+}
+
 #[multiversx_sc::module]
 pub trait AdminModule {
     /// Sets the admin address to the given address.
@@ -26,6 +49,17 @@ pub trait AdminModule {
         require!(caller == admin, "caller must be admin");
     }
 
+    /// Checks if the caller has been granted the given role. Otherwise, it panics with an error message.
+    ///
+    /// # Arguments:
+    ///
+    /// - `role` - The role the caller must have.
+    ///
+    fn require_role(&self, role: Role) {
+        let caller = self.blockchain().get_caller();
+        require!(self.has_role(role, &caller), "caller is missing the required role");
+    }
+
     /// Sets the admin address only if it has not already been set. If it has already been set, this function does
     /// nothing (otherwise, upgrades could change it).
     ///
@@ -108,6 +142,76 @@ pub trait AdminModule {
         }
     }
 
+    /// Grants a role to the given address.
+    ///
+    /// # Arguments:
+    ///
+    /// - `role` - The role to grant.
+    /// - `account` - The address being granted the role.
+    ///
+    /// # Notes:
+    ///
+    /// - Can only be called by the admin.
+    ///
+    #[endpoint(grantRole)]
+    fn grant_role(&self, role: Role, account: &ManagedAddress) {
+        self.require_admin();
+
+        require!(!account.is_zero(), "cannot be address zero");
+        require!(
+            self.role_members(role).insert(account.clone()),
+            "account already has the role"
+        );
+
+        self.role_granted_event(role, account);
+    }
+
+    /// Revokes a role from the given address.
+    ///
+    /// # Arguments:
+    ///
+    /// - `role` - The role to revoke.
+    /// - `account` - The address whose role is revoked.
+    ///
+    /// # Notes:
+    ///
+    /// - Can only be called by the admin.
+    ///
+    #[endpoint(revokeRole)]
+    fn revoke_role(&self, role: Role, account: &ManagedAddress) {
+        self.require_admin();
+
+        require!(
+            self.role_members(role).swap_remove(account),
+            "account does not have the role"
+        );
+
+        self.role_revoked_event(role, account);
+    }
+
+    /// Returns whether the given address has been granted the given role.
+    ///
+    /// # Arguments:
+    ///
+    /// - `role` - The role to check.
+    /// - `account` - The address to check.
+    ///
+    #[view(hasRole)]
+    fn has_role(&self, role: Role, account: &ManagedAddress) -> bool {
+        self.role_members(role).contains(account)
+    }
+
+    /// Returns all the addresses that have been granted the given role.
+    ///
+    /// # Arguments:
+    ///
+    /// - `role` - The role to look up.
+    ///
+    #[view(getRoleMembers)]
+    fn get_role_members(&self, role: Role) -> MultiValueEncoded<ManagedAddress> {
+        self.role_members(role).iter().collect()
+    }
+
     /// Stores the admin address
     #[storage_mapper("admin")]
     fn admin(&self) -> SingleValueMapper<ManagedAddress>;
@@ -116,6 +220,10 @@ pub trait AdminModule {
     #[storage_mapper("pending_admin")]
     fn pending_admin(&self) -> SingleValueMapper<ManagedAddress>;
 
+    /// Stores the addresses that have been granted each role
+    #[storage_mapper("role_members")]
+    fn role_members(&self, role: Role) -> UnorderedSetMapper<ManagedAddress>;
+
     /// Event emitted when the pending admin is updated.
     #[event("new_pending_admin_event")]
     fn new_pending_admin_event(&self, #[indexed] pending_admin: &ManagedAddress);
@@ -123,4 +231,12 @@ pub trait AdminModule {
     /// Event emitted when the admin is updated.
     #[event("new_admin_event")]
     fn new_admin_event(&self, #[indexed] admin: &ManagedAddress);
+
+    /// Event emitted when a role is granted.
+    #[event("role_granted_event")]
+    fn role_granted_event(&self, #[indexed] role: Role, #[indexed] account: &ManagedAddress);
+
+    /// Event emitted when a role is revoked.
+    #[event("role_revoked_event")]
+    fn role_revoked_event(&self, #[indexed] role: Role, #[indexed] account: &ManagedAddress);
 }
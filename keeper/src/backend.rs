@@ -0,0 +1,16 @@
+use anyhow::Result;
+use multiversx_sc::types::Address;
+
+/// The way the keeper reads from and writes to the chain. Arguments and results are raw top-encoded values, such that
+/// the same keeper logic runs both against a gateway and against the scenario VM.
+pub trait Backend {
+    /// Runs a view function and returns its raw results.
+    fn query(&mut self, contract: &Address, function: &str, arguments: &[Vec<u8>]) -> Result<Vec<Vec<u8>>>;
+
+    /// Submits a transaction that calls an endpoint. It fails if the transaction cannot be submitted or, when the
+    /// backend is able to tell, if the transaction fails.
+    fn call(&mut self, contract: &Address, function: &str, arguments: &[Vec<u8>], gas_limit: u64) -> Result<()>;
+
+    /// Returns the current epoch.
+    fn current_epoch(&mut self) -> Result<u64>;
+}
@@ -0,0 +1,167 @@
+use anyhow::{bail, Result};
+use multiversx_sc::types::Address;
+use multiversx_sdk::{
+    blockchain::CommunicationProxy,
+    data::{
+        address::Address as SdkAddress,
+        network_config::NetworkConfig,
+        transaction::Transaction,
+        vm::{VMOutputApi, VmValueRequest},
+    },
+    utils::{base64_decode, base64_encode},
+    wallet::Wallet,
+};
+use tokio::runtime::Runtime;
+
+use crate::backend::Backend;
+
+/// The gateway requests the backend relies on, which allows running it against a mock gateway.
+pub trait Gateway {
+    /// Runs a view function and returns its output.
+    fn execute_vmquery(&mut self, request: &VmValueRequest) -> Result<VMOutputApi>;
+
+    /// Returns the nonce of the given account.
+    fn account_nonce(&mut self, address: &SdkAddress) -> Result<u64>;
+
+    /// Sends a signed transaction and returns its hash.
+    fn send_transaction(&mut self, transaction: &Transaction) -> Result<String>;
+
+    /// Returns the current epoch.
+    fn current_epoch(&mut self) -> Result<u64>;
+}
+
+/// Performs the gateway requests through the MultiversX proxy API.
+pub struct ProxyGateway {
+    runtime: Runtime,
+    proxy: CommunicationProxy,
+}
+
+impl ProxyGateway {
+    pub fn new(gateway: &str) -> Result<Self> {
+        Ok(Self {
+            runtime: Runtime::new()?,
+            proxy: CommunicationProxy::new(gateway.to_string()),
+        })
+    }
+
+    pub fn network_config(&self) -> Result<NetworkConfig> {
+        self.runtime.block_on(self.proxy.get_network_config())
+    }
+}
+
+impl Gateway for ProxyGateway {
+    fn execute_vmquery(&mut self, request: &VmValueRequest) -> Result<VMOutputApi> {
+        let response = self.runtime.block_on(self.proxy.execute_vmquery(request))?;
+        Ok(response.data)
+    }
+
+    fn account_nonce(&mut self, address: &SdkAddress) -> Result<u64> {
+        let account = self.runtime.block_on(self.proxy.get_account(address))?;
+        Ok(account.nonce)
+    }
+
+    fn send_transaction(&mut self, transaction: &Transaction) -> Result<String> {
+        self.runtime.block_on(self.proxy.send_transaction(transaction))
+    }
+
+    fn current_epoch(&mut self) -> Result<u64> {
+        let nonce = self.runtime.block_on(self.proxy.get_latest_hyper_block_nonce(true))?;
+        let hyper_block = self.runtime.block_on(self.proxy.get_hyper_block_by_nonce(nonce))?;
+        Ok(hyper_block.epoch)
+    }
+}
+
+/// Runs the keeper against a MultiversX gateway, signing transactions with the given wallet.
+pub struct GatewayBackend<G: Gateway = ProxyGateway> {
+    gateway: G,
+    wallet: Wallet,
+    network_config: NetworkConfig,
+    next_nonce: u64,
+}
+
+impl GatewayBackend {
+    pub fn new(gateway: &str, wallet: Wallet) -> Result<Self> {
+        let gateway = ProxyGateway::new(gateway)?;
+        let network_config = gateway.network_config()?;
+        Ok(Self::with_gateway(gateway, wallet, network_config))
+    }
+}
+
+impl<G: Gateway> GatewayBackend<G> {
+    pub fn with_gateway(gateway: G, wallet: Wallet, network_config: NetworkConfig) -> Self {
+        Self {
+            gateway,
+            wallet,
+            network_config,
+            next_nonce: 0,
+        }
+    }
+
+    pub fn gateway(&self) -> &G {
+        &self.gateway
+    }
+
+    /// The account nonce might lag behind while previous transactions are still pending, so the highest known nonce is
+    /// used. The known nonce only advances once a transaction is sent, such that a failed send leaves no nonce gap.
+    fn nonce(&mut self) -> Result<u64> {
+        let account_nonce = self.gateway.account_nonce(&self.wallet.address())?;
+        Ok(account_nonce.max(self.next_nonce))
+    }
+}
+
+fn to_sdk_address(address: &Address) -> SdkAddress {
+    SdkAddress::from_bytes(*address.as_array())
+}
+
+impl<G: Gateway> Backend for GatewayBackend<G> {
+    fn query(&mut self, contract: &Address, function: &str, arguments: &[Vec<u8>]) -> Result<Vec<Vec<u8>>> {
+        let request = VmValueRequest {
+            sc_address: to_sdk_address(contract),
+            func_name: function.to_string(),
+            caller: self.wallet.address(),
+            value: "0".to_string(),
+            args: arguments.iter().map(hex::encode).collect(),
+        };
+        let output = self.gateway.execute_vmquery(&request)?;
+        if output.return_code != "ok" {
+            bail!(
+                "{function} failed with code {}: {}",
+                output.return_code,
+                output.return_message
+            );
+        }
+        Ok(output.return_data.iter().map(base64_decode).collect())
+    }
+
+    fn call(&mut self, contract: &Address, function: &str, arguments: &[Vec<u8>], gas_limit: u64) -> Result<()> {
+        let mut data = function.to_string();
+        for argument in arguments {
+            data.push('@');
+            data.push_str(&hex::encode(argument));
+        }
+
+        let nonce = self.nonce()?;
+        let mut transaction = Transaction {
+            nonce,
+            value: "0".to_string(),
+            receiver: to_sdk_address(contract),
+            sender: self.wallet.address(),
+            gas_price: self.network_config.min_gas_price,
+            gas_limit,
+            data: Some(base64_encode(data)),
+            signature: None,
+            chain_id: self.network_config.chain_id.clone(),
+            version: self.network_config.min_transaction_version,
+            options: 0,
+        };
+        transaction.signature = Some(hex::encode(self.wallet.sign_tx(&transaction)));
+
+        self.gateway.send_transaction(&transaction)?;
+        self.next_nonce = nonce + 1;
+        Ok(())
+    }
+
+    fn current_epoch(&mut self) -> Result<u64> {
+        self.gateway.current_epoch()
+    }
+}
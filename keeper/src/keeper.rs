@@ -0,0 +1,193 @@
+//! A keeper for the Liquid Staking smart contract. It periodically reads the Delegation smart contracts list and data,
+//! decides which permissionless actions are due (delegating and undelegating pending amounts, withdrawing matured
+//! undelegations, claiming rewards and delegating them) and submits them.
+
+pub mod backend;
+pub mod gateway;
+pub mod planner;
+pub mod scenario;
+
+use anyhow::{anyhow, Result};
+use liquid_staking::model::{DelegationContractData, PauseStatus};
+use multiversx_sc::{codec::TopDecode, types::Address};
+use multiversx_sc_scenario::{api::StaticApi, num_bigint::BigUint};
+
+pub use backend::Backend;
+pub use planner::{plan, Action, ContractState, PlannerConfig, ProtocolState};
+
+/// The gas limit used for the asynchronous endpoints, which needs to cover both the call and its callback.
+pub const DEFAULT_GAS_LIMIT: u64 = 60_000_000;
+
+/// Formats an address using its bech32 representation.
+pub fn bech32(address: &Address) -> String {
+    multiversx_sdk::data::address::Address::from_bytes(*address.as_array())
+        .to_bech32_string()
+        .unwrap_or_else(|_| hex::encode(address.as_bytes()))
+}
+
+/// The outcome of a keeper run.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// The actions that were due
+    pub planned: Vec<Action>,
+
+    /// The actions that failed together with their error, which is always empty on dry runs
+    pub failed: Vec<(Action, String)>,
+}
+
+pub struct Keeper<B: Backend> {
+    backend: B,
+    liquid_staking: Address,
+    config: PlannerConfig,
+    gas_limit: u64,
+}
+
+impl<B: Backend> Keeper<B> {
+    pub fn new(backend: B, liquid_staking: Address) -> Self {
+        Self {
+            backend,
+            liquid_staking,
+            config: PlannerConfig::default(),
+            gas_limit: DEFAULT_GAS_LIMIT,
+        }
+    }
+
+    pub fn with_config(mut self, config: PlannerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    /// Reads a snapshot of the protocol through the Liquid Staking and the Delegation smart contracts views.
+    pub fn fetch_state(&mut self) -> Result<ProtocolState> {
+        let epoch = self.backend.current_epoch()?;
+        let pause_status = self.query_ls_single("getPauseStatus", &[])?;
+        let rewards_reserve = self.query_ls_single("getRewardsReserve", &[])?;
+
+        // blacklisted contracts are removed from the list, but they might still have funds to undelegate or withdraw
+        let mut addresses = self.query_ls("getDelegationContractsList", &[])?;
+        for address in self.query_ls("getBlacklistedDelegationContracts", &[])? {
+            if !addresses.contains(&address) {
+                addresses.push(address);
+            }
+        }
+
+        let mut contracts = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let contract =
+                Address::decode_result(&address).map_err(|err| anyhow!("invalid contract address: {err}"))?;
+            contracts.push(self.fetch_contract_state(contract)?);
+        }
+
+        Ok(ProtocolState {
+            epoch,
+            pause_status,
+            rewards_reserve,
+            contracts,
+        })
+    }
+
+    fn fetch_contract_state(&mut self, contract: Address) -> Result<ContractState> {
+        let argument = contract.to_vec();
+        let data: DelegationContractData<StaticApi> =
+            self.query_ls_single("getDelegationContractData", &[argument.clone()])?;
+        let last_claim_rewards_epoch = self.query_ls_single("getLastClaimRewardsEpoch", &[argument])?;
+
+        let total_undelegated = to_rust(&data.total_undelegated);
+        let unbondable = if total_undelegated > BigUint::default() {
+            self.query_single(&contract, "getUserUnBondable", &[self.liquid_staking.to_vec()])?
+        } else {
+            BigUint::default()
+        };
+        let claimable_rewards = self.query_single(&contract, "getClaimableRewards", &[self.liquid_staking.to_vec()])?;
+
+        Ok(ContractState {
+            contract,
+            pending_to_delegate: to_rust(&data.pending_to_delegate),
+            pending_to_undelegate: to_rust(&data.pending_to_undelegate),
+            total_undelegated,
+            unbondable,
+            claimable_rewards,
+            last_claim_rewards_epoch,
+            outdated: data.outdated,
+        })
+    }
+
+    /// Fetches the protocol state, plans the due actions and, unless it is a dry run, submits them. A failing action
+    /// does not prevent the remaining ones from being submitted.
+    pub fn run_once(&mut self, dry_run: bool) -> Result<Report> {
+        let state = self.fetch_state()?;
+        let planned = plan(&state, &self.config);
+
+        let mut failed = Vec::new();
+        if !dry_run {
+            for action in planned.iter() {
+                if let Err(err) = self.execute(action) {
+                    failed.push((action.clone(), err.to_string()));
+                }
+            }
+        }
+
+        Ok(Report { planned, failed })
+    }
+
+    pub fn execute(&mut self, action: &Action) -> Result<()> {
+        let liquid_staking = self.liquid_staking.clone();
+        self.backend
+            .call(&liquid_staking, action.endpoint(), &action.arguments(), self.gas_limit)
+    }
+
+    fn query_ls(&mut self, function: &str, arguments: &[Vec<u8>]) -> Result<Vec<Vec<u8>>> {
+        let liquid_staking = self.liquid_staking.clone();
+        self.backend.query(&liquid_staking, function, arguments)
+    }
+
+    fn query_ls_single<T: TopDecodeResult>(&mut self, function: &str, arguments: &[Vec<u8>]) -> Result<T> {
+        let liquid_staking = self.liquid_staking.clone();
+        self.query_single(&liquid_staking, function, arguments)
+    }
+
+    fn query_single<T: TopDecodeResult>(
+        &mut self,
+        contract: &Address,
+        function: &str,
+        arguments: &[Vec<u8>],
+    ) -> Result<T> {
+        let results = self.backend.query(contract, function, arguments)?;
+        let result = results.first().map(Vec::as_slice).unwrap_or_default();
+        T::decode_result(result).map_err(|err| anyhow!("cannot decode the {function} result: {err}"))
+    }
+}
+
+/// Decodes a single view result, where big integers are decoded into their off-chain representation.
+trait TopDecodeResult: Sized {
+    fn decode_result(bytes: &[u8]) -> Result<Self, String>;
+}
+
+impl TopDecodeResult for BigUint {
+    fn decode_result(bytes: &[u8]) -> Result<Self, String> {
+        Ok(BigUint::from_bytes_be(bytes))
+    }
+}
+
+macro_rules! impl_top_decode_result {
+    ($($t:ty),*) => {
+        $(
+            impl TopDecodeResult for $t {
+                fn decode_result(bytes: &[u8]) -> Result<Self, String> {
+                    <$t>::top_decode(bytes).map_err(|err| format!("{err:?}"))
+                }
+            }
+        )*
+    };
+}
+
+impl_top_decode_result!(u64, Address, PauseStatus, DelegationContractData<StaticApi>);
+
+fn to_rust(amount: &multiversx_sc::types::BigUint<StaticApi>) -> BigUint {
+    BigUint::from_bytes_be(amount.to_bytes_be().as_slice())
+}
@@ -0,0 +1,79 @@
+use std::{thread, time::Duration};
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use keeper::{gateway::GatewayBackend, Keeper, PlannerConfig, DEFAULT_GAS_LIMIT};
+use multiversx_sc::types::Address;
+use multiversx_sc_scenario::num_bigint::BigUint;
+use multiversx_sdk::{data::address::Address as SdkAddress, wallet::Wallet};
+
+/// Drives the permissionless Liquid Staking endpoints: delegatePendingAmount, unDelegatePendingAmount, withdrawFrom,
+/// claimRewardsFrom and delegateRewards.
+#[derive(Parser)]
+struct Args {
+    /// The gateway URL
+    #[arg(long, default_value = multiversx_sdk::blockchain::DEVNET_GATEWAY)]
+    gateway: String,
+
+    /// The PEM file of the account that signs the transactions
+    #[arg(long)]
+    pem: String,
+
+    /// The Liquid Staking smart contract bech32 address
+    #[arg(long)]
+    contract: String,
+
+    /// Only logs the actions that are due without submitting them
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Runs a single time instead of looping
+    #[arg(long)]
+    once: bool,
+
+    /// The number of seconds between runs
+    #[arg(long, default_value_t = 60)]
+    interval: u64,
+
+    /// The gas limit of each transaction
+    #[arg(long, default_value_t = DEFAULT_GAS_LIMIT)]
+    gas_limit: u64,
+
+    /// The minimum amount of claimable rewards, in EGLD wei, to claim rewards from a Delegation smart contract
+    #[arg(long, default_value = "1")]
+    min_claimable_rewards: BigUint,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let wallet = Wallet::from_pem_file(&args.pem)?;
+    let contract = SdkAddress::from_bech32_string(&args.contract).map_err(|err| anyhow!("invalid contract: {err}"))?;
+    let backend = GatewayBackend::new(&args.gateway, wallet)?;
+    let config = PlannerConfig {
+        min_claimable_rewards: args.min_claimable_rewards,
+    };
+    let mut keeper = Keeper::new(backend, Address::from(contract.to_bytes()))
+        .with_config(config)
+        .with_gas_limit(args.gas_limit);
+
+    loop {
+        match keeper.run_once(args.dry_run) {
+            Ok(report) => {
+                for action in report.planned.iter() {
+                    let prefix = if args.dry_run { "due" } else { "submitted" };
+                    println!("{prefix}: {action}");
+                }
+                for (action, err) in report.failed.iter() {
+                    eprintln!("failed: {action}: {err}");
+                }
+            },
+            Err(err) => eprintln!("cannot fetch the protocol state: {err}"),
+        }
+
+        if args.once {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(args.interval));
+    }
+}
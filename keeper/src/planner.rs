@@ -0,0 +1,167 @@
+use std::fmt;
+
+use liquid_staking::{constants::MIN_DELEGATION_AMOUNT, model::PauseStatus};
+use multiversx_sc::types::Address;
+use multiversx_sc_scenario::num_bigint::BigUint;
+
+/// A snapshot of a Delegation smart contract as seen by the Liquid Staking smart contract, together with the amounts
+/// that the Liquid Staking smart contract can currently withdraw and claim from it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractState {
+    /// The Delegation smart contract address
+    pub contract: Address,
+
+    /// The amount pending to be delegated to the Delegation smart contract
+    pub pending_to_delegate: BigUint,
+
+    /// The amount pending to be undelegated from the Delegation smart contract
+    pub pending_to_undelegate: BigUint,
+
+    /// The amount undelegated from the Delegation smart contract that has not been withdrawn yet
+    pub total_undelegated: BigUint,
+
+    /// The undelegated amount whose unbond period is over and can be withdrawn right away
+    pub unbondable: BigUint,
+
+    /// The rewards that can be claimed from the Delegation smart contract
+    pub claimable_rewards: BigUint,
+
+    /// The last epoch rewards have been claimed from the Delegation smart contract
+    pub last_claim_rewards_epoch: u64,
+
+    /// Indicates whether the Delegation smart contract data is outdated
+    pub outdated: bool,
+}
+
+/// A snapshot of the Liquid Staking smart contract state relevant to the keeper.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtocolState {
+    /// The current epoch
+    pub epoch: u64,
+
+    /// The operations that are currently paused at the Liquid Staking smart contract
+    pub pause_status: PauseStatus,
+
+    /// The rewards that have been claimed but not delegated yet
+    pub rewards_reserve: BigUint,
+
+    /// Both the listed and the blacklisted Delegation smart contracts
+    pub contracts: Vec<ContractState>,
+}
+
+/// The permissionless actions the keeper performs on behalf of the protocol.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    WithdrawFrom(Address),
+    ClaimRewardsFrom(Address),
+    UndelegatePendingAmount(Address),
+    DelegatePendingAmount(Address),
+    DelegateRewards,
+}
+
+impl Action {
+    /// The Liquid Staking smart contract endpoint that performs this action
+    pub fn endpoint(&self) -> &'static str {
+        match self {
+            Action::WithdrawFrom(_) => "withdrawFrom",
+            Action::ClaimRewardsFrom(_) => "claimRewardsFrom",
+            Action::UndelegatePendingAmount(_) => "unDelegatePendingAmount",
+            Action::DelegatePendingAmount(_) => "delegatePendingAmount",
+            Action::DelegateRewards => "delegateRewards",
+        }
+    }
+
+    /// The top-encoded endpoint arguments
+    pub fn arguments(&self) -> Vec<Vec<u8>> {
+        match self {
+            Action::WithdrawFrom(contract)
+            | Action::ClaimRewardsFrom(contract)
+            | Action::UndelegatePendingAmount(contract)
+            | Action::DelegatePendingAmount(contract) => vec![contract.to_vec()],
+            Action::DelegateRewards => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::WithdrawFrom(contract)
+            | Action::ClaimRewardsFrom(contract)
+            | Action::UndelegatePendingAmount(contract)
+            | Action::DelegatePendingAmount(contract) => {
+                write!(f, "{}({})", self.endpoint(), crate::bech32(contract))
+            },
+            Action::DelegateRewards => write!(f, "{}()", self.endpoint()),
+        }
+    }
+}
+
+/// The keeper thresholds.
+#[derive(Clone, Debug)]
+pub struct PlannerConfig {
+    /// The minimum amount of claimable rewards that makes claiming worth the transaction fee
+    pub min_claimable_rewards: BigUint,
+}
+
+impl Default for PlannerConfig {
+    fn default() -> Self {
+        Self {
+            min_claimable_rewards: BigUint::from(1u64),
+        }
+    }
+}
+
+/// Decides which actions need to be taken given a snapshot of the protocol.
+///
+/// # Notes
+///
+/// - Withdrawals go first, since they bring EGLD back to the protocol.
+/// - Actions whose operation is paused are not planned.
+/// - Pending amounts are neither delegated nor undelegated from outdated Delegation smart contracts, since a previous
+///   attempt has already failed. They are retried once the data manager updates the Delegation smart contract data.
+/// - Pending amounts below the minimum delegation amount cannot be delegated, so they are left for a later run.
+/// - The rewards reserve is checked before claiming, which means that newly claimed rewards are delegated in the
+///   following run.
+///
+pub fn plan(state: &ProtocolState, config: &PlannerConfig) -> Vec<Action> {
+    let min_delegation_amount = BigUint::from(MIN_DELEGATION_AMOUNT);
+    let zero = BigUint::default();
+    let contracts = state.contracts.iter();
+
+    let paused = &state.pause_status;
+
+    let withdrawals = contracts
+        .clone()
+        .filter(|c| !paused.withdraw && c.total_undelegated > zero && c.unbondable > zero)
+        .map(|c| Action::WithdrawFrom(c.contract.clone()));
+
+    let claims = contracts
+        .clone()
+        .filter(|c| {
+            !paused.rewards
+                && c.last_claim_rewards_epoch < state.epoch
+                && c.claimable_rewards >= config.min_claimable_rewards
+        })
+        .map(|c| Action::ClaimRewardsFrom(c.contract.clone()));
+
+    let undelegations = contracts
+        .clone()
+        .filter(|c| !paused.undelegate && c.pending_to_undelegate > zero && !c.outdated)
+        .map(|c| Action::UndelegatePendingAmount(c.contract.clone()));
+
+    let delegations = contracts
+        .filter(|c| !paused.delegate && c.pending_to_delegate >= min_delegation_amount && !c.outdated)
+        .map(|c| Action::DelegatePendingAmount(c.contract.clone()));
+
+    let mut actions: Vec<Action> = withdrawals
+        .chain(claims)
+        .chain(undelegations)
+        .chain(delegations)
+        .collect();
+    if !paused.rewards && state.rewards_reserve >= min_delegation_amount {
+        actions.push(Action::DelegateRewards);
+    }
+
+    actions
+}
@@ -0,0 +1,78 @@
+use anyhow::{bail, Result};
+use multiversx_sc::{contract_base::ContractBase, types::Address};
+use multiversx_sc_scenario::{
+    scenario_model::{AddressValue, ScCallStep, ScQueryStep, TxResponseStatus},
+    DebugApi, ScenarioWorld, WhiteboxContract,
+};
+
+use crate::backend::Backend;
+
+/// Runs the keeper against the scenario VM, which allows rehearsing it offline. The given world needs to have the
+/// Liquid Staking smart contract, the Delegation smart contracts and the keeper account already set up.
+pub struct ScenarioBackend<'a> {
+    world: &'a mut ScenarioWorld,
+    caller: AddressValue,
+    ls_whitebox: WhiteboxContract<liquid_staking::ContractObj<DebugApi>>,
+}
+
+impl<'a> ScenarioBackend<'a> {
+    pub fn new(world: &'a mut ScenarioWorld, caller_expr: &str, liquid_staking_expr: &str) -> Self {
+        Self {
+            world,
+            caller: AddressValue::from(caller_expr),
+            ls_whitebox: WhiteboxContract::new(liquid_staking_expr, liquid_staking::contract_obj),
+        }
+    }
+}
+
+fn hex_argument(argument: &[u8]) -> String {
+    format!("0x{}", hex::encode(argument))
+}
+
+fn check_status(function: &str, status: TxResponseStatus) -> Result<()> {
+    if !status.is_success() {
+        bail!("{function} failed with status {}: {}", status.status, status.message);
+    }
+    Ok(())
+}
+
+impl<'a> Backend for ScenarioBackend<'a> {
+    fn query(&mut self, contract: &Address, function: &str, arguments: &[Vec<u8>]) -> Result<Vec<Vec<u8>>> {
+        let mut step = ScQueryStep::new().to(contract).function(function).no_expect();
+        for argument in arguments {
+            step = step.argument(&hex_argument(argument));
+        }
+
+        let mut response = None;
+        self.world
+            .sc_query_use_raw_response(step, |r| response = Some((r.tx_error.clone(), r.out.clone())));
+        let (status, out) = response.expect("the scenario VM always returns a response");
+        check_status(function, status)?;
+        Ok(out)
+    }
+
+    fn call(&mut self, contract: &Address, function: &str, arguments: &[Vec<u8>], gas_limit: u64) -> Result<()> {
+        let mut step = ScCallStep::new()
+            .from(&self.caller)
+            .to(contract)
+            .function(function)
+            .gas_limit(gas_limit)
+            .no_expect();
+        for argument in arguments {
+            step = step.argument(hex_argument(argument).as_str());
+        }
+
+        let mut status = None;
+        self.world
+            .sc_call_use_raw_response(step, |r| status = Some(r.tx_error.clone()));
+        check_status(function, status.expect("the scenario VM always returns a response"))
+    }
+
+    fn current_epoch(&mut self) -> Result<u64> {
+        let mut epoch = 0;
+        self.world.whitebox_query(&self.ls_whitebox, |sc| {
+            epoch = sc.blockchain().get_block_epoch();
+        });
+        Ok(epoch)
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::{bail, Result};
+use keeper::{
+    gateway::{Gateway, GatewayBackend},
+    Backend,
+};
+use multiversx_sc::types::Address;
+use multiversx_sdk::{
+    data::{
+        address::Address as SdkAddress,
+        network_config::NetworkConfig,
+        transaction::Transaction,
+        vm::{VMOutputApi, VmValueRequest},
+    },
+    wallet::Wallet,
+};
+
+const PRIVATE_KEY: &str = "1a927e2af5306a9bb2ea777f73e06ecc0ac9aaa72fb4ea3fecf659451394cccf";
+
+/// A gateway whose account nonce only advances as sent transactions get executed, which can be made to fail sends.
+#[derive(Default)]
+struct MockGateway {
+    account_nonce: u64,
+    failing_sends: usize,
+    sent_nonces: Vec<u64>,
+}
+
+impl Gateway for MockGateway {
+    fn execute_vmquery(&mut self, _request: &VmValueRequest) -> Result<VMOutputApi> {
+        bail!("queries are not supported")
+    }
+
+    fn account_nonce(&mut self, _address: &SdkAddress) -> Result<u64> {
+        Ok(self.account_nonce)
+    }
+
+    fn send_transaction(&mut self, transaction: &Transaction) -> Result<String> {
+        if self.failing_sends > 0 {
+            self.failing_sends -= 1;
+            bail!("transaction rejected");
+        }
+        self.sent_nonces.push(transaction.nonce);
+        Ok(String::new())
+    }
+
+    fn current_epoch(&mut self) -> Result<u64> {
+        Ok(0)
+    }
+}
+
+fn network_config() -> NetworkConfig {
+    NetworkConfig {
+        chain_id: "T".to_string(),
+        denomination: 18,
+        gas_per_data_byte: 1_500,
+        latest_tag_software_version: String::new(),
+        meta_consensus_group_size: 1,
+        min_gas_limit: 50_000,
+        min_gas_price: 1_000_000_000,
+        min_transaction_version: 1,
+        num_metachain_nodes: 1,
+        num_nodes_in_shard: 1,
+        num_shards_without_meta: 3,
+        round_duration: 6_000,
+        shard_consensus_group_size: 1,
+        start_time: 0,
+    }
+}
+
+fn new_backend(gateway: MockGateway) -> GatewayBackend<MockGateway> {
+    let wallet = Wallet::from_private_key(PRIVATE_KEY).unwrap();
+    GatewayBackend::with_gateway(gateway, wallet, network_config())
+}
+
+#[test]
+fn failed_send_leaves_no_nonce_gap() {
+    let gateway = MockGateway {
+        account_nonce: 5,
+        failing_sends: 1,
+        ..Default::default()
+    };
+    let mut backend = new_backend(gateway);
+    let contract = Address::zero();
+
+    assert!(backend
+        .call(&contract, "delegatePendingAmount", &[], 1_000_000)
+        .is_err());
+
+    // the pending transactions keep the account nonce behind, so the next nonces are tracked locally
+    backend
+        .call(&contract, "delegatePendingAmount", &[], 1_000_000)
+        .unwrap();
+    backend
+        .call(&contract, "unDelegatePendingAmount", &[], 1_000_000)
+        .unwrap();
+    assert_eq!(backend.gateway().sent_nonces, vec![5, 6]);
+}
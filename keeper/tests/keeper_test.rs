@@ -0,0 +1,153 @@
+#[path = "../../liquid-staking/tests/setup/mod.rs"]
+mod setup;
+
+use delegation_mock::MockEndpoint;
+use keeper::{plan, scenario::ScenarioBackend, Action, ContractState, Keeper, PlannerConfig, ProtocolState, Report};
+use liquid_staking::model::PauseStatus;
+use multiversx_sc_scenario::scenario_model::{Account, AddressValue, SetStateStep};
+use setup::*;
+
+const KEEPER_ADDRESS_EXPR: &str = "address:keeper";
+
+fn new_setup() -> LiquidStakingSetup {
+    let mut setup = LiquidStakingSetup::new();
+    setup
+        .world
+        .set_state_step(SetStateStep::new().put_account(KEEPER_ADDRESS_EXPR, Account::new().nonce(1)));
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup
+}
+
+fn run_keeper(setup: &mut LiquidStakingSetup, dry_run: bool) -> Report {
+    let liquid_staking = setup.ls_contract.to_address();
+    let backend = ScenarioBackend::new(&mut setup.world, KEEPER_ADDRESS_EXPR, LIQUID_STAKING_ADDRESS_EXPR);
+    let report = Keeper::new(backend, liquid_staking).run_once(dry_run).unwrap();
+    assert!(report.failed.is_empty(), "{:?}", report.failed);
+    report
+}
+
+#[test]
+fn keeper_drives_async_endpoints() {
+    let mut setup = new_setup();
+    let contract = setup.delegation_contract(0);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    let report = run_keeper(&mut setup, false);
+    assert_eq!(report.planned, vec![Action::DelegatePendingAmount(contract.clone())]);
+    assert_eq!(to_rust(&setup.contract_data(0).total_delegated), egld(10));
+
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(4));
+    setup.add_rewards(0, &egld(2));
+    let report = run_keeper(&mut setup, false);
+    assert_eq!(
+        report.planned,
+        vec![
+            Action::ClaimRewardsFrom(contract.clone()),
+            Action::UndelegatePendingAmount(contract.clone()),
+        ]
+    );
+    assert_eq!(setup.total_undelegated(), egld(4));
+    assert!(setup.rewards_reserve() > egld(1));
+
+    // rewards have already been claimed in this epoch and the undelegation has not matured yet
+    let report = run_keeper(&mut setup, false);
+    assert_eq!(report.planned, vec![Action::DelegateRewards]);
+    assert_eq!(setup.rewards_reserve(), egld(0));
+
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    let report = run_keeper(&mut setup, false);
+    assert_eq!(report.planned, vec![Action::WithdrawFrom(contract)]);
+    assert_eq!(setup.total_withdrawable(), egld(4));
+
+    assert!(run_keeper(&mut setup, false).planned.is_empty());
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn dry_run_does_not_submit_actions() {
+    let mut setup = new_setup();
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+
+    let report = run_keeper(&mut setup, true);
+    assert_eq!(
+        report.planned,
+        vec![Action::DelegatePendingAmount(setup.delegation_contract(0))]
+    );
+    assert_eq!(to_rust(&setup.contract_data(0).pending_to_delegate), egld(10));
+
+    // the same actions are due until they are submitted
+    assert_eq!(run_keeper(&mut setup, true).planned, report.planned);
+}
+
+#[test]
+fn keeper_skips_outdated_contracts() {
+    let mut setup = new_setup();
+    setup.set_failure(0, MockEndpoint::Delegate, true);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+
+    // the asynchronous call fails, so the pending amount is restored and the contract is marked as outdated
+    run_keeper(&mut setup, false);
+    let contract_data = setup.contract_data(0);
+    assert!(contract_data.outdated);
+    assert_eq!(to_rust(&contract_data.pending_to_delegate), egld(10));
+    assert!(run_keeper(&mut setup, false).planned.is_empty());
+
+    setup.set_failure(0, MockEndpoint::Delegate, false);
+    setup.change_delegation_contract_params(0, 100, 800, 1_000);
+    run_keeper(&mut setup, false);
+    assert_eq!(to_rust(&setup.contract_data(0).total_delegated), egld(10));
+}
+
+#[test]
+fn paused_operations_are_not_planned() {
+    let contract = |name: &str| AddressValue::from(name).to_address();
+    let contract_state = |name: &str| ContractState {
+        contract: contract(name),
+        pending_to_delegate: egld(5),
+        pending_to_undelegate: egld(3),
+        total_undelegated: egld(2),
+        unbondable: egld(2),
+        claimable_rewards: egld(1),
+        last_claim_rewards_epoch: 1,
+        outdated: false,
+    };
+
+    let mut state = ProtocolState {
+        epoch: 2,
+        pause_status: PauseStatus {
+            delegate: true,
+            undelegate: false,
+            withdraw: false,
+            rewards: true,
+            penalties: false,
+            migration: false,
+        },
+        rewards_reserve: egld(1),
+        contracts: vec![contract_state("sc:first"), contract_state("sc:second")],
+    };
+    state.contracts[1].outdated = true;
+
+    assert_eq!(
+        plan(&state, &PlannerConfig::default()),
+        vec![
+            Action::WithdrawFrom(contract("sc:first")),
+            Action::WithdrawFrom(contract("sc:second")),
+            Action::UndelegatePendingAmount(contract("sc:first")),
+        ]
+    );
+
+    state.pause_status.delegate = false;
+    state.pause_status.rewards = false;
+    assert_eq!(
+        plan(&state, &PlannerConfig::default()),
+        vec![
+            Action::WithdrawFrom(contract("sc:first")),
+            Action::WithdrawFrom(contract("sc:second")),
+            Action::ClaimRewardsFrom(contract("sc:first")),
+            Action::ClaimRewardsFrom(contract("sc:second")),
+            Action::UndelegatePendingAmount(contract("sc:first")),
+            Action::DelegatePendingAmount(contract("sc:first")),
+            Action::DelegateRewards,
+        ]
+    );
+}
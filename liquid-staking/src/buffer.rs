@@ -0,0 +1,248 @@
+multiversx_sc::imports!();
+use super::{
+    common,
+    constants::{BPS, MIN_DELEGATION_AMOUNT},
+    delegation,
+    errors::*,
+    events,
+    model::*,
+    proxies, score, selection, storage,
+};
+
+#[multiversx_sc::module]
+pub trait BufferModule:
+    admin::AdminModule
+    + common::CommonModule
+    + delegation::DelegationModule
+    + events::EventsModule
+    + proxies::ProxyModule
+    + score::ScoreModule
+    + selection::SelectionModule
+    + storage::StorageModule
+{
+    /// Allows users to redeem sEGLD in exchange for EGLD immediately, without waiting for the unbond period. The EGLD
+    /// is taken from the liquidity buffer, which is filled with a fraction of every delegation. The paid sEGLD is
+    /// burned.
+    ///
+    /// # Arguments
+    ///
+    /// - `opt_min_egld_out` - the optional minimum amount of EGLD the caller expects to receive, after fees
+    ///
+    /// # Notes
+    ///
+    /// - The liquidity buffer must hold the whole EGLD amount corresponding to the paid sEGLD.
+    /// - An instant undelegate fee is discounted from the EGLD amount. Depending on the configuration, the fee is added
+    ///   to the protocol reserve or it is left at the liquidity buffer, increasing the exchange rate for all sEGLD
+    ///   holders, who are the ones providing the buffer liquidity.
+    /// - The amount of EGLD received can be previewed using the `previewInstantUnDelegate` view.
+    ///
+    #[payable("*")]
+    #[endpoint(instantUnDelegate)]
+    fn instant_undelegate(&self, opt_min_egld_out: OptionalValue<BigUint>) -> BigUint {
+        self.require_not_paused(Operation::Undelegate);
+
+        let (ls_token_id, shares) = self.call_value().single_fungible_esdt();
+        self.require_valid_shares_payment(&ls_token_id, &shares);
+
+        let egld_amount = self.shares_to_egld(&shares);
+        require!(egld_amount > BigUint::zero(), ERROR_INSUFFICIENT_EGLD_AMOUNT);
+
+        let liquidity_buffer_mapper = self.liquidity_buffer();
+        require!(
+            egld_amount <= liquidity_buffer_mapper.get(),
+            ERROR_INSUFFICIENT_LIQUIDITY_BUFFER
+        );
+
+        let fee = self.get_instant_undelegate_fee_amount(&egld_amount);
+        let net_egld_amount = &egld_amount - &fee;
+        self.require_min_amount_out(&net_egld_amount, opt_min_egld_out, ERROR_EGLD_OUT_BELOW_MIN);
+
+        match self.instant_undelegate_fee_recipient().get() {
+            InstantUndelegateFeeRecipient::ProtocolReserve => {
+                self.redeem_shares(&egld_amount, &shares);
+                liquidity_buffer_mapper.update(|amount| *amount -= &egld_amount);
+                self.protocol_reserve().update(|amount| *amount += &fee);
+            },
+            InstantUndelegateFeeRecipient::BufferProviders => {
+                self.redeem_shares(&net_egld_amount, &shares);
+                liquidity_buffer_mapper.update(|amount| *amount -= &net_egld_amount);
+            },
+        }
+
+        let caller = self.blockchain().get_caller();
+        self.send().direct_egld(&caller, &net_egld_amount);
+
+        let liquidity_buffer = liquidity_buffer_mapper.get();
+        self.instant_undelegate_event(&caller, &shares, &net_egld_amount, &fee, &liquidity_buffer);
+
+        net_egld_amount
+    }
+
+    /// Refills the liquidity buffer by undelegating from a Delegation smart contract selected by the undelegation
+    /// algorithm. Similarly to `unDelegate`, it does not perform the undelegation automatically, which is left to the
+    /// `unDelegatePendingAmount` public endpoint. Once the unbond period has passed and the EGLD has been brought back
+    /// using `withdrawFrom`, the refill can be moved into the liquidity buffer using `withdrawBufferRefill`.
+    ///
+    /// # Arguments
+    ///
+    /// - `opt_egld_amount` - the amount of EGLD to undelegate. If unspecified, it defaults to the liquidity buffer
+    ///   deficit.
+    ///
+    /// # Notes
+    ///
+    /// - This endpoint can be called by anyone.
+    /// - The EGLD amount cannot exceed the liquidity buffer deficit, which already accounts for previous refills that
+    ///   have not been withdrawn yet.
+    ///
+    #[endpoint(refillLiquidityBuffer)]
+    fn refill_liquidity_buffer(&self, opt_egld_amount: OptionalValue<BigUint>) -> u64 {
+        self.require_not_paused(Operation::Undelegate);
+
+        let deficit = self.get_liquidity_buffer_deficit();
+        require!(deficit > BigUint::zero(), ERROR_LIQUIDITY_BUFFER_ON_TARGET);
+
+        let egld_amount = match opt_egld_amount {
+            OptionalValue::None => deficit,
+            OptionalValue::Some(amount) => {
+                require!(amount <= deficit, ERROR_TOO_MUCH_EGLD_AMOUNT);
+                amount
+            },
+        };
+        self.require_sufficient_egld(&egld_amount);
+
+        let delegation_contract = self.get_delegation_contract_for_undelegate(&egld_amount);
+
+        let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
+        self.update_delegation_contract_data(&delegation_contract, |data| {
+            data.total_delegated -= &egld_amount;
+            data.pending_to_undelegate += &egld_amount;
+        });
+
+        self.pending_buffer_refill().update(|amount| *amount += &egld_amount);
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        let unbond_epoch = current_epoch + self.unbond_period().get();
+
+        let refill_id = self.get_next_buffer_refill_id();
+        let attrs = UndelegateAttributes {
+            delegation_contract,
+            egld_amount,
+            shares: BigUint::zero(),
+            undelegate_epoch: current_epoch,
+            unbond_epoch,
+        };
+
+        self.buffer_refills(refill_id).set(&attrs);
+
+        let caller = self.blockchain().get_caller();
+        let contract_data = contract_data_mapper.get();
+        self.refill_liquidity_buffer_event(&caller, refill_id, &attrs, &contract_data);
+
+        refill_id
+    }
+
+    /// Moves a buffer refill into the liquidity buffer once the unbond period has passed. Similarly to `withdraw`, the
+    /// public endpoint `withdrawFrom` should have been called prior to using this function.
+    ///
+    /// # Arguments
+    ///
+    /// - `refill_id` - the buffer refill identifier
+    ///
+    /// # Notes
+    ///
+    /// - This endpoint can be called by anyone.
+    ///
+    #[endpoint(withdrawBufferRefill)]
+    fn withdraw_buffer_refill(&self, refill_id: u64) {
+        self.require_not_paused(Operation::Withdraw);
+
+        let refill_mapper = self.buffer_refills(refill_id);
+        require!(!refill_mapper.is_empty(), ERROR_UNEXPECTED_BUFFER_REFILL_ID);
+
+        let attributes = refill_mapper.take();
+        self.withdraw_internal(&attributes);
+
+        let egld_amount = attributes.egld_amount;
+        self.pending_buffer_refill().update(|amount| *amount -= &egld_amount);
+        self.liquidity_buffer().update(|amount| *amount += &egld_amount);
+
+        let caller = self.blockchain().get_caller();
+        let liquidity_buffer = self.liquidity_buffer().get();
+        self.withdraw_buffer_refill_event(&caller, refill_id, &liquidity_buffer);
+    }
+
+    /// Returns the amount of EGLD that would be received for a given amount of sEGLD using `instantUnDelegate`, i.e.
+    /// after discounting the instant undelegate fee
+    ///
+    #[view(previewInstantUnDelegate)]
+    fn preview_instant_undelegate(&self, shares: BigUint) -> BigUint {
+        let egld_amount = self.shares_to_egld(&shares);
+        let fee = self.get_instant_undelegate_fee_amount(&egld_amount);
+        egld_amount - fee
+    }
+
+    /// Returns the target size of the liquidity buffer in EGLD, based on the current cash reserve
+    ///
+    #[view(getLiquidityBufferTargetAmount)]
+    fn get_liquidity_buffer_target_amount(&self) -> BigUint {
+        self.cash_reserve().get() * self.liquidity_buffer_target().get() / BPS
+    }
+
+    /// Returns the amount of EGLD missing at the liquidity buffer to reach its target, taking into account the buffer
+    /// refills that have not been withdrawn yet
+    ///
+    #[view(getLiquidityBufferDeficit)]
+    fn get_liquidity_buffer_deficit(&self) -> BigUint {
+        let target_amount = self.get_liquidity_buffer_target_amount();
+        let available = self.liquidity_buffer().get() + self.pending_buffer_refill().get();
+        if target_amount > available {
+            target_amount - available
+        } else {
+            BigUint::zero()
+        }
+    }
+
+    /// Keeps part of a delegation at the liquidity buffer such that it reaches its target, and returns the retained
+    /// amount. The remaining amount is never left below the minimum delegation amount, in which case it is retained as
+    /// well.
+    ///
+    /// # Arguments
+    ///
+    /// - `egld_amount` - the delegated amount, which has not been added to the cash reserve yet
+    ///
+    fn retain_liquidity_buffer(&self, egld_amount: &BigUint) -> BigUint {
+        let target = self.liquidity_buffer_target().get();
+        if target == BigUint::zero() {
+            return BigUint::zero();
+        }
+
+        let target_amount = (self.cash_reserve().get() + egld_amount) * &target / BPS;
+        let available = self.liquidity_buffer().get() + self.pending_buffer_refill().get();
+        if target_amount <= available {
+            return BigUint::zero();
+        }
+
+        let deficit = target_amount - available;
+        let mut retained_amount = core::cmp::min(deficit, egld_amount.clone());
+
+        let amount_left = egld_amount - &retained_amount;
+        if amount_left > BigUint::zero() && amount_left < MIN_DELEGATION_AMOUNT {
+            retained_amount = egld_amount.clone();
+        }
+
+        self.liquidity_buffer().update(|amount| *amount += &retained_amount);
+
+        retained_amount
+    }
+
+    fn get_instant_undelegate_fee_amount(&self, egld_amount: &BigUint) -> BigUint {
+        egld_amount * &self.instant_undelegate_fee().get() / BPS
+    }
+
+    fn get_next_buffer_refill_id(&self) -> u64 {
+        let next_buffer_refill_id = self.next_buffer_refill_id();
+        let refill_id = next_buffer_refill_id.get();
+        next_buffer_refill_id.set(refill_id + 1);
+        refill_id
+    }
+}
@@ -3,9 +3,14 @@ use super::{
     constants::*,
     errors::*,
     events,
-    model::{State, UndelegateAttributes, UndelegationMode},
+    model::{
+        CapacityIndex, DelegationContractData, Operation, PauseStatus, RewardsStream, UndelegateAttributes,
+        UndelegationMode,
+    },
     proxies, storage,
 };
+use admin::Role;
+use core::cmp::Ordering;
 
 #[multiversx_sc::module]
 pub trait CommonModule:
@@ -18,9 +23,25 @@ pub trait CommonModule:
         true
     }
 
-    #[view(isActive)]
-    fn is_active(&self) -> bool {
-        self.state().get() == State::Active
+    /// Returns whether the given operation is paused
+    ///
+    #[view(isPaused)]
+    fn is_paused(&self, operation: Operation) -> bool {
+        self.paused_operations().get() & operation.flag() != 0
+    }
+
+    /// Returns the pause status of every operation
+    ///
+    #[view(getPauseStatus)]
+    fn get_pause_status(&self) -> PauseStatus {
+        PauseStatus {
+            delegate: self.is_paused(Operation::Delegate),
+            undelegate: self.is_paused(Operation::Undelegate),
+            withdraw: self.is_paused(Operation::Withdraw),
+            rewards: self.is_paused(Operation::Rewards),
+            penalties: self.is_paused(Operation::Penalties),
+            migration: self.is_paused(Operation::Migration),
+        }
     }
 
     /// Verifies if rewards have been already claimed for a given Delegation smart contract
@@ -41,20 +62,163 @@ pub trait CommonModule:
     /// Verifies if the given Delegation smart contract is in the list
     ///
     fn is_delegation_contract_in_list(&self, delegation_contract: &ManagedAddress) -> bool {
+        !self.list_position(delegation_contract).is_empty()
+    }
+
+    /// Checks whether a new value deviates from the current one within the given bound (in bps). Values are not bounded
+    /// if either the bound or the current value is zero
+    ///
+    fn is_within_deviation(&self, current: &BigUint, new: &BigUint, max_deviation: u64) -> bool {
+        if max_deviation == 0 || current == &BigUint::zero() {
+            return true;
+        }
+        let deviation = if new > current { new - current } else { current - new };
+        deviation * BPS <= current * max_deviation
+    }
+
+    /// Verifies if the data of a given Delegation smart contract is older than the maximum contract data age
+    ///
+    fn is_stale_contract_data(&self, delegation_contract: &ManagedAddress) -> bool {
+        let max_age = self.max_contract_data_age().get();
+        if max_age == 0 {
+            return false;
+        }
+        let current_epoch = self.blockchain().get_block_epoch();
+        let update_epoch = self.get_contract_data_update_epoch(delegation_contract);
+        current_epoch.saturating_sub(update_epoch) > max_age
+    }
+
+    /// Returns the Delegation smart contracts in the list whose data is older than the maximum contract data age
+    ///
+    #[view(getStaleDelegationContracts)]
+    fn get_stale_delegation_contracts(&self) -> MultiValueEncoded<ManagedAddress> {
+        let mut stale_contracts = MultiValueEncoded::new();
         for node in self.delegation_contracts_list().iter() {
-            let delegation_contract_in_list = node.into_value();
-            if delegation_contract == &delegation_contract_in_list {
-                return true;
+            let delegation_contract = node.into_value();
+            if self.is_stale_contract_data(&delegation_contract) {
+                stale_contracts.push(delegation_contract);
+            }
+        }
+        stale_contracts
+    }
+
+    /// Verifies if delegating a given EGLD amount to a Delegation smart contract keeps both the Delegation smart
+    /// contract and its operator within their maximum shares of the cash reserve. Internal moves of already delegated
+    /// EGLD (i.e. rebalances and penalty re-delegations) do not grow the cash reserve. The limits only apply once the
+    /// cash reserve reaches `MIN_CASH_RESERVE_FOR_CONCENTRATION_LIMITS` EGLD.
+    ///
+    fn is_within_concentration_limits(
+        &self,
+        delegation_contract: &ManagedAddress,
+        egld_amount: &BigUint,
+        grows_cash_reserve: bool,
+    ) -> bool {
+        let limits_mapper = self.concentration_limits();
+        if limits_mapper.is_empty() {
+            return true;
+        }
+
+        // the cash reserve grows with the delegation when it comes from a user
+        let limits = limits_mapper.get();
+        let mut cash_reserve = self.cash_reserve().get();
+        if grows_cash_reserve {
+            cash_reserve += egld_amount;
+        }
+
+        // a small cash reserve is bootstrapped without limits
+        if cash_reserve < BigUint::from(WAD) * MIN_CASH_RESERVE_FOR_CONCENTRATION_LIMITS {
+            return true;
+        }
+
+        if limits.max_contract_share > 0 {
+            let allocation = self.get_allocation(delegation_contract.clone()) + egld_amount;
+            if allocation * BPS > &cash_reserve * limits.max_contract_share {
+                return false;
+            }
+        }
+
+        let operator_mapper = self.operator_id(delegation_contract);
+        if limits.max_operator_share > 0 && !operator_mapper.is_empty() {
+            let allocation = self.get_operator_allocation(operator_mapper.get()) + egld_amount;
+            if allocation * BPS > &cash_reserve * limits.max_operator_share {
+                return false;
             }
         }
-        false
+
+        true
+    }
+
+    /// Returns the amount of EGLD allocated to a given Delegation smart contract, including the amount pending to be
+    /// delegated and the rebalances on their way to it
+    ///
+    /// # Arguments
+    ///
+    /// - `delegation_contract` - the Delegation smart contract address
+    ///
+    #[view(getAllocation)]
+    fn get_allocation(&self, delegation_contract: ManagedAddress) -> BigUint {
+        let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
+        if contract_data_mapper.is_empty() {
+            return BigUint::zero();
+        }
+
+        let contract_data = contract_data_mapper.get();
+        contract_data.total_delegated
+            + contract_data.pending_to_delegate
+            + self.incoming_rebalance(&delegation_contract).get()
+    }
+
+    /// Returns the amount of EGLD allocated to all the Delegation smart contracts of a given operator
+    ///
+    /// # Arguments
+    ///
+    /// - `operator_id` - the operator identifier
+    ///
+    #[view(getOperatorAllocation)]
+    fn get_operator_allocation(&self, operator_id: u64) -> BigUint {
+        let operator_data_mapper = self.operator_data(operator_id);
+        if operator_data_mapper.is_empty() {
+            return BigUint::zero();
+        }
+
+        operator_data_mapper.get().allocation
+    }
+
+    /// Returns the share of the cash reserve allocated to a given Delegation smart contract in bps
+    ///
+    /// # Arguments
+    ///
+    /// - `delegation_contract` - the Delegation smart contract address
+    ///
+    #[view(getConcentration)]
+    fn get_concentration(&self, delegation_contract: ManagedAddress) -> BigUint {
+        self.get_share_of_cash_reserve(self.get_allocation(delegation_contract))
+    }
+
+    /// Returns the share of the cash reserve allocated to all the Delegation smart contracts of a given operator in bps
+    ///
+    /// # Arguments
+    ///
+    /// - `operator_id` - the operator identifier
+    ///
+    #[view(getOperatorConcentration)]
+    fn get_operator_concentration(&self, operator_id: u64) -> BigUint {
+        self.get_share_of_cash_reserve(self.get_operator_allocation(operator_id))
+    }
+
+    fn get_share_of_cash_reserve(&self, egld_amount: BigUint) -> BigUint {
+        let cash_reserve = self.cash_reserve().get();
+        if cash_reserve == BigUint::zero() {
+            return BigUint::zero();
+        }
+        egld_amount * BPS / cash_reserve
     }
 
     // Requires
 
     #[inline]
-    fn require_active_state(&self) {
-        require!(self.is_active(), ERROR_INACTIVE);
+    fn require_not_paused(&self, operation: Operation) {
+        require!(!self.is_paused(operation), ERROR_OPERATION_PAUSED);
     }
 
     #[inline]
@@ -78,6 +242,14 @@ pub trait CommonModule:
         require!(shares > &BigUint::zero(), ERROR_INSUFFICIENT_SHARES);
     }
 
+    /// Reverts if the amount received by the caller is below the minimum amount they expect, if given
+    ///
+    fn require_min_amount_out(&self, amount: &BigUint, opt_min_amount_out: OptionalValue<BigUint>, err_msg: &[u8]) {
+        if let OptionalValue::Some(min_amount_out) = opt_min_amount_out {
+            require!(amount >= &min_amount_out, err_msg);
+        }
+    }
+
     #[inline]
     fn require_valid_penalty_id(&self, penalty_id: u64) {
         require!(!self.penalties(penalty_id).is_empty(), ERROR_UNEXPECTED_PENALTY_ID);
@@ -91,11 +263,11 @@ pub trait CommonModule:
         );
     }
 
-    fn require_open_mode_or_admin(&self) {
+    fn require_open_mode_or_guardian(&self) {
         if self.undelegation_mode().get() == UndelegationMode::Open {
             return;
         }
-        self.require_admin();
+        self.require_role(Role::Guardian);
     }
 
     #[inline]
@@ -174,6 +346,27 @@ pub trait CommonModule:
         });
     }
 
+    /// Sets the last contract data update epoch of a given Delegation smart contract, which also updates the last
+    /// contract data update epoch across all of them
+    ///
+    fn set_contract_data_update_epoch_internal(&self, delegation_contract: &ManagedAddress, epoch: u64) {
+        self.contract_data_update_epoch(delegation_contract).set(epoch);
+        self.set_last_contract_data_update_epoch_internal(epoch);
+    }
+
+    /// Tries to set the last contract data update epoch of the Delegation smart contracts in the list that have not
+    /// been tracked yet
+    ///
+    fn try_set_contract_data_update_epochs(&self, epoch: u64) {
+        for node in self.delegation_contracts_list().iter() {
+            let delegation_contract = node.into_value();
+            let update_epoch_mapper = self.contract_data_update_epoch(&delegation_contract);
+            if update_epoch_mapper.is_empty() {
+                update_epoch_mapper.set(epoch);
+            }
+        }
+    }
+
     // Gets
 
     /// Returns the liquid staking token identifier
@@ -197,6 +390,27 @@ pub trait CommonModule:
         self.last_contract_data_update_epoch().get()
     }
 
+    /// Returns the last epoch the data of a given Delegation smart contract has been updated
+    ///
+    fn get_contract_data_update_epoch(&self, delegation_contract: &ManagedAddress) -> u64 {
+        self.contract_data_update_epoch(delegation_contract).get()
+    }
+
+    /// Returns the oldest data update epoch among the Delegation smart contracts in the list, or the last contract data
+    /// update epoch if the list is empty
+    ///
+    fn get_oldest_contract_data_update_epoch(&self) -> u64 {
+        let mut oldest_epoch = None;
+        for node in self.delegation_contracts_list().iter() {
+            let delegation_contract = node.into_value();
+            let update_epoch = self.get_contract_data_update_epoch(&delegation_contract);
+            if oldest_epoch.map_or(true, |epoch| update_epoch < epoch) {
+                oldest_epoch = Some(update_epoch);
+            }
+        }
+        oldest_epoch.unwrap_or_else(|| self.get_last_contract_data_update_epoch())
+    }
+
     /// Returns the last epoch rewards have been claimed for a given Delegation smart contract or 0 if the rewards have
     /// not been claimed yet
     ///
@@ -250,6 +464,18 @@ pub trait CommonModule:
         gas_left - MIN_GAS_FOR_CALLBACK
     }
 
+    /// Returns the gas for one of the asynchronous calls performed within a batch, or `None` if the gas left cannot
+    /// afford another asynchronous call together with its callback
+    ///
+    fn get_gas_for_batched_async_call(&self) -> Option<u64> {
+        let gas_left = self.blockchain().get_gas_left();
+        if gas_left > MIN_GAS_FOR_ASYNC_CALL + MIN_GAS_FOR_CALLBACK {
+            Some(MIN_GAS_FOR_ASYNC_CALL)
+        } else {
+            None
+        }
+    }
+
     /// Computes the current exchange rate in WAD between EGLD and sEGLD
     ///
     #[view(getExchangeRate)]
@@ -263,11 +489,80 @@ pub trait CommonModule:
             return BigUint::from(INITIAL_EXCHANGE_RATE);
         }
 
-        let cash = self.cash_reserve().get();
+        // rewards that have not been vested yet are excluded, which are always backed by the cash reserve because
+        // redemptions are priced without them
+        let cash = self.cash_reserve().get() - self.get_unvested_rewards();
 
         cash * wad / ls_token_supply
     }
 
+    /// Computes the amount of delegated rewards that have not been vested into the exchange rate yet
+    ///
+    #[view(getUnvestedRewards)]
+    fn get_unvested_rewards(&self) -> BigUint {
+        let stream_mapper = self.rewards_stream();
+        if stream_mapper.is_empty() {
+            return BigUint::zero();
+        }
+
+        let stream = stream_mapper.get();
+        let round = self.blockchain().get_block_round();
+        if round >= stream.end_round {
+            return BigUint::zero();
+        }
+
+        let remaining_rounds = stream.end_round - round;
+        let total_rounds = stream.end_round - stream.start_round;
+        stream.amount * remaining_rounds / total_rounds
+    }
+
+    /// Adds delegated rewards to the rewards stream. The new rewards are vested over a whole rewards vesting period
+    /// starting at the current round, while the rewards that have not been vested yet keep their remaining period: the
+    /// stream ends after the average of both periods weighted by their amounts, such that topping up the stream does
+    /// not restart the vesting of the unvested rewards.
+    ///
+    fn stream_rewards(&self, egld_amount: &BigUint) {
+        let period = self.rewards_vesting_period().get();
+        if period == 0 {
+            return;
+        }
+
+        let round = self.blockchain().get_block_round();
+        let unvested_rewards = self.get_unvested_rewards();
+        let remaining_rounds = if unvested_rewards == 0 {
+            period
+        } else {
+            let stream_remaining_rounds = self.rewards_stream().get().end_round - round;
+            let weighted_rounds = &unvested_rewards * stream_remaining_rounds + egld_amount * period;
+            // the weighted average never exceeds the longest of both periods
+            (weighted_rounds / &(&unvested_rewards + egld_amount))
+                .to_u64()
+                .unwrap_or(period)
+        };
+
+        let stream = RewardsStream {
+            amount: unvested_rewards + egld_amount,
+            start_round: round,
+            end_round: round + remaining_rounds,
+        };
+        self.rewards_stream().set(&stream);
+        self.rewards_stream_event(egld_amount, &stream);
+    }
+
+    /// Returns the amount of sEGLD that would be minted for a given amount of EGLD at the current exchange rate
+    ///
+    #[view(previewDelegate)]
+    fn preview_delegate(&self, egld_amount: BigUint) -> BigUint {
+        self.egld_to_shares(&egld_amount)
+    }
+
+    /// Returns the amount of EGLD that would be undelegated for a given amount of sEGLD at the current exchange rate
+    ///
+    #[view(previewUnDelegate)]
+    fn preview_undelegate(&self, shares: BigUint) -> BigUint {
+        self.shares_to_egld(&shares)
+    }
+
     /// Translates an amount of EGLD into sEGLD based on the current exchange rate
     ///
     fn egld_to_shares(&self, egld_amount: &BigUint) -> BigUint {
@@ -344,6 +639,53 @@ pub trait CommonModule:
         self.undelegate_token().nft_burn(token_nonce, &BigUint::from(1u64));
     }
 
+    /// Sends a payment to a given receiver. If an endpoint is given, the payment is sent along with a call to that
+    /// endpoint and arguments at the receiver, which must be a smart contract in the same shard. A failed cross-shard
+    /// transfer and execute would bounce the payment back to this smart contract, where it would be stranded.
+    ///
+    fn send_or_transfer_execute(
+        &self,
+        receiver: &ManagedAddress,
+        payment: EgldOrEsdtTokenPayment,
+        opt_endpoint: OptionalValue<ManagedBuffer>,
+        args: MultiValueEncoded<ManagedBuffer>,
+    ) {
+        match opt_endpoint {
+            OptionalValue::None => {
+                self.send().direct(
+                    receiver,
+                    &payment.token_identifier,
+                    payment.token_nonce,
+                    &payment.amount,
+                );
+            },
+            OptionalValue::Some(endpoint) => {
+                require!(
+                    self.blockchain().is_smart_contract(receiver),
+                    ERROR_RECEIVER_NOT_SMART_CONTRACT
+                );
+                let sc_address = self.blockchain().get_sc_address();
+                require!(
+                    self.blockchain().get_shard_of_address(receiver)
+                        == self.blockchain().get_shard_of_address(&sc_address),
+                    ERROR_RECEIVER_CROSS_SHARD
+                );
+
+                ContractCallNoPayment::<_, IgnoreValue>::new(receiver.clone(), endpoint)
+                    .with_egld_or_single_esdt_transfer(payment)
+                    .with_raw_arguments(args.to_arg_buffer())
+                    .transfer_execute();
+            },
+        }
+    }
+
+    /// Returns the undelegation batch assigned to undelegate NFTs minted at this point for a given Delegation smart
+    /// contract, which is one-based such that it can be stored and told apart from an empty storage
+    ///
+    fn get_current_undelegation_batch(&self, delegation_contract: &ManagedAddress) -> u64 {
+        self.undelegation_batch(delegation_contract).get() + 1
+    }
+
     /// Computes a linear function on a domain given by `min` and `max`. Also, this linear function has a bounded image
     /// between zero and one (in basis points). Finally, if `down` is true, the line has a negative slope.
     ///
@@ -389,10 +731,129 @@ pub trait CommonModule:
             ERROR_TOO_MUCH_EGLD_AMOUNT
         );
 
-        contract_data_mapper.update(|data| {
+        self.update_delegation_contract_data(delegation_contract, |data| {
             data.total_withdrawable -= egld_amount;
         });
 
         self.total_withdrawable().update(|amount| *amount -= egld_amount);
     }
+
+    /// Updates the data of a Delegation smart contract and keeps its capacity indexes and the allocation of its
+    /// operator, if any, in sync with it
+    ///
+    fn update_delegation_contract_data<F>(&self, delegation_contract: &ManagedAddress, update: F)
+    where
+        F: FnOnce(&mut DelegationContractData<Self::Api>),
+    {
+        let contract_data_mapper = self.delegation_contract_data(delegation_contract);
+        let old_contract_data = contract_data_mapper.get();
+        let mut contract_data = old_contract_data.clone();
+        update(&mut contract_data);
+        contract_data_mapper.set(&contract_data);
+        self.update_capacity_indexes(Some(&old_contract_data), &contract_data);
+
+        let old_allocation = old_contract_data.total_delegated + old_contract_data.pending_to_delegate;
+        let new_allocation = &contract_data.total_delegated + &contract_data.pending_to_delegate;
+        match new_allocation.cmp(&old_allocation) {
+            Ordering::Greater => {
+                self.increase_operator_allocation(delegation_contract, &(new_allocation - old_allocation))
+            },
+            Ordering::Less => {
+                self.decrease_operator_allocation(delegation_contract, &(old_allocation - new_allocation))
+            },
+            Ordering::Equal => {},
+        }
+    }
+
+    /// Adds an amount of EGLD allocated to a Delegation smart contract to the allocation of its operator, if any
+    ///
+    fn increase_operator_allocation(&self, delegation_contract: &ManagedAddress, egld_amount: &BigUint) {
+        let operator_mapper = self.operator_id(delegation_contract);
+        if operator_mapper.is_empty() {
+            return;
+        }
+
+        self.operator_data(operator_mapper.get())
+            .update(|operator_data| operator_data.allocation += egld_amount);
+    }
+
+    /// Removes an amount of EGLD no longer allocated to a Delegation smart contract from the allocation of its
+    /// operator, if any
+    ///
+    fn decrease_operator_allocation(&self, delegation_contract: &ManagedAddress, egld_amount: &BigUint) {
+        let operator_mapper = self.operator_id(delegation_contract);
+        if operator_mapper.is_empty() {
+            return;
+        }
+
+        self.operator_data(operator_mapper.get())
+            .update(|operator_data| operator_data.allocation -= egld_amount);
+    }
+
+    /// Moves a Delegation smart contract to the buckets of the capacity indexes given by its new data
+    ///
+    fn update_capacity_indexes(
+        &self,
+        opt_old_contract_data: Option<&DelegationContractData<Self::Api>>,
+        new_contract_data: &DelegationContractData<Self::Api>,
+    ) {
+        for index in [CapacityIndex::Delegate, CapacityIndex::Undelegate] {
+            let old_bucket = opt_old_contract_data.and_then(|data| self.get_capacity_bucket(index, data));
+            let new_bucket = self.get_capacity_bucket(index, new_contract_data);
+            if old_bucket == new_bucket {
+                continue;
+            }
+
+            let delegation_contract = &new_contract_data.contract;
+            if let Some(bucket) = old_bucket {
+                let mut bucket_mapper = self.capacity_bucket(index, bucket);
+                bucket_mapper.swap_remove(delegation_contract);
+                if bucket_mapper.is_empty() {
+                    self.capacity_bitmap(index).update(|bits| *bits &= !(1u64 << bucket));
+                }
+            }
+
+            if let Some(bucket) = new_bucket {
+                self.capacity_bucket(index, bucket).insert(delegation_contract.clone());
+                self.capacity_bitmap(index).update(|bits| *bits |= 1u64 << bucket);
+            }
+        }
+    }
+
+    /// Returns the bucket of a capacity index holding a Delegation smart contract, if any. Outdated and blacklisted
+    /// Delegation smart contracts, as well as those without any capacity, are not indexed.
+    ///
+    fn get_capacity_bucket(
+        &self,
+        index: CapacityIndex,
+        contract_data: &DelegationContractData<Self::Api>,
+    ) -> Option<u32> {
+        if contract_data.outdated || contract_data.blacklisted {
+            return None;
+        }
+
+        let capacity = match index {
+            CapacityIndex::Delegate => match &contract_data.cap {
+                Some(cap) if cap > &contract_data.total_value_locked => cap - &contract_data.total_value_locked,
+                Some(_) => return None,
+                None => return Some(NR_CAPACITY_BUCKETS - 1),
+            },
+            CapacityIndex::Undelegate => contract_data.total_delegated.clone(),
+        };
+
+        if capacity == BigUint::zero() {
+            return None;
+        }
+
+        Some(self.get_amount_bucket(&capacity))
+    }
+
+    /// Returns the bucket of a capacity index matching a given EGLD amount, such that Delegation smart contracts in
+    /// lower buckets have a lower capacity and those in higher buckets have a higher capacity
+    ///
+    fn get_amount_bucket(&self, egld_amount: &BigUint) -> u32 {
+        let nr_egld = (egld_amount / &BigUint::from(WAD)).to_u64().unwrap_or(u64::MAX);
+        let bit_length = u64::BITS - nr_egld.leading_zeros();
+        bit_length.min(NR_CAPACITY_BUCKETS - 2)
+    }
 }
@@ -16,8 +16,44 @@ pub const INITIAL_EXCHANGE_RATE: u64 = 1_000_000_000_000_000_000;
 /// The minimum amount of EGLD that can be delegated or undelegated from a Delegation smart contract
 pub const MIN_DELEGATION_AMOUNT: u64 = 1_000_000_000_000_000_000;
 
-/// The maximum number of Delegation smart contracts that can be registered based on gas limits
-pub const MAX_DELEGATION_CONTRACTS_LIST_SIZE: usize = 100;
+/// The maximum number of rounds over which delegated rewards are vested into the exchange rate, i.e. ten epochs at
+/// Mainnet
+pub const MAX_REWARDS_VESTING_PERIOD: u64 = 144_000;
+
+/// The maximum delay in epochs between queueing a timelocked call and executing it
+pub const MAX_TIMELOCK_DELAY: u64 = 30;
+
+/// The maximum delegation score, which all delegation score methods are bounded by
+pub const MAX_DELEGATION_SCORE: u64 = BPS;
+
+/// The maximum number of terms of the weighted delegation score method
+pub const MAX_DELEGATION_SCORE_TERMS: usize = 10;
+
+/// The maximum number of breakpoints of a piecewise-linear delegation score curve
+pub const MAX_SCORE_CURVE_BREAKPOINTS: usize = 10;
+
+/// The maximum number of Delegation smart contracts inspected when selecting a Delegation smart contract or its
+/// sampling candidates, which keeps the gas of delegations and undelegations independent of the list size
+pub const MAX_SELECTION_SCAN_SIZE: usize = 25;
+
+/// The number of buckets of each capacity index. Delegation smart contracts are indexed in the bucket given by the bit
+/// length of their capacity in whole EGLD, except for uncapped Delegation smart contracts, which fill the last bucket
+pub const NR_CAPACITY_BUCKETS: u32 = 64;
+
+/// The maximum number of Delegation smart contracts rescored right away when the delegation score model changes, the
+/// rest being left to `sortDelegationContractsList`
+pub const MAX_SORT_BATCH_SIZE: usize = 50;
+
+/// The maximum number of Delegation smart contracts run by a single operator, which bounds the gas spent rescoring them
+/// whenever the aggregated data of the operator changes
+pub const MAX_OPERATOR_CONTRACTS: usize = 10;
+
+/// The cash reserve (in whole EGLD) below which the concentration limits do not apply yet, since a smaller cash reserve
+/// cannot be spread among enough Delegation smart contracts to stay within them
+pub const MIN_CASH_RESERVE_FOR_CONCENTRATION_LIMITS: u64 = 100;
+
+/// The number of delegation scores tracked by each word of the delegation scores bitmap
+pub const SCORE_BITMAP_WORD_SIZE: u64 = 64;
 
 /// The gas provided for any async call to a delegation contract, including delegate, undelegate, withdraw or claim
 /// rewards
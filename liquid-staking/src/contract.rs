@@ -5,6 +5,7 @@ multiversx_sc::derive_imports!();
 pub use admin;
 use constants::NO_DATA_UPDATE_EPOCHS;
 
+pub mod buffer;
 pub mod common;
 pub mod constants;
 pub mod delegate;
@@ -12,14 +13,20 @@ pub mod delegation;
 pub mod errors;
 pub mod events;
 pub mod governance;
+pub mod invariants;
+pub mod invariants_view;
 pub mod migration;
 pub mod model;
 pub mod penalty;
 pub mod proxies;
+pub mod rebalance;
+pub mod refresh;
+pub mod reporting;
 pub mod rewards;
 pub mod score;
 pub mod selection;
 pub mod storage;
+pub mod timelock;
 pub mod undelegate;
 pub mod withdraw;
 
@@ -28,20 +35,27 @@ use crate::{constants::NO_UNDELEGATE_EPOCHS, model::*};
 #[multiversx_sc::contract]
 pub trait LiquidStaking:
     admin::AdminModule
+    + buffer::BufferModule
     + common::CommonModule
     + delegate::DelegateModule
     + delegation::DelegationModule
     + events::EventsModule
     + governance::GovernanceModule
+    + invariants::InvariantsModule
+    + invariants_view::InvariantsViewModule
     + migration::MigrationModule
     + rewards::RewardsModule
     + score::ScoreModule
     + selection::SelectionModule
     + storage::StorageModule
+    + timelock::TimelockModule
     + undelegate::UndelegateModule
     + withdraw::WithdrawModule
     + penalty::PenaltyModule
     + proxies::ProxyModule
+    + rebalance::RebalanceModule
+    + refresh::RefreshModule
+    + reporting::ReportingModule
 {
     /// Initializes the contract.
     ///
@@ -71,12 +85,28 @@ pub trait LiquidStaking:
         // try set admin
         self.try_set_admin(opt_admin);
 
-        // protocol status
-        self.state().set(State::Inactive);
+        // all operations are paused until the protocol is configured
+        self.set_paused_internal(MultiValueEncoded::new(), true);
     }
 
     #[upgrade]
     fn upgrade(&self) {
-        self.state().set(State::Inactive);
+        // score curves extend the delegation score model
+        self.try_migrate_delegation_score_model();
+
+        // track the position and the capacity of the Delegation smart contracts already in the list
+        self.try_index_delegation_contracts_list();
+        self.try_set_total_delegation_score();
+        self.try_index_delegation_contract_capacities();
+
+        // start tracking the data update epoch of each Delegation smart contract
+        let current_epoch = self.blockchain().get_block_epoch();
+        self.try_set_contract_data_update_epochs(current_epoch);
+
+        // deposits are paused until the upgrade is reviewed, while exits keep their current status
+        let mut operations = MultiValueEncoded::new();
+        operations.push(Operation::Delegate);
+        operations.push(Operation::Migration);
+        self.set_paused_internal(operations, true);
     }
 }
@@ -1,10 +1,13 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
-use super::{common, delegation, errors::*, events, migration, proxies, score, selection, storage};
+use super::{
+    buffer, common, delegation, errors::*, events, migration, model::Operation, proxies, score, selection, storage,
+};
 
 #[multiversx_sc::module]
 pub trait DelegateModule:
     admin::AdminModule
+    + buffer::BufferModule
     + common::CommonModule
     + delegation::DelegationModule
     + events::EventsModule
@@ -19,39 +22,87 @@ pub trait DelegateModule:
     /// Instead, anyone can perform the delegation at any given point in time using the `delegatePendingAmount` public
     /// endpoint.
     ///
+    /// # Arguments
+    ///
+    /// - `opt_min_shares_out` - the optional minimum amount of sEGLD the caller expects to receive
+    /// - `opt_receiver` - the optional account receiving the sEGLD, which defaults to the caller
+    /// - `opt_endpoint` - the optional endpoint at the receiver smart contract to be called along with the sEGLD
+    /// - `args` - the arguments for the receiver endpoint
+    ///
     /// # Notes
     ///
     /// - There is a minimum amount of 1 EGLD required for delegations.
     /// - If the caller is whitelisted, they may bypass the delegation algorithm.
-    /// - The amount of sEGLD minted depends on the current exchange rate between EGLD and sEGLD.
+    /// - The amount of sEGLD minted depends on the current exchange rate between EGLD and sEGLD, and can be previewed
+    ///   using the `previewDelegate` view.
+    /// - Part of the EGLD amount is kept at the liquidity buffer if the buffer is below its target. Whitelisted callers
+    ///   always delegate their whole amount.
+    /// - The sEGLD can be forwarded to a smart contract endpoint using a transfer and execute, allowing integrators to
+    ///   compose delegations with other actions in a single transaction. The receiver smart contract must be in the
+    ///   same shard, since the sEGLD of a failed cross-shard call would bounce back to this smart contract.
+    /// - Optional arguments are positional, so a zero minimum amount of sEGLD must be given in order to specify a
+    ///   receiver.
     ///
     #[payable("EGLD")]
+    #[allow_multiple_var_args]
     #[endpoint(delegate)]
-    fn delegate(&self) -> EsdtTokenPayment {
-        self.require_active_state();
+    fn delegate(
+        &self,
+        opt_min_shares_out: OptionalValue<BigUint>,
+        opt_receiver: OptionalValue<ManagedAddress>,
+        opt_endpoint: OptionalValue<ManagedBuffer>,
+        args: MultiValueEncoded<ManagedBuffer>,
+    ) -> EsdtTokenPayment {
+        self.require_not_paused(Operation::Delegate);
 
         let egld_amount = self.call_value().egld_value().clone_value();
         self.require_sufficient_egld(&egld_amount);
 
         let caller = self.blockchain().get_caller();
-        let delegation_contract = if self.migration_whitelist(&caller).is_empty() {
-            self.get_delegation_contract_for_delegate(&egld_amount, &OptionalValue::None)
+        let receiver = opt_receiver.into_option().unwrap_or_else(|| caller.clone());
+        let is_whitelisted = !self.migration_whitelist(&caller).is_empty();
+        if is_whitelisted {
+            self.require_not_paused(Operation::Migration);
+        }
+
+        let buffer_amount = if is_whitelisted {
+            BigUint::zero()
         } else {
-            self.get_whitelisted_delegation_contract_for_delegate(&caller, &egld_amount)
+            self.retain_liquidity_buffer(&egld_amount)
         };
 
-        let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
-        contract_data_mapper.update(|data| {
-            data.pending_to_delegate += &egld_amount;
-        });
+        let delegated_amount = &egld_amount - &buffer_amount;
+        let opt_contract_data = if delegated_amount > BigUint::zero() {
+            let delegation_contract = if is_whitelisted {
+                self.get_whitelisted_delegation_contract_for_delegate(&caller, &delegated_amount)
+            } else {
+                self.get_delegation_contract_for_delegate(&delegated_amount, &OptionalValue::None, true)
+            };
+
+            let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
+            self.update_delegation_contract_data(&delegation_contract, |data| {
+                data.pending_to_delegate += &delegated_amount;
+            });
+
+            Some(contract_data_mapper.get())
+        } else {
+            None
+        };
 
         let (ls_token_id, _, shares) = self.mint_shares(&egld_amount).into_tuple();
-        self.send().direct_esdt(&caller, &ls_token_id, 0, &shares);
+        self.require_min_amount_out(&shares, opt_min_shares_out, ERROR_SHARES_OUT_BELOW_MIN);
 
-        let contract_data = contract_data_mapper.get();
-        self.delegate_event(&caller, &egld_amount, &shares, &contract_data);
+        if buffer_amount > BigUint::zero() {
+            let liquidity_buffer = self.liquidity_buffer().get();
+            self.retain_liquidity_buffer_event(&caller, &buffer_amount, &liquidity_buffer);
+        }
+
+        self.delegate_event(&caller, &receiver, &egld_amount, &shares, &opt_contract_data);
 
-        EsdtTokenPayment::new(ls_token_id, 0, shares)
+        let payment = EsdtTokenPayment::new(ls_token_id, 0, shares);
+        self.send_or_transfer_execute(&receiver, payment.clone().into(), opt_endpoint, args);
+
+        payment
     }
 
     /// Initiates the delegation of the pending amount to the specified Delegation smart contract. This endpoint
@@ -76,7 +127,7 @@ pub trait DelegateModule:
     ///
     #[endpoint(delegatePendingAmount)]
     fn delegate_pending_amount(&self, delegation_contract: ManagedAddress, opt_egld_amount: OptionalValue<BigUint>) {
-        self.require_active_state();
+        self.require_not_paused(Operation::Delegate);
 
         let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
         require!(!contract_data_mapper.is_empty(), ERROR_UNEXPECTED_DELEGATION_CONTRACT);
@@ -97,7 +148,7 @@ pub trait DelegateModule:
         };
 
         // update smart contract data to handle concurrent calls to this endpoint
-        contract_data_mapper.update(|data| {
+        self.update_delegation_contract_data(&delegation_contract, |data| {
             data.pending_to_delegate -= &egld_amount;
         });
 
@@ -109,6 +160,59 @@ pub trait DelegateModule:
         self.delegate_to_delegation_contract(delegation_contract, egld_amount, gas_for_async_call, callback);
     }
 
+    /// Initiates the delegation of the pending amounts of all the Delegation smart contracts in the list within a
+    /// single transaction. It performs one asynchronous call per Delegation smart contract with a non-zero pending
+    /// amount, each one with its own callback.
+    ///
+    /// # Notes
+    ///
+    /// - This endpoint can be called by anyone.
+    /// - Delegation smart contracts are visited in the list order while there is enough gas left for another
+    ///   asynchronous call and its callback. The remaining ones can be delegated in a later call.
+    /// - A failing delegation only reverts the pending amount and marks as outdated its own Delegation smart contract.
+    ///
+    #[endpoint(delegateAllPending)]
+    fn delegate_all_pending(&self) {
+        self.require_not_paused(Operation::Delegate);
+
+        // makes sure there is gas for at least one asynchronous call
+        self.get_gas_for_async_call();
+
+        let caller = self.blockchain().get_caller();
+        let mut num_calls = 0usize;
+        for node in self.delegation_contracts_list().iter() {
+            let delegation_contract = node.into_value();
+            let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
+            let egld_amount = contract_data_mapper.get().pending_to_delegate;
+            if egld_amount == BigUint::zero() {
+                continue;
+            }
+
+            let gas_for_async_call = match self.get_gas_for_batched_async_call() {
+                Some(gas) => gas,
+                None => break,
+            };
+
+            // update smart contract data to handle concurrent calls to this endpoint
+            self.update_delegation_contract_data(&delegation_contract, |data| {
+                data.pending_to_delegate -= &egld_amount;
+            });
+
+            let callback = self
+                .callbacks()
+                .delegate_all_pending_cb(&caller, &delegation_contract, &egld_amount);
+            self.delegate_to_delegation_contract_promise(
+                delegation_contract,
+                egld_amount,
+                gas_for_async_call,
+                callback,
+            );
+            num_calls += 1;
+        }
+
+        require!(num_calls > 0, ERROR_NO_PENDING_TO_DELEGATE);
+    }
+
     #[callback]
     fn delegate_pending_amount_cb(
         &self,
@@ -116,16 +220,42 @@ pub trait DelegateModule:
         delegation_contract: &ManagedAddress,
         egld_amount: &BigUint,
         #[call_result] result: ManagedAsyncCallResult<()>,
+    ) {
+        self.delegate_pending_amount_result(caller, delegation_contract, egld_amount, result);
+    }
+
+    // the EGLD is sent back along with the callback if the delegation fails
+    #[payable("EGLD")]
+    #[promises_callback]
+    fn delegate_all_pending_cb(
+        &self,
+        caller: &ManagedAddress,
+        delegation_contract: &ManagedAddress,
+        egld_amount: &BigUint,
+        #[call_result] result: ManagedAsyncCallResult<()>,
+    ) {
+        self.delegate_pending_amount_result(caller, delegation_contract, egld_amount, result);
+    }
+
+    /// Updates the Delegation smart contract data once the delegation of a pending amount has finished, either
+    /// successfully or not.
+    ///
+    fn delegate_pending_amount_result(
+        &self,
+        caller: &ManagedAddress,
+        delegation_contract: &ManagedAddress,
+        egld_amount: &BigUint,
+        result: ManagedAsyncCallResult<()>,
     ) {
         match result {
             ManagedAsyncCallResult::Ok(()) => {
-                self.delegation_contract_data(delegation_contract).update(|data| {
+                self.update_delegation_contract_data(delegation_contract, |data| {
                     data.total_delegated += egld_amount;
                 });
                 self.delegate_pending_amount_event(caller, delegation_contract, egld_amount);
             },
             ManagedAsyncCallResult::Err(err) => {
-                self.delegation_contract_data(delegation_contract).update(|data| {
+                self.update_delegation_contract_data(delegation_contract, |data| {
                     data.pending_to_delegate += egld_amount;
                     data.outdated = true;
                 });
@@ -1,6 +1,13 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
-use super::{common, constants::*, errors::*, events, proxies, score, selection, storage};
+use super::{
+    common,
+    constants::*,
+    errors::*,
+    events,
+    model::{DelegationContractData, ListPosition, OperatorData},
+    proxies, score, selection, storage,
+};
 
 #[multiversx_sc::module]
 pub trait DelegationModule:
@@ -12,18 +19,53 @@ pub trait DelegationModule:
     + selection::SelectionModule
     + storage::StorageModule
 {
-    /// Removes a Delegation smart contract from the Delegation smart contracts list based on its value (i.e. address)
-    /// if it belongs to the list.
+    /// Removes a Delegation smart contract from the Delegation smart contracts list if it belongs to the list. Its
+    /// position is tracked, so the list is not traversed.
     ///
-    fn remove_delegation_contract_from_list(&self, new_delegation_contract: &ManagedAddress) {
-        for node in self.delegation_contracts_list().iter() {
-            let node_id = node.get_node_id();
-            let delegation_contract = node.into_value();
-            if new_delegation_contract == &delegation_contract {
-                self.delegation_contracts_list().remove_node_by_id(node_id);
-                break;
+    fn remove_delegation_contract_from_list(&self, delegation_contract: &ManagedAddress) {
+        let position_mapper = self.list_position(delegation_contract);
+        if position_mapper.is_empty() {
+            return;
+        }
+
+        let ListPosition {
+            node_id,
+            delegation_score,
+        } = position_mapper.take();
+
+        let mut delegation_contracts_mapper = self.delegation_contracts_list();
+        let head_mapper = self.score_head_node_id(delegation_score);
+        if head_mapper.get() == node_id {
+            // the next node becomes the first one with this score if it shares the score
+            let next_node_id = delegation_contracts_mapper
+                .get_node_by_id(node_id)
+                .map(|node| node.get_next_node_id())
+                .unwrap_or_default();
+            let next_delegation_score = delegation_contracts_mapper
+                .get_node_by_id(next_node_id)
+                .map(|node| self.list_position(node.get_value_as_ref()).get().delegation_score);
+
+            if next_delegation_score == Some(delegation_score) {
+                head_mapper.set(next_node_id);
+            } else {
+                head_mapper.clear();
+                self.set_score_bit(delegation_score, false);
             }
         }
+
+        // the pending rescoring resumes from the next node
+        let sort_cursor_mapper = self.sort_cursor();
+        if sort_cursor_mapper.get() == node_id {
+            let next_node_id = delegation_contracts_mapper
+                .get_node_by_id(node_id)
+                .map(|node| node.get_next_node_id())
+                .unwrap_or_default();
+            sort_cursor_mapper.set(next_node_id);
+        }
+
+        delegation_contracts_mapper.remove_node_by_id(node_id);
+        self.total_delegation_score()
+            .update(|total_score| *total_score -= delegation_score);
     }
 
     /// Adds a given Staking Provider Delegation smart contract into the Delegation smart contracts list based on its
@@ -34,55 +76,292 @@ pub trait DelegationModule:
     ///
     /// - if there is an existent Delegation smart contract with the same score, the new Delegation smart contract will
     ///   have priority.
+    /// - the position is found through the first node of each delegation score and the delegation scores bitmap, so
+    ///   the list is not traversed.
     ///
     fn add_and_order_delegation_contract_in_list(
         &self,
         new_delegation_contract: &ManagedAddress,
         delegation_score: &BigUint,
     ) {
+        let delegation_score = delegation_score
+            .to_u64()
+            .filter(|score| *score <= MAX_DELEGATION_SCORE)
+            .unwrap_or_else(|| sc_panic!(ERROR_INVALID_DELEGATION_SCORE));
+
         let mut delegation_contracts_mapper = self.delegation_contracts_list();
-        require!(
-            delegation_contracts_mapper.len() < MAX_DELEGATION_CONTRACTS_LIST_SIZE,
-            ERROR_DELEGATION_CONTRACTS_LIST_FULL
-        );
-        if delegation_contracts_mapper.is_empty() {
-            delegation_contracts_mapper.push_front(new_delegation_contract.clone());
+        let head_mapper = self.score_head_node_id(delegation_score);
+
+        let next_node_id = if !head_mapper.is_empty() {
+            Some(head_mapper.get())
         } else {
-            let mut added = false;
-            for node in delegation_contracts_mapper.iter() {
-                let node_id = node.get_node_id();
-                let delegation_contract = node.into_value();
-                let delegation_contract_data = self.delegation_contract_data(&delegation_contract).get();
-                if delegation_score >= &delegation_contract_data.delegation_score {
-                    delegation_contracts_mapper.push_before_node_id(node_id, new_delegation_contract.clone());
-                    added = true;
-                    break;
-                }
+            self.set_score_bit(delegation_score, true);
+            self.get_lower_held_score(delegation_score)
+                .map(|lower_score| self.score_head_node_id(lower_score).get())
+        };
+
+        let node = match next_node_id {
+            Some(next_node_id) => delegation_contracts_mapper
+                .push_before_node_id(next_node_id, new_delegation_contract.clone())
+                .unwrap(),
+            None => delegation_contracts_mapper.push_back(new_delegation_contract.clone()),
+        };
+
+        let node_id = node.get_node_id();
+        head_mapper.set(node_id);
+        self.list_position(new_delegation_contract).set(ListPosition {
+            node_id,
+            delegation_score,
+        });
+        self.total_delegation_score()
+            .update(|total_score| *total_score += delegation_score);
+    }
+
+    /// Marks whether a delegation score is held by at least one Delegation smart contract in the list
+    ///
+    fn set_score_bit(&self, delegation_score: u64, held: bool) {
+        let word = delegation_score / SCORE_BITMAP_WORD_SIZE;
+        let bit = 1u64 << (delegation_score % SCORE_BITMAP_WORD_SIZE);
+        let word_bits = self.score_bitmap(word).update(|bits| {
+            if held {
+                *bits |= bit;
+            } else {
+                *bits &= !bit;
             }
-            if !added {
-                delegation_contracts_mapper.push_back(new_delegation_contract.clone());
+            *bits
+        });
+
+        let summary_word = word / SCORE_BITMAP_WORD_SIZE;
+        let summary_bit = 1u64 << (word % SCORE_BITMAP_WORD_SIZE);
+        self.score_summary_bitmap(summary_word).update(|bits| {
+            if word_bits != 0 {
+                *bits |= summary_bit;
+            } else {
+                *bits &= !summary_bit;
             }
+        });
+    }
+
+    /// Returns the highest delegation score below the given one that is held by at least one Delegation smart contract
+    /// in the list, if any
+    ///
+    fn get_lower_held_score(&self, delegation_score: u64) -> Option<u64> {
+        let word = delegation_score / SCORE_BITMAP_WORD_SIZE;
+        let lower_bits = self.score_bitmap(word).get() & ((1u64 << (delegation_score % SCORE_BITMAP_WORD_SIZE)) - 1);
+        if lower_bits != 0 {
+            return Some(word * SCORE_BITMAP_WORD_SIZE + highest_bit(lower_bits));
+        }
+
+        // look for the highest non-empty word below this one
+        let mut summary_word = word / SCORE_BITMAP_WORD_SIZE;
+        let mut mask = (1u64 << (word % SCORE_BITMAP_WORD_SIZE)) - 1;
+        loop {
+            let summary_bits = self.score_summary_bitmap(summary_word).get() & mask;
+            if summary_bits != 0 {
+                let lower_word = summary_word * SCORE_BITMAP_WORD_SIZE + highest_bit(summary_bits);
+                let word_bits = self.score_bitmap(lower_word).get();
+                return Some(lower_word * SCORE_BITMAP_WORD_SIZE + highest_bit(word_bits));
+            }
+            if summary_word == 0 {
+                return None;
+            }
+            summary_word -= 1;
+            mask = u64::MAX;
         }
     }
 
-    /// Computes all delegation scores and sorts the Delegation smart contracts list based on these new values.
+    /// Sums the delegation scores of the Delegation smart contracts in the list if it has not been done yet. It is
+    /// intended to be called after indexing the list.
     ///
-    fn sort_delegation_contracts_list(&self) {
+    fn try_set_total_delegation_score(&self) {
+        let total_score_mapper = self.total_delegation_score();
+        if !total_score_mapper.is_empty() {
+            return;
+        }
+
+        let mut total_score = 0u64;
         for node in self.delegation_contracts_list().iter() {
+            total_score += self.list_position(node.get_value_as_ref()).get().delegation_score;
+        }
+        total_score_mapper.set(total_score);
+    }
+
+    /// Indexes the Delegation smart contracts added to the list before their positions were tracked. The list is
+    /// already sorted, so the first node found with a given score is the first one with that score.
+    ///
+    fn try_index_delegation_contracts_list(&self) {
+        for node in self.delegation_contracts_list().iter() {
+            let node_id = node.get_node_id();
             let delegation_contract = node.into_value();
-            let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
-            let contract_data = contract_data_mapper.get();
+            let position_mapper = self.list_position(&delegation_contract);
+            if !position_mapper.is_empty() {
+                continue;
+            }
+
+            let delegation_score = self
+                .delegation_contract_data(&delegation_contract)
+                .get()
+                .delegation_score
+                .to_u64()
+                .unwrap_or_else(|| sc_panic!(ERROR_INVALID_DELEGATION_SCORE));
+
+            let head_mapper = self.score_head_node_id(delegation_score);
+            if head_mapper.is_empty() {
+                head_mapper.set(node_id);
+                self.set_score_bit(delegation_score, true);
+            }
+
+            position_mapper.set(ListPosition {
+                node_id,
+                delegation_score,
+            });
+        }
+    }
 
-            let delegation_score = self.compute_delegation_score(&contract_data);
+    /// Indexes the capacities of the Delegation smart contracts added to the list before the capacity indexes existed
+    ///
+    fn try_index_delegation_contract_capacities(&self) {
+        for node in self.delegation_contracts_list().iter() {
+            let contract_data = self.delegation_contract_data(&node.into_value()).get();
+            self.update_capacity_indexes(None, &contract_data);
+        }
+    }
 
-            if contract_data.delegation_score != delegation_score {
-                self.remove_delegation_contract_from_list(&delegation_contract);
-                self.add_and_order_delegation_contract_in_list(&delegation_contract, &delegation_score);
+    /// Computes the delegation score of a given Delegation smart contract and, if it has changed, moves the Delegation
+    /// smart contract to its new position in the Delegation smart contracts list. If the Delegation smart contract has
+    /// an operator, the delegation scores of all the Delegation smart contracts of the operator in the list are
+    /// updated, since all of them depend on the operator data.
+    ///
+    fn update_delegation_score(&self, delegation_contract: &ManagedAddress) {
+        let operator_mapper = self.operator_id(delegation_contract);
+        if operator_mapper.is_empty() {
+            self.update_delegation_score_internal(delegation_contract);
+            return;
+        }
+
+        self.update_operator_delegation_scores(operator_mapper.get());
+    }
+
+    /// Updates the delegation scores of the Delegation smart contracts of a given operator that are in the list
+    ///
+    fn update_operator_delegation_scores(&self, operator_id: u64) {
+        for delegation_contract in self.operator_contracts(operator_id).iter() {
+            if self.is_delegation_contract_in_list(&delegation_contract) {
+                self.update_delegation_score_internal(&delegation_contract);
             }
+        }
+    }
+
+    fn update_delegation_score_internal(&self, delegation_contract: &ManagedAddress) {
+        let contract_data_mapper = self.delegation_contract_data(delegation_contract);
+        let contract_data = contract_data_mapper.get();
+        let new_delegation_score = self.compute_delegation_score(&contract_data);
 
-            contract_data_mapper.update(|contract_data| {
-                contract_data.delegation_score = delegation_score;
+        if contract_data.delegation_score != new_delegation_score {
+            self.update_delegation_contract_data(delegation_contract, |data| {
+                data.delegation_score = new_delegation_score.clone();
             });
+
+            self.remove_delegation_contract_from_list(delegation_contract);
+            self.add_and_order_delegation_contract_in_list(delegation_contract, &new_delegation_score);
         }
     }
+
+    /// Adds the total value locked, the number of nodes and the allocation of a Delegation smart contract to the data
+    /// of a given operator
+    ///
+    fn add_to_operator_data(&self, operator_id: u64, contract_data: &DelegationContractData<Self::Api>) {
+        let allocation = self.get_allocation(contract_data.contract.clone());
+        let operator_data_mapper = self.operator_data(operator_id);
+        let operator_data = if operator_data_mapper.is_empty() {
+            OperatorData {
+                total_value_locked: contract_data.total_value_locked.clone(),
+                nr_nodes: contract_data.nr_nodes,
+                allocation,
+            }
+        } else {
+            let operator_data = operator_data_mapper.get();
+            OperatorData {
+                total_value_locked: operator_data.total_value_locked + &contract_data.total_value_locked,
+                nr_nodes: operator_data.nr_nodes + contract_data.nr_nodes,
+                allocation: operator_data.allocation + allocation,
+            }
+        };
+        operator_data_mapper.set(operator_data);
+    }
+
+    /// Removes the total value locked, the number of nodes and the allocation of a Delegation smart contract from the
+    /// data of a given operator
+    ///
+    fn remove_from_operator_data(&self, operator_id: u64, contract_data: &DelegationContractData<Self::Api>) {
+        if self.operator_contracts(operator_id).is_empty() {
+            self.operator_data(operator_id).clear();
+            return;
+        }
+
+        let allocation = self.get_allocation(contract_data.contract.clone());
+        self.operator_data(operator_id).update(|operator_data| {
+            operator_data.total_value_locked -= &contract_data.total_value_locked;
+            operator_data.nr_nodes -= contract_data.nr_nodes;
+            operator_data.allocation -= allocation;
+        });
+    }
+
+    /// Replaces the total value locked and the number of nodes of a Delegation smart contract at the data of its
+    /// operator, if any, once they have been updated
+    ///
+    fn update_operator_data(
+        &self,
+        old_contract_data: &DelegationContractData<Self::Api>,
+        new_contract_data: &DelegationContractData<Self::Api>,
+    ) {
+        let operator_mapper = self.operator_id(&new_contract_data.contract);
+        if operator_mapper.is_empty() {
+            return;
+        }
+
+        self.operator_data(operator_mapper.get()).update(|operator_data| {
+            operator_data.total_value_locked += &new_contract_data.total_value_locked;
+            operator_data.total_value_locked -= &old_contract_data.total_value_locked;
+            operator_data.nr_nodes = operator_data.nr_nodes + new_contract_data.nr_nodes - old_contract_data.nr_nodes;
+        });
+    }
+
+    /// Starts rescoring the Delegation smart contracts list from its first node, which is done in batches of at most
+    /// `MAX_SORT_BATCH_SIZE` Delegation smart contracts. The first batch is rescored right away.
+    ///
+    fn sort_delegation_contracts_list(&self) {
+        let first_node_id = self
+            .delegation_contracts_list()
+            .front()
+            .map(|node| node.get_node_id())
+            .unwrap_or_default();
+        self.sort_cursor().set(first_node_id);
+        self.continue_sorting_delegation_contracts_list(MAX_SORT_BATCH_SIZE);
+    }
+
+    /// Computes the delegation scores of at most `max_contracts` Delegation smart contracts from the sort cursor and
+    /// moves them to their new positions. Delegation smart contracts moved beyond the cursor are visited again, but
+    /// their delegation scores do not change anymore.
+    ///
+    fn continue_sorting_delegation_contracts_list(&self, max_contracts: usize) {
+        let delegation_contracts_mapper = self.delegation_contracts_list();
+        let sort_cursor_mapper = self.sort_cursor();
+        for _ in 0..max_contracts {
+            let opt_node = delegation_contracts_mapper.get_node_by_id(sort_cursor_mapper.get());
+            let node = match opt_node {
+                Some(node) => node,
+                None => break,
+            };
+
+            // moving the current node does not affect the next one
+            sort_cursor_mapper.set(node.get_next_node_id());
+            self.update_delegation_score_internal(&node.into_value());
+        }
+    }
+}
+
+/// Returns the position of the highest set bit of a non-zero word
+fn highest_bit(bits: u64) -> u64 {
+    (u64::BITS - 1 - bits.leading_zeros()) as u64
 }
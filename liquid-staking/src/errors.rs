@@ -1,4 +1,5 @@
-pub static ERROR_INACTIVE: &[u8] = b"Inactive state";
+pub static ERROR_OPERATION_PAUSED: &[u8] = b"Operation is paused";
+pub static ERROR_NOT_PAUSER_OR_GUARDIAN: &[u8] = b"Caller must be either a pauser or a guardian";
 pub static ERROR_INVALID_DELEGATION_CONTRACT: &[u8] = b"Invalid delegation contract";
 pub static ERROR_INVALID_UNBOND_PERIOD: &[u8] = b"Invalid unbond period";
 pub static ERROR_ONLY_DATA_MANAGER: &[u8] = b"Only data manager allowed";
@@ -36,8 +37,53 @@ pub static ERROR_UNEXPECTED_PENALTY_ID: &[u8] = b"Unexpected penalty id";
 pub static ERROR_WITHDRAWN_PENALTY: &[u8] = b"Penalty has been already withdrawn";
 pub static ERROR_NO_PENDING_TO_DELEGATE: &[u8] = b"There is no pending amount to delegate";
 pub static ERROR_NO_PENDING_TO_UNDELEGATE: &[u8] = b"There is no pending amount to undelegate";
-pub static ERROR_DELEGATION_CONTRACTS_LIST_FULL: &[u8] = b"Delegation contracts list is full";
+pub static ERROR_INVALID_DELEGATION_SCORE: &[u8] = b"Invalid delegation score";
 pub static ERROR_IN_MIGRATION_WHITELIST: &[u8] = b"Delegation contract is in migration whitelist";
 pub static ERROR_NOT_ENOUGH_ELAPSED_EPOCHS: &[u8] = b"Not enough elapsed epochs";
 pub static ERROR_DELEGATION_CONTRACT_NOT_IN_LIST: &[u8] = b"Delegation contract is not in the list";
 pub static ERROR_WOULD_LEAVE_DUST: &[u8] = b"Operation would leave dust at Delegation smart contract";
+pub static ERROR_SHARES_OUT_BELOW_MIN: &[u8] = b"sEGLD amount is below the minimum expected";
+pub static ERROR_EGLD_OUT_BELOW_MIN: &[u8] = b"EGLD amount is below the minimum expected";
+pub static ERROR_RECEIVER_NOT_SMART_CONTRACT: &[u8] = b"Receiver must be a smart contract";
+pub static ERROR_RECEIVER_CROSS_SHARD: &[u8] = b"Receiver must be in the same shard";
+pub static ERROR_INVALID_SPLIT_AMOUNTS: &[u8] = b"Split amounts must be positive and add up to the NFT EGLD amount";
+pub static ERROR_NOT_ENOUGH_NFTS_TO_MERGE: &[u8] = b"At least two undelegate NFTs are required";
+pub static ERROR_NFTS_CANNOT_BE_MERGED: &[u8] =
+    b"Undelegate NFTs must have the same Delegation contract and unbond epoch";
+pub static ERROR_UNDELEGATION_ALREADY_SENT: &[u8] = b"Undelegation has already been sent to the Delegation contract";
+pub static ERROR_REWARDS_VESTING_PERIOD_TOO_LONG: &[u8] = b"Rewards vesting period is too long";
+pub static ERROR_TIMELOCKED_CALL: &[u8] = b"Call must be queued through the timelock";
+pub static ERROR_TIMELOCK_DELAY_TOO_LONG: &[u8] = b"Timelock delay is too long";
+pub static ERROR_UNEXPECTED_TIMELOCK_CALL_ID: &[u8] = b"Unexpected timelock call id";
+pub static ERROR_TIMELOCK_NOT_ELAPSED: &[u8] = b"Timelock has not elapsed yet";
+pub static ERROR_INVALID_TIMELOCK_ARGUMENTS: &[u8] = b"Invalid timelock call arguments";
+pub static ERROR_INSUFFICIENT_LIQUIDITY_BUFFER: &[u8] = b"Insufficient liquidity buffer";
+pub static ERROR_LIQUIDITY_BUFFER_ON_TARGET: &[u8] = b"Liquidity buffer has reached its target";
+pub static ERROR_UNEXPECTED_BUFFER_REFILL_ID: &[u8] = b"Unexpected buffer refill id";
+pub static ERROR_ONLY_DATA_REPORTER: &[u8] = b"Caller is not a data reporter";
+pub static ERROR_DATA_REPORTER_ALREADY_REGISTERED: &[u8] = b"Data reporter has been already registered";
+pub static ERROR_UNEXPECTED_DATA_REPORTER: &[u8] = b"Unexpected data reporter";
+pub static ERROR_INVALID_DATA_REPORTERS_QUORUM: &[u8] = b"Invalid data reporters quorum";
+pub static ERROR_DATA_REPORTERS_QUORUM_UNSET: &[u8] = b"Data reporters quorum has not been set";
+pub static ERROR_DATA_REPORTERS_QUORUM_SET: &[u8] = b"Delegation contract data must be submitted by the data reporters";
+pub static ERROR_INVALID_NR_NODES: &[u8] = b"Invalid number of nodes";
+pub static ERROR_DATA_OUT_OF_BOUNDS: &[u8] = b"Delegation contract data is out of bounds";
+pub static ERROR_DELEGATION_CONTRACT_NOT_QUARANTINED: &[u8] = b"Delegation contract is not quarantined";
+pub static ERROR_UNEXPECTED_REBALANCE_ID: &[u8] = b"Unexpected rebalance id";
+pub static ERROR_INVALID_REBALANCE_CONTRACTS: &[u8] = b"Cannot rebalance a Delegation contract into itself";
+pub static ERROR_REBALANCE_ABOVE_TARGET: &[u8] = b"Rebalance amount exceeds the target allocations";
+pub static ERROR_REBALANCE_PARAMS_UNSET: &[u8] = b"Rebalance parameters have not been set";
+pub static ERROR_REBALANCE_BELOW_MIN_AMOUNT: &[u8] = b"Rebalance amount is below the minimum amount";
+pub static ERROR_REBALANCE_COOLDOWN: &[u8] = b"Delegation contract has been rebalanced too recently";
+pub static ERROR_CONCENTRATION_LIMIT_EXCEEDED: &[u8] = b"Delegation contract concentration limit exceeded";
+pub static ERROR_INVALID_OPERATOR_ID: &[u8] = b"Invalid operator id";
+pub static ERROR_UNEXPECTED_OPERATOR: &[u8] = b"Delegation contract has no operator";
+pub static ERROR_TOO_MANY_OPERATOR_CONTRACTS: &[u8] = b"Operator runs too many Delegation contracts";
+pub static ERROR_INVALID_DELEGATION_SCORE_TERMS: &[u8] = b"Invalid number of delegation score terms";
+pub static ERROR_INVALID_DELEGATION_SCORE_WEIGHTS: &[u8] = b"Delegation score weights must add up to BPS";
+pub static ERROR_MISSING_DELEGATION_SCORE_TERMS: &[u8] = b"Delegation score terms have not been set";
+pub static ERROR_MISSING_TVL_PER_NODE_DOMAIN: &[u8] = b"TVL per node domain has not been set";
+pub static ERROR_INVALID_SCORE_CURVE: &[u8] = b"Invalid delegation score curve";
+pub static ERROR_LIST_ALREADY_SORTED: &[u8] = b"Delegation contracts list is already sorted";
+pub static ERROR_INVALID_CONTRACT_CONFIG: &[u8] = b"Invalid Delegation contract config";
+pub static ERROR_NOTHING_TO_CLAIM_OR_DELEGATE: &[u8] = b"There are neither rewards to claim nor rewards to delegate";
@@ -1,7 +1,9 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 use super::model::{
-    DelegationContractData, DelegationScoreModel, Penalty, SamplingModel, UndelegateAttributes, UndelegationMode,
+    ConcentrationLimits, DataBounds, DataReport, DelegationContractData, DelegationScoreModel, DelegationScoreTerm,
+    InstantUndelegateFeeRecipient, Operation, Penalty, Rebalance, RebalanceParams, RewardsStream, SamplingModel,
+    ScoreDomain, TimelockCall, UndelegateAttributes, UndelegationMode,
 };
 
 #[multiversx_sc::module]
@@ -38,6 +40,14 @@ pub trait EventsModule {
     #[event("set_delegation_score_model_params_event")]
     fn set_delegation_score_model_params_event(&self, #[indexed] score_model: &DelegationScoreModel<Self::Api>);
 
+    /// Emitted when the terms of the weighted delegation score method are set or modified
+    #[event("set_delegation_score_terms_event")]
+    fn set_delegation_score_terms_event(&self, #[indexed] terms: &ManagedVec<DelegationScoreTerm<Self::Api>>);
+
+    /// Emitted when the domain of the TVL per node delegation score method is set or modified
+    #[event("set_tvl_per_node_domain_event")]
+    fn set_tvl_per_node_domain_event(&self, #[indexed] domain: &ScoreDomain<Self::Api>);
+
     /// Emitted when the delegation sampling model parameters are set or modified
     #[event("set_delegation_sampling_model_params_event")]
     fn set_delegation_sampling_model_params_event(&self, #[indexed] sampling_model: &SamplingModel<Self::Api>);
@@ -50,9 +60,9 @@ pub trait EventsModule {
     #[event("clear_delegation_sampling_model_event")]
     fn clear_delegation_sampling_model_event(&self);
 
-    /// Emitted when the liquid staking state modified
-    #[event("set_state_event")]
-    fn set_state_event(&self, #[indexed] active: bool);
+    /// Emitted when an operation is paused or unpaused
+    #[event("set_paused_event")]
+    fn set_paused_event(&self, #[indexed] operation: Operation, #[indexed] paused: bool);
 
     /// Emitted when a Delegation smart contract is whitelisted
     #[event("whitelist_delegation_contract_event")]
@@ -66,14 +76,16 @@ pub trait EventsModule {
     #[event("change_delegation_contract_params_event")]
     fn change_delegation_contract_params_event(&self, #[indexed] contract_data: &DelegationContractData<Self::Api>);
 
-    /// Emitted when a user delegates to Liquid Staking
+    /// Emitted when a user delegates to Liquid Staking, where the Delegation smart contract data is `None` if the
+    /// whole amount has been retained in the liquidity buffer
     #[event("delegate_event")]
     fn delegate_event(
         &self,
         #[indexed] account: &ManagedAddress,
+        #[indexed] receiver: &ManagedAddress,
         #[indexed] egld_amount: &BigUint,
         #[indexed] shares: &BigUint,
-        #[indexed] contract_data: &DelegationContractData<Self::Api>,
+        #[indexed] opt_contract_data: &Option<DelegationContractData<Self::Api>>,
     );
 
     /// Emitted when a pending amount is delegated to a Delegation smart contract
@@ -90,11 +102,42 @@ pub trait EventsModule {
     fn undelegate_event(
         &self,
         #[indexed] account: &ManagedAddress,
+        #[indexed] receiver: &ManagedAddress,
         #[indexed] undelegate_token_nonce: u64,
         #[indexed] undelegate_attrs: &UndelegateAttributes<Self::Api>,
         #[indexed] contract_data: &DelegationContractData<Self::Api>,
     );
 
+    /// Emitted when an undelegate NFT is split into many undelegate NFTs
+    #[event("split_undelegate_nft_event")]
+    fn split_undelegate_nft_event(
+        &self,
+        #[indexed] account: &ManagedAddress,
+        #[indexed] undelegate_token_nonce: u64,
+        #[indexed] new_undelegate_token_nonces: &ManagedVec<u64>,
+    );
+
+    /// Emitted when many undelegate NFTs are merged into a single undelegate NFT
+    #[event("merge_undelegate_nfts_event")]
+    fn merge_undelegate_nfts_event(
+        &self,
+        #[indexed] account: &ManagedAddress,
+        #[indexed] undelegate_token_nonces: &ManagedVec<u64>,
+        #[indexed] new_undelegate_token_nonce: u64,
+        #[indexed] undelegate_attrs: &UndelegateAttributes<Self::Api>,
+    );
+
+    /// Emitted when a user cancels an undelegation before it is sent to the Delegation smart contract
+    #[event("cancel_undelegate_event")]
+    fn cancel_undelegate_event(
+        &self,
+        #[indexed] account: &ManagedAddress,
+        #[indexed] undelegate_token_nonce: u64,
+        #[indexed] shares: &BigUint,
+        #[indexed] undelegate_attrs: &UndelegateAttributes<Self::Api>,
+        #[indexed] contract_data: &DelegationContractData<Self::Api>,
+    );
+
     /// Emitted when a pending amount is undelegated from a Delegation smart contract
     #[event("undelegate_pending_amount_event")]
     fn undelegate_pending_amount_event(
@@ -109,10 +152,21 @@ pub trait EventsModule {
     fn withdraw_event(
         &self,
         #[indexed] account: &ManagedAddress,
+        #[indexed] receiver: &ManagedAddress,
         #[indexed] undelegate_token_nonce: u64,
         #[indexed] contract_data: &DelegationContractData<Self::Api>,
     );
 
+    /// Emitted when a user withdraws EGLD from Liquid Staking, listing all the redeemed undelegate NFTs
+    #[event("batch_withdraw_event")]
+    fn batch_withdraw_event(
+        &self,
+        #[indexed] account: &ManagedAddress,
+        #[indexed] receiver: &ManagedAddress,
+        #[indexed] undelegate_token_nonces: &ManagedVec<u64>,
+        #[indexed] egld_amount: &BigUint,
+    );
+
     /// Emitted when an amount of EGLD in withdrawn from a Delegation smart contract
     #[event("withdraw_from_event")]
     fn withdraw_from_event(
@@ -215,4 +269,171 @@ pub trait EventsModule {
     /// Emitted when an async call fails and contract data is outdated
     #[event("outdated_event")]
     fn outdated_event(&self, #[indexed] contract: &ManagedAddress);
+
+    /// Emitted when the liquidity buffer target is set or modified
+    #[event("set_liquidity_buffer_target_event")]
+    fn set_liquidity_buffer_target_event(&self, #[indexed] target: &BigUint);
+
+    /// Emitted when the rewards vesting period is set or modified
+    #[event("set_rewards_vesting_period_event")]
+    fn set_rewards_vesting_period_event(&self, #[indexed] period: u64);
+
+    /// Emitted when delegated rewards are added to the rewards stream
+    #[event("rewards_stream_event")]
+    fn rewards_stream_event(&self, #[indexed] egld_amount: &BigUint, stream: &RewardsStream<Self::Api>);
+
+    /// Emitted when the instant undelegate fee is set or modified
+    #[event("set_instant_undelegate_fee_event")]
+    fn set_instant_undelegate_fee_event(
+        &self,
+        #[indexed] fee: &BigUint,
+        #[indexed] recipient: InstantUndelegateFeeRecipient,
+    );
+
+    /// Emitted when part of a delegation is kept at the liquidity buffer
+    #[event("retain_liquidity_buffer_event")]
+    fn retain_liquidity_buffer_event(
+        &self,
+        #[indexed] account: &ManagedAddress,
+        #[indexed] egld_amount: &BigUint,
+        #[indexed] liquidity_buffer: &BigUint,
+    );
+
+    /// Emitted when a user undelegates instantly from the liquidity buffer
+    #[event("instant_undelegate_event")]
+    fn instant_undelegate_event(
+        &self,
+        #[indexed] account: &ManagedAddress,
+        #[indexed] shares: &BigUint,
+        #[indexed] egld_amount: &BigUint,
+        #[indexed] fee: &BigUint,
+        #[indexed] liquidity_buffer: &BigUint,
+    );
+
+    /// Emitted when an undelegation is made to refill the liquidity buffer
+    #[event("refill_liquidity_buffer_event")]
+    fn refill_liquidity_buffer_event(
+        &self,
+        #[indexed] account: &ManagedAddress,
+        #[indexed] refill_id: u64,
+        #[indexed] attributes: &UndelegateAttributes<Self::Api>,
+        #[indexed] contract_data: &DelegationContractData<Self::Api>,
+    );
+
+    /// Emitted when a buffer refill is withdrawn into the liquidity buffer
+    #[event("withdraw_buffer_refill_event")]
+    fn withdraw_buffer_refill_event(
+        &self,
+        #[indexed] account: &ManagedAddress,
+        #[indexed] refill_id: u64,
+        #[indexed] liquidity_buffer: &BigUint,
+    );
+
+    /// Emitted when an undelegation is made to move EGLD between Delegation smart contracts
+    #[event("rebalance_event")]
+    fn rebalance_event(
+        &self,
+        #[indexed] account: &ManagedAddress,
+        #[indexed] rebalance: &Rebalance<Self::Api>,
+        #[indexed] contract_data: &DelegationContractData<Self::Api>,
+    );
+
+    /// Emitted when a rebalance is withdrawn and becomes pending to be delegated
+    #[event("complete_rebalance_event")]
+    fn complete_rebalance_event(
+        &self,
+        #[indexed] account: &ManagedAddress,
+        #[indexed] rebalance_id: u64,
+        #[indexed] contract_data: &DelegationContractData<Self::Api>,
+    );
+
+    /// Emitted when the rebalance parameters are set or modified
+    #[event("set_rebalance_params_event")]
+    fn set_rebalance_params_event(&self, #[indexed] params: &RebalanceParams<Self::Api>);
+
+    /// Emitted when the concentration limits are set or modified
+    #[event("set_concentration_limits_event")]
+    fn set_concentration_limits_event(&self, #[indexed] limits: &ConcentrationLimits);
+
+    /// Emitted when a Delegation smart contract is assigned to an operator
+    #[event("set_operator_event")]
+    fn set_operator_event(&self, #[indexed] delegation_contract: &ManagedAddress, #[indexed] operator_id: u64);
+
+    /// Emitted when a Delegation smart contract is removed from its operator
+    #[event("remove_operator_event")]
+    fn remove_operator_event(&self, #[indexed] delegation_contract: &ManagedAddress, #[indexed] operator_id: u64);
+
+    /// Emitted when the timelock delay is set or modified
+    #[event("set_timelock_delay_event")]
+    fn set_timelock_delay_event(&self, #[indexed] delay: u64);
+
+    /// Emitted when a governance call is queued through the timelock
+    #[event("queue_timelock_call_event")]
+    fn queue_timelock_call_event(&self, #[indexed] id: u64, call: &TimelockCall<Self::Api>);
+
+    /// Emitted when a queued governance call is executed
+    #[event("execute_timelock_call_event")]
+    fn execute_timelock_call_event(
+        &self,
+        #[indexed] id: u64,
+        #[indexed] caller: &ManagedAddress,
+        call: &TimelockCall<Self::Api>,
+    );
+
+    /// Emitted when a queued governance call is cancelled
+    #[event("cancel_timelock_call_event")]
+    fn cancel_timelock_call_event(
+        &self,
+        #[indexed] id: u64,
+        #[indexed] caller: &ManagedAddress,
+        call: &TimelockCall<Self::Api>,
+    );
+
+    /// Emitted when a data reporter is added
+    #[event("add_data_reporter_event")]
+    fn add_data_reporter_event(&self, #[indexed] reporter: &ManagedAddress);
+
+    /// Emitted when a data reporter is removed
+    #[event("remove_data_reporter_event")]
+    fn remove_data_reporter_event(&self, #[indexed] reporter: &ManagedAddress);
+
+    /// Emitted when the data reporters quorum is set or modified
+    #[event("set_data_reporters_quorum_event")]
+    fn set_data_reporters_quorum_event(&self, #[indexed] quorum: usize);
+
+    /// Emitted when the maximum deviation of a data report is set or modified
+    #[event("set_max_data_report_deviation_event")]
+    fn set_max_data_report_deviation_event(&self, #[indexed] max_deviation: u64);
+
+    /// Emitted when a data reporter submits the data of a Delegation smart contract
+    #[event("submit_data_report_event")]
+    fn submit_data_report_event(
+        &self,
+        #[indexed] reporter: &ManagedAddress,
+        #[indexed] delegation_contract: &ManagedAddress,
+        report: &DataReport<Self::Api>,
+    );
+
+    /// Emitted when the bounds on Delegation smart contract data updates are set or modified
+    #[event("set_data_bounds_event")]
+    fn set_data_bounds_event(&self, #[indexed] bounds: &DataBounds<Self::Api>);
+
+    /// Emitted when a Delegation smart contract data update breaks the bounds and the contract is quarantined
+    #[event("quarantine_delegation_contract_event")]
+    fn quarantine_delegation_contract_event(
+        &self,
+        #[indexed] contract_data: &DelegationContractData<Self::Api>,
+        #[indexed] total_value_locked: &BigUint,
+        #[indexed] nr_nodes: u64,
+        #[indexed] apr: &BigUint,
+        #[indexed] service_fee: &BigUint,
+    );
+
+    /// Emitted when the Delegation smart contract data is refreshed from the Delegation smart contract itself
+    #[event("refresh_delegation_contract_data_event")]
+    fn refresh_delegation_contract_data_event(&self, #[indexed] contract_data: &DelegationContractData<Self::Api>);
+
+    /// Emitted when the maximum contract data age is set or modified
+    #[event("set_max_contract_data_age_event")]
+    fn set_max_contract_data_age_event(&self, #[indexed] max_age: u64);
 }
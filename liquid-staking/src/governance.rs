@@ -1,5 +1,6 @@
 multiversx_sc::imports!();
 use super::{common, constants::*, delegation, errors::*, events, model::*, proxies, score, selection, storage};
+use admin::Role;
 
 #[multiversx_sc::module]
 pub trait GovernanceModule:
@@ -178,31 +179,350 @@ pub trait GovernanceModule:
     ///
     /// # Notes
     ///
-    /// - can only be called by the admin
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
     ///
     #[endpoint(setDataManager)]
     fn set_data_manager(&self, new_data_manager: ManagedAddress) {
-        self.require_admin();
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.set_data_manager_internal(new_data_manager);
+    }
+
+    fn set_data_manager_internal(&self, new_data_manager: ManagedAddress) {
         let old_data_manager = self.get_data_manager();
         self.data_manager().set(&new_data_manager);
         self.new_data_manager_event(&old_data_manager, &new_data_manager);
     }
 
-    /// Activates the Liquid Staking Module state. The activation can only occur iff:
+    /// Registers an account entitled to report the data associated to each Delegation smart contract.
+    ///
+    /// # Arguments
+    ///
+    /// - `reporter` - the address of the data reporter
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    ///
+    #[endpoint(addDataReporter)]
+    fn add_data_reporter(&self, reporter: ManagedAddress) {
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.add_data_reporter_internal(reporter);
+    }
+
+    fn add_data_reporter_internal(&self, reporter: ManagedAddress) {
+        require!(
+            self.data_reporters().insert(reporter.clone()),
+            ERROR_DATA_REPORTER_ALREADY_REGISTERED
+        );
+        self.add_data_reporter_event(&reporter);
+    }
+
+    /// Removes a data reporter. Its pending reports are no longer taken into account.
+    ///
+    /// # Arguments
+    ///
+    /// - `reporter` - the address of the data reporter
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    /// - the remaining data reporters must be enough to reach the quorum
+    ///
+    #[endpoint(removeDataReporter)]
+    fn remove_data_reporter(&self, reporter: ManagedAddress) {
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.remove_data_reporter_internal(reporter);
+    }
+
+    fn remove_data_reporter_internal(&self, reporter: ManagedAddress) {
+        let mut reporters = self.data_reporters();
+        require!(reporters.swap_remove(&reporter), ERROR_UNEXPECTED_DATA_REPORTER);
+        require!(
+            reporters.len() >= self.data_reporters_quorum().get(),
+            ERROR_INVALID_DATA_REPORTERS_QUORUM
+        );
+        self.remove_data_reporter_event(&reporter);
+    }
+
+    /// Sets the number of data reports required to update the data of a Delegation smart contract. Once set, the data
+    /// can no longer be updated by the data manager and is instead given by the median of the reports.
+    ///
+    /// # Arguments
+    ///
+    /// - `quorum` - the data reporters quorum. Zero gives the control back to the data manager
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    /// - cannot be greater than the number of data reporters
+    ///
+    #[endpoint(setDataReportersQuorum)]
+    fn set_data_reporters_quorum(&self, quorum: usize) {
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.set_data_reporters_quorum_internal(quorum);
+    }
+
+    fn set_data_reporters_quorum_internal(&self, quorum: usize) {
+        require!(
+            quorum <= self.data_reporters().len(),
+            ERROR_INVALID_DATA_REPORTERS_QUORUM
+        );
+        self.data_reporters_quorum().set(quorum);
+        self.set_data_reporters_quorum_event(quorum);
+    }
+
+    /// Sets the bounds on Delegation smart contract data updates, which prevent a compromised or faulty data source from
+    /// silently reordering the list of Delegation smart contracts.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_tvl_change` - the maximum relative change of the total value locked per update in bps, unbounded if zero
+    /// - `max_apr` - the maximum APR, unbounded if zero
+    /// - `max_service_fee` - the maximum service fee, unbounded if zero
+    /// - `max_nr_nodes_change` - the maximum change of the number of nodes per update, unbounded if zero
+    /// - `action` - whether updates breaking the bounds are rejected or quarantined
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    ///
+    #[endpoint(setDataBounds)]
+    fn set_data_bounds(
+        &self,
+        max_tvl_change: u64,
+        max_apr: BigUint,
+        max_service_fee: BigUint,
+        max_nr_nodes_change: u64,
+        action: DataBoundsAction,
+    ) {
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.set_data_bounds_internal(max_tvl_change, max_apr, max_service_fee, max_nr_nodes_change, action);
+    }
+
+    fn set_data_bounds_internal(
+        &self,
+        max_tvl_change: u64,
+        max_apr: BigUint,
+        max_service_fee: BigUint,
+        max_nr_nodes_change: u64,
+        action: DataBoundsAction,
+    ) {
+        let bounds = DataBounds {
+            max_tvl_change,
+            max_apr,
+            max_service_fee,
+            max_nr_nodes_change,
+            action,
+        };
+        self.data_bounds().set(&bounds);
+        self.set_data_bounds_event(&bounds);
+    }
+
+    /// Sets the number of epochs after which the data of a Delegation smart contract is considered stale. Delegation
+    /// smart contracts with stale data do not receive delegations until their data is updated.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_age` - the maximum contract data age in epochs. Zero means data never becomes stale
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    /// - refreshing the data through `refreshDelegationContractData` does not reset its age, since the APR is not
+    ///   refreshed
+    ///
+    #[endpoint(setMaxContractDataAge)]
+    fn set_max_contract_data_age(&self, max_age: u64) {
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.set_max_contract_data_age_internal(max_age);
+    }
+
+    fn set_max_contract_data_age_internal(&self, max_age: u64) {
+        self.max_contract_data_age().set(max_age);
+        self.set_max_contract_data_age_event(max_age);
+    }
+
+    /// Sets the maximum shares of the cash reserve that can be allocated to a single Delegation smart contract and to
+    /// the Delegation smart contracts of a single operator. Delegation smart contracts that would exceed any of them do
+    /// not receive delegations, and delegations that no Delegation smart contract can receive within the limits are
+    /// rejected.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_contract_share` - the maximum share per Delegation smart contract in bps, unlimited if zero
+    /// - `max_operator_share` - the maximum share per operator in bps, unlimited if zero
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    /// - Delegation smart contracts without an operator are only bound by the maximum share per Delegation smart
+    ///   contract
+    /// - the limits only apply once the cash reserve reaches `MIN_CASH_RESERVE_FOR_CONCENTRATION_LIMITS` EGLD
+    ///
+    #[endpoint(setConcentrationLimits)]
+    fn set_concentration_limits(&self, max_contract_share: u64, max_operator_share: u64) {
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.set_concentration_limits_internal(max_contract_share, max_operator_share);
+    }
+
+    fn set_concentration_limits_internal(&self, max_contract_share: u64, max_operator_share: u64) {
+        require!(
+            max_contract_share <= BPS && max_operator_share <= BPS,
+            ERROR_VALUE_EXCEEDS_BPS
+        );
+        let limits = ConcentrationLimits {
+            max_contract_share,
+            max_operator_share,
+        };
+        self.concentration_limits().set(limits);
+        self.set_concentration_limits_event(&limits);
+    }
+
+    /// Assigns a Delegation smart contract to an operator, replacing its previous operator if any.
+    ///
+    /// # Arguments
+    ///
+    /// - `delegation_contract` - the Delegation smart contract address
+    /// - `operator_id` - the operator identifier, which must be positive
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the WhitelistManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    /// - the Delegation smart contracts of an operator are scored using the aggregated total value locked of the
+    ///   operator, so the delegation scores of both the previous and the new operator are updated
+    /// - an operator can run at most `MAX_OPERATOR_CONTRACTS` Delegation smart contracts
+    ///
+    #[endpoint(setOperator)]
+    fn set_operator(&self, delegation_contract: ManagedAddress, operator_id: u64) {
+        self.require_role(Role::WhitelistManager);
+        self.require_not_timelocked();
+        self.set_operator_internal(delegation_contract, operator_id);
+    }
+
+    fn set_operator_internal(&self, delegation_contract: ManagedAddress, operator_id: u64) {
+        require!(operator_id > 0, ERROR_INVALID_OPERATOR_ID);
+        require!(
+            !self.delegation_contract_data(&delegation_contract).is_empty(),
+            ERROR_UNEXPECTED_DELEGATION_CONTRACT
+        );
+
+        let contract_data = self.delegation_contract_data(&delegation_contract).get();
+        let operator_mapper = self.operator_id(&delegation_contract);
+        if !operator_mapper.is_empty() {
+            let old_operator_id = operator_mapper.take();
+            self.operator_contracts(old_operator_id)
+                .swap_remove(&delegation_contract);
+            self.remove_from_operator_data(old_operator_id, &contract_data);
+            self.update_operator_delegation_scores(old_operator_id);
+        }
+
+        let mut operator_contracts_mapper = self.operator_contracts(operator_id);
+        operator_contracts_mapper.insert(delegation_contract.clone());
+        require!(
+            operator_contracts_mapper.len() <= MAX_OPERATOR_CONTRACTS,
+            ERROR_TOO_MANY_OPERATOR_CONTRACTS
+        );
+        operator_mapper.set(operator_id);
+        self.add_to_operator_data(operator_id, &contract_data);
+        self.update_operator_delegation_scores(operator_id);
+
+        self.set_operator_event(&delegation_contract, operator_id);
+    }
+
+    /// Removes a Delegation smart contract from its operator.
+    ///
+    /// # Arguments
+    ///
+    /// - `delegation_contract` - the Delegation smart contract address
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the WhitelistManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    ///
+    #[endpoint(removeOperator)]
+    fn remove_operator(&self, delegation_contract: ManagedAddress) {
+        self.require_role(Role::WhitelistManager);
+        self.require_not_timelocked();
+        self.remove_operator_internal(delegation_contract);
+    }
+
+    fn remove_operator_internal(&self, delegation_contract: ManagedAddress) {
+        let operator_mapper = self.operator_id(&delegation_contract);
+        require!(!operator_mapper.is_empty(), ERROR_UNEXPECTED_OPERATOR);
+
+        let operator_id = operator_mapper.take();
+        self.operator_contracts(operator_id).swap_remove(&delegation_contract);
+
+        let contract_data = self.delegation_contract_data(&delegation_contract).get();
+        self.remove_from_operator_data(operator_id, &contract_data);
+        self.update_operator_delegation_scores(operator_id);
+        if self.is_delegation_contract_in_list(&delegation_contract) {
+            self.update_delegation_score(&delegation_contract);
+        }
+
+        self.remove_operator_event(&delegation_contract, operator_id);
+    }
+
+    /// Sets the maximum deviation of a data report from the median of the reports submitted within the same epoch.
+    /// Reports with a total value locked, a number of nodes, an APR or a service fee that deviate more than this bound
+    /// are not taken into account when updating the Delegation smart contract data.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_deviation` - the maximum deviation in bps. Zero means reports are not bounded
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    ///
+    #[endpoint(setMaxDataReportDeviation)]
+    fn set_max_data_report_deviation(&self, max_deviation: u64) {
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.set_max_data_report_deviation_internal(max_deviation);
+    }
+
+    fn set_max_data_report_deviation_internal(&self, max_deviation: u64) {
+        self.max_data_report_deviation().set(max_deviation);
+        self.set_max_data_report_deviation_event(max_deviation);
+    }
+
+    /// Unpauses the given operations, or all of them if none is given. Unpausing can only occur iff:
     ///
     /// - the total fee has been set
     /// - the Liquid Staking token has been issued
     /// - the undelegate NFT has been issued
     /// - the delegation score model has been defined
-    /// - the data manager has been set
+    /// - either the data manager or the data reporters quorum has been set
+    ///
+    /// # Arguments
+    ///
+    /// - `operations` - the operations to unpause
     ///
     /// # Notes
     ///
-    /// - can only be called by the admin
+    /// - can only be called by an account with the Pauser role
     ///
-    #[endpoint(setStateActive)]
-    fn set_state_active(&self) {
-        self.require_admin();
+    #[endpoint(unpause)]
+    fn unpause(&self, operations: MultiValueEncoded<Operation>) {
+        self.require_role(Role::Pauser);
 
         // check total fee
         require!(!self.total_fee().is_empty(), ERROR_TOTAL_FEE_UNSET);
@@ -224,24 +544,57 @@ pub trait GovernanceModule:
             ERROR_DELEGATION_SCORE_MODEL_UNSET
         );
 
-        // check data manager is set
-        require!(!self.data_manager().is_empty(), ERROR_DATA_MANAGER_UNSET);
+        // check data manager or data reporters are set
+        require!(
+            !self.data_manager().is_empty() || self.data_reporters_quorum().get() > 0,
+            ERROR_DATA_MANAGER_UNSET
+        );
 
-        self.state().set(State::Active);
-        self.set_state_event(true);
+        self.set_paused_internal(operations, false);
     }
 
-    /// Deactivates the Liquid Staking Module state.
+    /// Pauses the given operations, or all of them if none is given. For example, delegations can be paused during an
+    /// incident while undelegations and withdrawals remain open.
+    ///
+    /// # Arguments
+    ///
+    /// - `operations` - the operations to pause
     ///
     /// # Notes
     ///
-    /// - can only be called by the admin
+    /// - can only be called by an account with either the Pauser or the Guardian role
     ///
-    #[endpoint(setStateInactive)]
-    fn set_state_inactive(&self) {
-        self.require_admin();
-        self.state().set(State::Inactive);
-        self.set_state_event(false);
+    #[endpoint(pause)]
+    fn pause(&self, operations: MultiValueEncoded<Operation>) {
+        let caller = self.blockchain().get_caller();
+        require!(
+            self.has_role(Role::Pauser, &caller) || self.has_role(Role::Guardian, &caller),
+            ERROR_NOT_PAUSER_OR_GUARDIAN
+        );
+        self.set_paused_internal(operations, true);
+    }
+
+    fn set_paused_internal(&self, operations: MultiValueEncoded<Operation>, paused: bool) {
+        if operations.is_empty() {
+            for operation in Operation::ALL {
+                self.set_paused_operation(operation, paused);
+            }
+        } else {
+            for operation in operations {
+                self.set_paused_operation(operation, paused);
+            }
+        }
+    }
+
+    fn set_paused_operation(&self, operation: Operation, paused: bool) {
+        self.paused_operations().update(|paused_operations| {
+            if paused {
+                *paused_operations |= operation.flag();
+            } else {
+                *paused_operations &= !operation.flag();
+            }
+        });
+        self.set_paused_event(operation, paused);
     }
 
     /// Whitelists a Staking Provider Delegation smart contract. From this point onwards, this smart contract will be
@@ -260,7 +613,8 @@ pub trait GovernanceModule:
     ///
     /// # Notes
     ///
-    /// - can only be called by the admin
+    /// - can only be called by an account with the WhitelistManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
     /// - it will compute a delegation score based on the current state of the delegation algorithm
     ///
     #[endpoint(whitelistDelegationContract)]
@@ -273,11 +627,28 @@ pub trait GovernanceModule:
         service_fee: BigUint,
         opt_cap: OptionalValue<BigUint>,
     ) {
-        self.require_admin();
-        self.require_delegation_contract(&delegation_contract);
+        self.require_role(Role::WhitelistManager);
+        self.require_not_timelocked();
+        self.whitelist_delegation_contract_internal(
+            delegation_contract,
+            total_value_locked,
+            nr_nodes,
+            apr,
+            service_fee,
+            opt_cap,
+        );
+    }
 
-        // compute delegation score
-        let delegation_score = self.compute_delegation_score_internal(&total_value_locked, &apr);
+    fn whitelist_delegation_contract_internal(
+        &self,
+        delegation_contract: ManagedAddress,
+        total_value_locked: BigUint,
+        nr_nodes: u64,
+        apr: BigUint,
+        service_fee: BigUint,
+        opt_cap: OptionalValue<BigUint>,
+    ) {
+        self.require_delegation_contract(&delegation_contract);
 
         // if previously blacklisted, whitelist
         self.blacklisted_delegation_contracts()
@@ -289,14 +660,14 @@ pub trait GovernanceModule:
         }
 
         let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
-        let contract_data = if contract_data_mapper.is_empty() {
+        let mut contract_data = if contract_data_mapper.is_empty() {
             DelegationContractData {
                 contract: delegation_contract.clone(),
                 total_value_locked,
                 cap,
                 nr_nodes,
                 apr,
-                delegation_score: delegation_score.clone(),
+                delegation_score: BigUint::zero(),
                 service_fee,
                 pending_to_delegate: BigUint::zero(),
                 total_delegated: BigUint::zero(),
@@ -309,25 +680,42 @@ pub trait GovernanceModule:
         } else {
             // we only manage the case where the contract has been blacklisted if the contract data already exist.
             // notice there is no need to remove it from the list, since it has been already removed when blacklisting.
-            let contract_data = contract_data_mapper.get();
-            require!(contract_data.blacklisted, ERROR_NOT_BLACKLISTED_DELEGATION_CONTRACT);
+            let old_contract_data = contract_data_mapper.get();
+            require!(old_contract_data.blacklisted, ERROR_NOT_BLACKLISTED_DELEGATION_CONTRACT);
 
-            DelegationContractData {
+            let contract_data = DelegationContractData {
                 total_value_locked,
                 cap,
                 nr_nodes,
                 apr,
-                delegation_score: delegation_score.clone(),
                 service_fee,
                 outdated: false,
                 blacklisted: false,
-                ..contract_data
-            }
+                ..old_contract_data.clone()
+            };
+
+            // a blacklisted Delegation smart contract keeps its operator
+            self.update_operator_data(&old_contract_data, &contract_data);
+            contract_data
         };
+
+        // compute delegation score
+        let delegation_score = self.compute_delegation_score(&contract_data);
+        contract_data.delegation_score = delegation_score.clone();
         contract_data_mapper.set(&contract_data);
 
+        // blacklisted Delegation smart contracts are not indexed
+        self.update_capacity_indexes(None, &contract_data);
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        self.set_contract_data_update_epoch_internal(&delegation_contract, current_epoch);
+
         self.add_and_order_delegation_contract_in_list(&delegation_contract, &delegation_score);
 
+        // the data of its operator might have changed as well
+        self.update_delegation_score(&delegation_contract);
+
+        let contract_data = contract_data_mapper.get();
         self.whitelist_delegation_contract_event(&contract_data);
     }
 
@@ -338,10 +726,19 @@ pub trait GovernanceModule:
     ///
     /// - `delegation_contract` - the Delegation smart contract address
     ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the WhitelistManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    ///
     #[endpoint(blacklistDelegationContract)]
     fn blacklist_delegation_contract(&self, delegation_contract: ManagedAddress) {
-        self.require_admin();
+        self.require_role(Role::WhitelistManager);
+        self.require_not_timelocked();
+        self.blacklist_delegation_contract_internal(delegation_contract);
+    }
 
+    fn blacklist_delegation_contract_internal(&self, delegation_contract: ManagedAddress) {
         let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
         require!(!contract_data_mapper.is_empty(), ERROR_UNEXPECTED_DELEGATION_CONTRACT);
 
@@ -360,10 +757,11 @@ pub trait GovernanceModule:
 
         self.remove_delegation_contract_from_list(&delegation_contract);
 
-        contract_data_mapper.update(|data| {
+        self.update_delegation_contract_data(&delegation_contract, |data| {
             data.outdated = true;
             data.blacklisted = true;
         });
+        self.quarantined_data(&delegation_contract).clear();
 
         let contract_data = contract_data_mapper.get();
         self.blacklist_delegation_contract_event(&contract_data);
@@ -384,7 +782,11 @@ pub trait GovernanceModule:
     /// # Notes
     ///
     /// - can only be called by the admin set for the Delegation smart contract data
+    /// - will revert if the data reporters quorum has been set, i.e. data must be submitted by the data reporters
     /// - will revert if the contract has been blacklisted
+    /// - if the update breaks the data bounds, it will either revert or quarantine the Delegation smart contract, i.e.
+    ///   store the update aside and mark the contract as outdated until a later update is within bounds or the
+    ///   quarantined update is released through `releaseQuarantine`
     /// - it will compute a delegation score based on the current state of the delegation algorithm
     ///
     #[endpoint(changeDelegationContractParams)]
@@ -398,21 +800,94 @@ pub trait GovernanceModule:
         opt_cap: OptionalValue<BigUint>,
     ) {
         self.require_data_manager();
+        require!(self.data_reporters_quorum().get() == 0, ERROR_DATA_REPORTERS_QUORUM_SET);
 
-        let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
+        let cap = opt_cap.into_option();
+        self.change_delegation_contract_params_internal(
+            &delegation_contract,
+            total_value_locked,
+            nr_nodes,
+            apr,
+            service_fee,
+            cap,
+        );
+    }
+
+    fn change_delegation_contract_params_internal(
+        &self,
+        delegation_contract: &ManagedAddress,
+        total_value_locked: BigUint,
+        nr_nodes: u64,
+        apr: BigUint,
+        service_fee: BigUint,
+        cap: Option<BigUint>,
+    ) {
+        let contract_data_mapper = self.delegation_contract_data(delegation_contract);
         require!(!contract_data_mapper.is_empty(), ERROR_UNEXPECTED_DELEGATION_CONTRACT);
 
         let old_contract_data = contract_data_mapper.get();
         require!(!old_contract_data.blacklisted, ERROR_BLACKLISTED_DELEGATION_CONTRACT);
 
-        let cap = opt_cap.into_option();
         if cap.is_some() {
             require!(cap.as_ref().unwrap() >= &total_value_locked, ERROR_DELEGATION_CAP);
         }
 
-        let old_delegation_score = old_contract_data.delegation_score;
+        if !self.is_within_data_bounds(&old_contract_data, &total_value_locked, nr_nodes, &apr, &service_fee) {
+            require!(
+                self.data_bounds().get().action == DataBoundsAction::Quarantine,
+                ERROR_DATA_OUT_OF_BOUNDS
+            );
+
+            self.update_delegation_contract_data(delegation_contract, |data| data.outdated = true);
 
-        contract_data_mapper.update(|data| {
+            let contract_data = contract_data_mapper.get();
+            self.quarantine_delegation_contract_event(
+                &contract_data,
+                &total_value_locked,
+                nr_nodes,
+                &apr,
+                &service_fee,
+            );
+
+            // a later quarantined update replaces the previous one
+            self.quarantined_data(delegation_contract).set(DataReport {
+                epoch: self.blockchain().get_block_epoch(),
+                total_value_locked,
+                nr_nodes,
+                apr,
+                service_fee,
+                cap,
+            });
+            return;
+        }
+
+        self.quarantined_data(delegation_contract).clear();
+        self.apply_delegation_contract_params(
+            delegation_contract,
+            &old_contract_data,
+            total_value_locked,
+            nr_nodes,
+            apr,
+            service_fee,
+            cap,
+        );
+    }
+
+    /// Stores the given data for a Delegation smart contract, lifting its outdated flag, and updates its delegation
+    /// score
+    ///
+    fn apply_delegation_contract_params(
+        &self,
+        delegation_contract: &ManagedAddress,
+        old_contract_data: &DelegationContractData<Self::Api>,
+        total_value_locked: BigUint,
+        nr_nodes: u64,
+        apr: BigUint,
+        service_fee: BigUint,
+        cap: Option<BigUint>,
+    ) {
+        let contract_data_mapper = self.delegation_contract_data(delegation_contract);
+        self.update_delegation_contract_data(delegation_contract, |data| {
             data.total_value_locked = total_value_locked;
             data.nr_nodes = nr_nodes;
             data.apr = apr;
@@ -420,28 +895,91 @@ pub trait GovernanceModule:
             data.cap = cap;
             data.outdated = false;
         });
+        self.update_operator_data(old_contract_data, &contract_data_mapper.get());
 
-        // get updated data and compute new score
-        let new_contract_data = contract_data_mapper.get();
-        let new_delegation_score = self.compute_delegation_score(&new_contract_data);
-
-        // Check if delegation_score has changed
-        if old_delegation_score != new_delegation_score {
-            contract_data_mapper.update(|data| {
-                data.delegation_score = new_delegation_score.clone();
-            });
-
-            self.remove_delegation_contract_from_list(&delegation_contract);
-            self.add_and_order_delegation_contract_in_list(&delegation_contract, &new_delegation_score);
-        }
+        // compute new score and reorder the list if needed
+        self.update_delegation_score(delegation_contract);
 
         let current_epoch = self.blockchain().get_block_epoch();
-        self.set_last_contract_data_update_epoch_internal(current_epoch);
+        self.set_contract_data_update_epoch_internal(delegation_contract, current_epoch);
 
         let contract_data = contract_data_mapper.get();
         self.change_delegation_contract_params_event(&contract_data);
     }
 
+    /// Checks whether a Delegation smart contract data update is within the data bounds, if any
+    ///
+    fn is_within_data_bounds(
+        &self,
+        contract_data: &DelegationContractData<Self::Api>,
+        total_value_locked: &BigUint,
+        nr_nodes: u64,
+        apr: &BigUint,
+        service_fee: &BigUint,
+    ) -> bool {
+        let bounds_mapper = self.data_bounds();
+        if bounds_mapper.is_empty() {
+            return true;
+        }
+
+        let bounds = bounds_mapper.get();
+        let zero = BigUint::zero();
+        self.is_within_deviation(
+            &contract_data.total_value_locked,
+            total_value_locked,
+            bounds.max_tvl_change,
+        ) && (bounds.max_apr == zero || apr <= &bounds.max_apr)
+            && (bounds.max_service_fee == zero || service_fee <= &bounds.max_service_fee)
+            && (bounds.max_nr_nodes_change == 0
+                || nr_nodes.abs_diff(contract_data.nr_nodes) <= bounds.max_nr_nodes_change)
+    }
+
+    /// Applies the data update that quarantined a Delegation smart contract, confirming it regardless of the data
+    /// bounds.
+    ///
+    /// # Arguments
+    ///
+    /// - `delegation_contract` - the Delegation smart contract address
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    /// - will revert if the contract is not quarantined
+    /// - it will compute a delegation score based on the current state of the delegation algorithm
+    ///
+    #[endpoint(releaseQuarantine)]
+    fn release_quarantine(&self, delegation_contract: ManagedAddress) {
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.release_quarantine_internal(delegation_contract);
+    }
+
+    fn release_quarantine_internal(&self, delegation_contract: ManagedAddress) {
+        let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
+        require!(!contract_data_mapper.is_empty(), ERROR_UNEXPECTED_DELEGATION_CONTRACT);
+
+        let old_contract_data = contract_data_mapper.get();
+        require!(!old_contract_data.blacklisted, ERROR_BLACKLISTED_DELEGATION_CONTRACT);
+
+        let quarantined_data_mapper = self.quarantined_data(&delegation_contract);
+        require!(
+            !quarantined_data_mapper.is_empty(),
+            ERROR_DELEGATION_CONTRACT_NOT_QUARANTINED
+        );
+
+        let data = quarantined_data_mapper.take();
+        self.apply_delegation_contract_params(
+            &delegation_contract,
+            &old_contract_data,
+            data.total_value_locked,
+            data.nr_nodes,
+            data.apr,
+            data.service_fee,
+            data.cap,
+        );
+    }
+
     /// Withdraws a given amount of EGLD from the protocol reserves to an optionally given account.
     ///
     /// # Arguments
@@ -451,13 +989,18 @@ pub trait GovernanceModule:
     ///
     /// # Notes
     ///
-    /// - can only be called by the admin
+    /// - can only be called by an account with the ReserveTreasurer role
+    /// - must be queued through the timelock while the timelock delay is not zero
     /// - the EGLD amount is directed to the admin account if none is provided
     ///
     #[endpoint(withdrawReserve)]
     fn withdraw_reserve(&self, egld_amount: BigUint, opt_to: OptionalValue<ManagedAddress>) {
-        self.require_admin();
+        self.require_role(Role::ReserveTreasurer);
+        self.require_not_timelocked();
+        self.withdraw_reserve_internal(egld_amount, opt_to);
+    }
 
+    fn withdraw_reserve_internal(&self, egld_amount: BigUint, opt_to: OptionalValue<ManagedAddress>) {
         require!(
             egld_amount <= self.protocol_reserve().get(),
             ERROR_NOT_ENOUGH_PROTOCOL_RESERVES
@@ -468,13 +1011,13 @@ pub trait GovernanceModule:
         // get beneficiary
         let caller = self.blockchain().get_caller();
         let to = match opt_to {
-            OptionalValue::None => &caller,
-            OptionalValue::Some(ref to) => to,
+            OptionalValue::None => self.get_admin(),
+            OptionalValue::Some(to) => to,
         };
 
-        self.send().direct_egld(to, &egld_amount);
+        self.send().direct_egld(&to, &egld_amount);
 
-        self.withdraw_reserve_event(&caller, &egld_amount, to);
+        self.withdraw_reserve_event(&caller, &egld_amount, &to);
     }
 
     /// Sets the total fee, which represents the final fee end users see discounted from their rewards based on the
@@ -488,24 +1031,114 @@ pub trait GovernanceModule:
     ///
     /// # Notes
     ///
-    /// - can only be called by the admin
+    /// - can only be called by an account with the FeeManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
     ///
     #[endpoint(setTotalFee)]
     fn set_total_fee(&self, fee: &BigUint) {
-        self.require_admin();
+        self.require_role(Role::FeeManager);
+        self.require_not_timelocked();
+        self.set_total_fee_internal(fee);
+    }
+
+    fn set_total_fee_internal(&self, fee: &BigUint) {
         require!(*fee > BigUint::zero(), ERROR_VALUE_CANNOT_BE_ZERO);
         require!(*fee <= BPS, ERROR_VALUE_EXCEEDS_BPS);
         self.total_fee().set(fee);
         self.set_total_fee_event(fee);
     }
 
+    /// Sets the target size of the liquidity buffer as a fraction of the cash reserve. Delegations keep part of their
+    /// EGLD at the liquidity buffer until this target is reached, which is then used to serve instant undelegations.
+    ///
+    /// # Arguments
+    ///
+    /// - `target` - the liquidity buffer target in basis points. Setting it to zero disables the liquidity buffer for
+    ///   new delegations.
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the FeeManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    ///
+    #[endpoint(setLiquidityBufferTarget)]
+    fn set_liquidity_buffer_target(&self, target: BigUint) {
+        self.require_role(Role::FeeManager);
+        self.require_not_timelocked();
+        self.set_liquidity_buffer_target_internal(&target);
+    }
+
+    fn set_liquidity_buffer_target_internal(&self, target: &BigUint) {
+        require!(*target <= BPS, ERROR_VALUE_EXCEEDS_BPS);
+        self.liquidity_buffer_target().set(target);
+        self.set_liquidity_buffer_target_event(target);
+    }
+
+    /// Sets the number of rounds over which delegated rewards are vested into the exchange rate. Vesting rewards
+    /// linearly prevents users from capturing rewards they did not earn by delegating right before rewards are
+    /// delegated and undelegating right after.
+    ///
+    /// # Arguments
+    ///
+    /// - `period` - the rewards vesting period in rounds. Setting it to zero includes delegated rewards in the
+    ///   exchange rate at once.
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the FeeManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    /// - rewards that are already being vested keep their current schedule
+    ///
+    #[endpoint(setRewardsVestingPeriod)]
+    fn set_rewards_vesting_period(&self, period: u64) {
+        self.require_role(Role::FeeManager);
+        self.require_not_timelocked();
+        self.set_rewards_vesting_period_internal(period);
+    }
+
+    fn set_rewards_vesting_period_internal(&self, period: u64) {
+        require!(
+            period <= MAX_REWARDS_VESTING_PERIOD,
+            ERROR_REWARDS_VESTING_PERIOD_TOO_LONG
+        );
+        self.rewards_vesting_period().set(period);
+        self.set_rewards_vesting_period_event(period);
+    }
+
+    /// Sets the fee charged on instant undelegations and who receives it.
+    ///
+    /// # Arguments
+    ///
+    /// - `fee` - the instant undelegate fee in basis points
+    /// - `recipient` - either the protocol reserve or the buffer providers, i.e. all sEGLD holders
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the FeeManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    ///
+    #[endpoint(setInstantUndelegateFee)]
+    fn set_instant_undelegate_fee(&self, fee: BigUint, recipient: InstantUndelegateFeeRecipient) {
+        self.require_role(Role::FeeManager);
+        self.require_not_timelocked();
+        self.set_instant_undelegate_fee_internal(&fee, recipient);
+    }
+
+    fn set_instant_undelegate_fee_internal(&self, fee: &BigUint, recipient: InstantUndelegateFeeRecipient) {
+        require!(*fee <= BPS, ERROR_VALUE_EXCEEDS_BPS);
+        self.instant_undelegate_fee().set(fee);
+        self.instant_undelegate_fee_recipient().set(recipient);
+        self.set_instant_undelegate_fee_event(fee, recipient);
+    }
+
     /// Sets the Delegation Score Model parameters used for the computation of the delegation score for each Staking
     /// Provider Delegation smart contract. Higher scores imply better chances of being selected at delegations as well
     /// as lower chances of being selected for undelegations.
     ///
     /// # Arguments
     ///
-    /// - `method` - the score can be based only on Total Value Locked, APR or a weighted mix of both parameters
+    /// - `method` - the score can be based only on Total Value Locked, APR, a weighted mix of both parameters, Total
+    ///   Value Locked per node, service fee or a weighted combination of terms set through `setDelegationScoreTerms`
     /// - `min_tvl` - Delegation smart contracts with lower TVLs than this parameters share the same TVL score
     /// - `max_tvl` - Delegation smart contracts with higher TVLs than this parameters share the same TVL score
     /// - `min_apr` - Delegation smart contracts with lower APRs than this parameters share the same APR score (in bps)
@@ -513,11 +1146,19 @@ pub trait GovernanceModule:
     /// - `opt_omega` - should be given only for a Mixed delegation score method and defines the weight for both TVL and
     ///   APR scores
     /// - `sort` - if true, the list of Delegation smart contracts will be sorted based on the new delegation score
-    ///   model parameters. If false, the sorting is left to `changeDelegationContractParams`.
+    ///   model parameters, starting with a first batch of `MAX_SORT_BATCH_SIZE` Delegation smart contracts and
+    ///   continued through `sortDelegationContractsList`. If false, the sorting is left to
+    ///   `changeDelegationContractParams`.
     ///
     /// # Notes
     ///
-    /// - can only be called by the admin
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    /// - the TvlPerNode method scores the TVL per node within the domain set through `setTvlPerNodeDomain`, which must
+    ///   have been set beforehand
+    /// - the ServiceFee method scores service fees linearly between zero and BPS, with lower fees yielding higher
+    ///   scores
+    /// - the current score curve is kept, or the Linear one is used if none has been set
     ///
     #[endpoint(setDelegationScoreModelParams)]
     fn set_delegation_score_model_params(
@@ -530,8 +1171,22 @@ pub trait GovernanceModule:
         sort: bool,
         opt_omega: OptionalValue<BigUint>,
     ) {
-        self.require_admin();
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.set_delegation_score_model_params_internal(method, min_tvl, max_tvl, min_apr, max_apr, sort, opt_omega);
+    }
 
+    #[allow(clippy::too_many_arguments)]
+    fn set_delegation_score_model_params_internal(
+        &self,
+        method: DelegationScoreMethod,
+        min_tvl: BigUint,
+        max_tvl: BigUint,
+        min_apr: BigUint,
+        max_apr: BigUint,
+        sort: bool,
+        opt_omega: OptionalValue<BigUint>,
+    ) {
         let omega = match method {
             DelegationScoreMethod::Tvl => {
                 require!(max_tvl > min_tvl, ERROR_INVALID_DOMAIN);
@@ -557,6 +1212,33 @@ pub trait GovernanceModule:
                     },
                 }
             },
+            DelegationScoreMethod::TvlPerNode => {
+                require!(
+                    !self.tvl_per_node_domain().is_empty(),
+                    ERROR_MISSING_TVL_PER_NODE_DOMAIN
+                );
+                require!(opt_omega.is_none(), ERROR_UNEXPECTED_VALUE);
+                BigUint::zero()
+            },
+            DelegationScoreMethod::ServiceFee => {
+                require!(opt_omega.is_none(), ERROR_UNEXPECTED_VALUE);
+                BigUint::zero()
+            },
+            DelegationScoreMethod::Weighted => {
+                require!(
+                    !self.delegation_score_terms().is_empty(),
+                    ERROR_MISSING_DELEGATION_SCORE_TERMS
+                );
+                require!(opt_omega.is_none(), ERROR_UNEXPECTED_VALUE);
+                BigUint::zero()
+            },
+        };
+
+        let model_mapper = self.delegation_score_model();
+        let curve = if model_mapper.is_empty() {
+            ScoreCurve::Linear
+        } else {
+            model_mapper.get().curve
         };
 
         let model = DelegationScoreModel {
@@ -566,8 +1248,48 @@ pub trait GovernanceModule:
             min_apr,
             max_apr,
             omega,
+            curve,
         };
-        self.delegation_score_model().set(&model);
+        model_mapper.set(&model);
+
+        if sort {
+            self.sort_delegation_contracts_list();
+        }
+
+        self.set_delegation_score_model_params_event(&model);
+    }
+
+    /// Sets the curve applied to every normalized parameter of the Delegation Score Model, which allows tuning how fast
+    /// the delegation score changes across the domain of each parameter.
+    ///
+    /// # Arguments
+    ///
+    /// - `curve` - the score curve
+    /// - `sort` - if true, the list of Delegation smart contracts will be sorted based on the new curve, as done by
+    ///   `setDelegationScoreModelParams`. If false, the sorting is left to `changeDelegationContractParams`.
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    /// - the Delegation Score Model parameters must have been set through `setDelegationScoreModelParams`
+    ///
+    #[endpoint(setDelegationScoreCurve)]
+    fn set_delegation_score_curve(&self, curve: ScoreCurve<Self::Api>, sort: bool) {
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.set_delegation_score_curve_internal(curve, sort);
+    }
+
+    fn set_delegation_score_curve_internal(&self, curve: ScoreCurve<Self::Api>, sort: bool) {
+        self.require_valid_score_curve(&curve);
+
+        let model_mapper = self.delegation_score_model();
+        require!(!model_mapper.is_empty(), ERROR_DELEGATION_SCORE_MODEL_UNSET);
+
+        let mut model = model_mapper.get();
+        model.curve = curve;
+        model_mapper.set(&model);
 
         if sort {
             self.sort_delegation_contracts_list();
@@ -576,6 +1298,157 @@ pub trait GovernanceModule:
         self.set_delegation_score_model_params_event(&model);
     }
 
+    /// Checks that a delegation score curve is non-decreasing and bounded between zero and one (in bps)
+    ///
+    fn require_valid_score_curve(&self, curve: &ScoreCurve<Self::Api>) {
+        match curve {
+            ScoreCurve::Linear => {},
+            ScoreCurve::PiecewiseLinear { breakpoints } => {
+                require!(
+                    !breakpoints.is_empty() && breakpoints.len() <= MAX_SCORE_CURVE_BREAKPOINTS,
+                    ERROR_INVALID_SCORE_CURVE
+                );
+
+                let mut previous = ScoreCurvePoint { x: 0, y: 0 };
+                for point in breakpoints.iter() {
+                    require!(
+                        point.x > previous.x && point.x < BPS && point.y >= previous.y && point.y <= BPS,
+                        ERROR_INVALID_SCORE_CURVE
+                    );
+                    previous = point;
+                }
+            },
+            ScoreCurve::Logarithmic { curvature } => {
+                require!(*curvature > 0, ERROR_INVALID_SCORE_CURVE);
+            },
+            ScoreCurve::Sigmoid { midpoint, curvature } => {
+                require!(
+                    *midpoint > 0 && *midpoint < BPS && *curvature > 0,
+                    ERROR_INVALID_SCORE_CURVE
+                );
+            },
+        }
+    }
+
+    /// Sets the terms of the weighted delegation score method. Each term normalizes a factor of the Delegation smart
+    /// contract data to a score between zero and BPS, and the delegation score is the weighted sum of these scores.
+    ///
+    /// # Arguments
+    ///
+    /// - `terms` - the delegation score terms, each given by its factor, weight, domain and direction. The weights
+    ///   must add up to BPS.
+    /// - `sort` - if true and the delegation score method is Weighted, the list of Delegation smart contracts will be
+    ///   sorted based on the new terms, as done by `setDelegationScoreModelParams`. If false, the sorting is left to
+    ///   `changeDelegationContractParams`.
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    /// - the terms only apply once the delegation score method is set to Weighted through
+    ///   `setDelegationScoreModelParams`
+    ///
+    #[endpoint(setDelegationScoreTerms)]
+    fn set_delegation_score_terms(&self, terms: ManagedVec<DelegationScoreTerm<Self::Api>>, sort: bool) {
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.set_delegation_score_terms_internal(terms, sort);
+    }
+
+    fn set_delegation_score_terms_internal(&self, terms: ManagedVec<DelegationScoreTerm<Self::Api>>, sort: bool) {
+        require!(
+            !terms.is_empty() && terms.len() <= MAX_DELEGATION_SCORE_TERMS,
+            ERROR_INVALID_DELEGATION_SCORE_TERMS
+        );
+
+        let mut sum_weights = 0u64;
+        for term in terms.iter() {
+            require!(term.max > term.min, ERROR_INVALID_DOMAIN);
+            require!(term.weight <= BPS, ERROR_VALUE_EXCEEDS_BPS);
+            sum_weights += term.weight;
+        }
+        require!(sum_weights == BPS, ERROR_INVALID_DELEGATION_SCORE_WEIGHTS);
+
+        self.delegation_score_terms().set(&terms);
+
+        let model_mapper = self.delegation_score_model();
+        if sort && !model_mapper.is_empty() && model_mapper.get().method == DelegationScoreMethod::Weighted {
+            self.sort_delegation_contracts_list();
+        }
+
+        self.set_delegation_score_terms_event(&terms);
+    }
+
+    /// Sets the domain of the TVL per node delegation score method, which is kept apart from the TVL domain since TVLs
+    /// per node are much lower than TVLs.
+    ///
+    /// # Arguments
+    ///
+    /// - `min` - Delegation smart contracts with lower TVLs per node than this parameter share the same score
+    /// - `max` - Delegation smart contracts with higher TVLs per node than this parameter share the same score
+    /// - `sort` - if true and the delegation score method is TvlPerNode, the list of Delegation smart contracts will be
+    ///   sorted based on the new domain, as done by `setDelegationScoreModelParams`. If false, the sorting is left to
+    ///   `changeDelegationContractParams`.
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    ///
+    #[endpoint(setTvlPerNodeDomain)]
+    fn set_tvl_per_node_domain(&self, min: BigUint, max: BigUint, sort: bool) {
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.set_tvl_per_node_domain_internal(min, max, sort);
+    }
+
+    fn set_tvl_per_node_domain_internal(&self, min: BigUint, max: BigUint, sort: bool) {
+        require!(max > min, ERROR_INVALID_DOMAIN);
+
+        let domain = ScoreDomain { min, max };
+        self.tvl_per_node_domain().set(&domain);
+
+        let model_mapper = self.delegation_score_model();
+        if sort && !model_mapper.is_empty() && model_mapper.get().method == DelegationScoreMethod::TvlPerNode {
+            self.sort_delegation_contracts_list();
+        }
+
+        self.set_tvl_per_node_domain_event(&domain);
+    }
+
+    /// Sets the parameters bounding permissionless rebalances, which prevent moving stake back and forth between
+    /// Delegation smart contracts whose allocations are close to their targets.
+    ///
+    /// # Arguments
+    ///
+    /// - `min_deviation` - the minimum excess of the source Delegation smart contract over its target allocation,
+    ///   relative to the target allocation (in bps)
+    /// - `min_amount` - the minimum amount of EGLD moved by a single rebalance
+    /// - `cooldown_epochs` - the number of epochs between two rebalances from the same Delegation smart contract
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
+    /// - rebalances are not allowed until these parameters are set
+    ///
+    #[endpoint(setRebalanceParams)]
+    fn set_rebalance_params(&self, min_deviation: u64, min_amount: BigUint, cooldown_epochs: u64) {
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.set_rebalance_params_internal(min_deviation, min_amount, cooldown_epochs);
+    }
+
+    fn set_rebalance_params_internal(&self, min_deviation: u64, min_amount: BigUint, cooldown_epochs: u64) {
+        let params = RebalanceParams {
+            min_deviation,
+            min_amount,
+            cooldown_epochs,
+        };
+        self.rebalance_params().set(&params);
+        self.set_rebalance_params_event(&params);
+    }
+
     /// Sets the Delegation Sampling Model parameters used for the random selection between candidates on a computed
     /// list of Staking Providers Delegation smart contracts.
     ///
@@ -588,12 +1461,22 @@ pub trait GovernanceModule:
     ///
     /// # Notes
     ///
-    /// - can only be called by the admin
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
     ///
     #[endpoint(setDelegationSamplingModelParams)]
     fn set_delegation_sampling_model_params(&self, tolerance: BigUint, max_service_fee: BigUint, premium: BigUint) {
-        self.require_admin();
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.set_delegation_sampling_model_params_internal(tolerance, max_service_fee, premium);
+    }
 
+    fn set_delegation_sampling_model_params_internal(
+        &self,
+        tolerance: BigUint,
+        max_service_fee: BigUint,
+        premium: BigUint,
+    ) {
         require!(tolerance > BigUint::zero(), ERROR_VALUE_CANNOT_BE_ZERO);
         require!(tolerance <= BPS, ERROR_VALUE_EXCEEDS_BPS);
 
@@ -614,30 +1497,88 @@ pub trait GovernanceModule:
     ///
     /// # Notes
     ///
-    /// - can only be called by the admin
+    /// - can only be called by an account with the ScoreModelManager role
+    /// - must be queued through the timelock while the timelock delay is not zero
     ///
     #[endpoint(clearDelegationSamplingModel)]
     fn clear_delegation_sampling_model(&self) {
-        self.require_admin();
+        self.require_role(Role::ScoreModelManager);
+        self.require_not_timelocked();
+        self.clear_delegation_sampling_model_internal();
+    }
+
+    fn clear_delegation_sampling_model_internal(&self) {
         self.delegation_sampling_model().clear();
         self.clear_delegation_sampling_model_event();
     }
 
+    /// Sets the number of epochs between queueing a timelocked call and executing it. Timelocked calls give sEGLD
+    /// holders a window to exit before adverse changes apply.
+    ///
+    /// # Arguments
+    ///
+    /// - `delay` - the timelock delay in epochs. Setting it to zero allows performing timelocked calls directly.
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by the admin
+    /// - the delay can be increased directly, but decreasing it must be queued through the timelock as well
+    ///
+    #[endpoint(setTimelockDelay)]
+    fn set_timelock_delay(&self, delay: u64) {
+        self.require_admin();
+        if delay < self.timelock_delay().get() {
+            self.require_not_timelocked();
+        }
+        self.set_timelock_delay_internal(delay);
+    }
+
+    fn set_timelock_delay_internal(&self, delay: u64) {
+        require!(delay <= MAX_TIMELOCK_DELAY, ERROR_TIMELOCK_DELAY_TOO_LONG);
+        self.timelock_delay().set(delay);
+        self.set_timelock_delay_event(delay);
+    }
+
+    /// Checks that timelocked calls can be performed directly, i.e. that the timelock delay is zero
+    ///
+    fn require_not_timelocked(&self) {
+        require!(self.timelock_delay().get() == 0, ERROR_TIMELOCKED_CALL);
+    }
+
+    /// A public endpoint that continues rescoring the Delegation smart contracts list after a delegation score model
+    /// change, such that the list can be sorted across many transactions regardless of its size.
+    ///
+    /// # Arguments
+    ///
+    /// - `max_contracts` - the maximum number of Delegation smart contracts to rescore
+    ///
+    /// # Notes
+    ///
+    /// - can be called by anyone while there is a pending rescoring, which can be checked through `getSortCursor`
+    ///
+    #[endpoint(sortDelegationContractsList)]
+    fn sort_delegation_contracts_list_endpoint(&self, max_contracts: usize) {
+        require!(self.sort_cursor().get() != 0, ERROR_LIST_ALREADY_SORTED);
+        self.continue_sorting_delegation_contracts_list(max_contracts);
+    }
+
     /// A public endpoint that allows to start bypassing the undelegation algorithm in order to undelegate and,
     /// consequently, withdraw EGLD from the protocol.
     ///
     /// # Notes
     ///
     /// - can be called by anyone after `NO_UNDELEGATIONS_EPOCHS` have passed since the last undelegation
+    /// - can be called by anyone after `NO_DATA_UPDATE_EPOCHS` have passed since the data of any Delegation smart
+    ///   contract in the list has been updated, i.e. updating a single Delegation smart contract does not delay it
     ///
     #[endpoint(deactivateUndelegationAlgorithm)]
     fn deactivate_undelegation_algorithm(&self) {
         let last_undelegate_epoch = self.get_last_undelegate_epoch();
-        let last_contract_data_update_epoch = self.get_last_contract_data_update_epoch();
+        let oldest_contract_data_update_epoch = self.get_oldest_contract_data_update_epoch();
         let current_epoch = self.blockchain().get_block_epoch();
         require!(
             current_epoch - last_undelegate_epoch >= NO_UNDELEGATE_EPOCHS
-                || current_epoch - last_contract_data_update_epoch >= NO_DATA_UPDATE_EPOCHS,
+                || current_epoch.saturating_sub(oldest_contract_data_update_epoch) >= NO_DATA_UPDATE_EPOCHS,
             ERROR_NOT_ENOUGH_ELAPSED_EPOCHS
         );
         self.set_undelegation_mode_internal(UndelegationMode::Open);
@@ -647,13 +1588,13 @@ pub trait GovernanceModule:
     ///
     /// # Notes
     ///
-    /// - can only be called by the admin
+    /// - can only be called by an account with the Guardian role
     /// - can only be reactivated after `NO_UNDELEGATIONS_EPOCHS + COOLDOWN_REACTIVATE_UNDELEGATION_ALGORITHM` have
     ///   elapsed since the last undelegation
     ///
     #[endpoint(reactivateUndelegationAlgorithm)]
     fn reactivate_undelegation_algorithm(&self) {
-        self.require_admin();
+        self.require_role(Role::Guardian);
         let last_epoch = self.get_last_undelegate_epoch();
         let current_epoch = self.blockchain().get_block_epoch();
         require!(
@@ -0,0 +1,147 @@
+multiversx_sc::imports!();
+use super::{common, events, model::*, proxies, storage};
+
+#[multiversx_sc::module]
+pub trait InvariantsModule:
+    admin::AdminModule + common::CommonModule + events::EventsModule + proxies::ProxyModule + storage::StorageModule
+{
+    /// Reconciles the accounting of the protocol and returns the list of invariants that do not hold. An empty result
+    /// means that the accounting is consistent.
+    ///
+    /// # Notes
+    ///
+    /// - This iterates over all Delegation smart contracts and penalties, so it is only meant to be used from tests
+    ///   and, through the `invariants` feature, from a debug view.
+    /// - The invariants only hold when there are no asynchronous calls in flight. For instance, while a
+    ///   `delegatePendingAmount` call is waiting for its callback, the delegated amount has left the pending amount but
+    ///   has not yet been added to the total delegated amount.
+    ///
+    fn check_invariants(&self) -> ManagedVec<InvariantViolation<Self::Api>> {
+        let mut delegation_contracts = ManagedVec::<Self::Api, ManagedAddress>::new();
+        for node in self.delegation_contracts_list().iter() {
+            delegation_contracts.push(node.into_value());
+        }
+
+        let mut misplaced_contracts = 0u64;
+        for delegation_contract in self.blacklisted_delegation_contracts().iter() {
+            let contract_data = self.delegation_contract_data(&delegation_contract).get();
+            if !contract_data.blacklisted || delegation_contracts.contains(&delegation_contract) {
+                misplaced_contracts += 1;
+            }
+            delegation_contracts.push(delegation_contract);
+        }
+
+        let mut total_pending_to_delegate = BigUint::zero();
+        let mut total_delegated = BigUint::zero();
+        let mut total_undelegated = BigUint::zero();
+        let mut total_withdrawable = BigUint::zero();
+        for delegation_contract in delegation_contracts.iter() {
+            let contract_data = self.delegation_contract_data(&delegation_contract).get();
+            total_pending_to_delegate += &contract_data.pending_to_delegate;
+            total_delegated += &contract_data.total_delegated;
+            total_undelegated += &contract_data.total_undelegated;
+            total_withdrawable += &contract_data.total_withdrawable;
+        }
+
+        // penalties are not backed by sEGLD redemptions, so they remain part of the cash reserve until they are
+        // delegated again or withdrawn by users
+        let mut penalties_amount = BigUint::zero();
+        let mut withdrawn_penalties_amount = BigUint::zero();
+        let next_penalty_id = self.next_penalty_id().get();
+        for penalty_id in 0..next_penalty_id {
+            let penalty_mapper = self.penalties(penalty_id);
+            if penalty_mapper.is_empty() {
+                continue;
+            }
+            let penalty = penalty_mapper.get();
+            penalties_amount += &penalty.attributes.egld_amount;
+            if penalty.withdrawn {
+                withdrawn_penalties_amount += &penalty.attributes.egld_amount;
+            }
+        }
+
+        let mut violations = ManagedVec::new();
+
+        // the liquidity buffer, its refills and the rebalances are EGLD owned by sEGLD holders as well
+        let liquidity_buffer = self.liquidity_buffer().get();
+        let expected_cash_reserve = &total_pending_to_delegate
+            + &total_delegated
+            + &penalties_amount
+            + &liquidity_buffer
+            + &self.pending_buffer_refill().get()
+            + &self.pending_rebalance().get();
+        self.check_invariant(
+            &mut violations,
+            AccountingInvariant::CashReserve,
+            expected_cash_reserve,
+            self.cash_reserve().get(),
+        );
+
+        self.check_invariant(
+            &mut violations,
+            AccountingInvariant::TotalUndelegated,
+            total_undelegated,
+            self.total_undelegated().get(),
+        );
+
+        self.check_invariant(
+            &mut violations,
+            AccountingInvariant::TotalWithdrawable,
+            total_withdrawable,
+            self.total_withdrawable().get(),
+        );
+
+        // the balance can only exceed the accounted amount, e.g. if EGLD is sent to the contract through other means
+        let accounted_balance = total_pending_to_delegate
+            + self.total_withdrawable().get()
+            + self.rewards_reserve().get()
+            + self.protocol_reserve().get()
+            + withdrawn_penalties_amount
+            + liquidity_buffer;
+        let balance = self.blockchain().get_sc_balance(&EgldOrEsdtTokenIdentifier::egld(), 0);
+        if balance < accounted_balance {
+            violations.push(InvariantViolation {
+                invariant: AccountingInvariant::EgldBalance,
+                expected: accounted_balance,
+                actual: balance,
+            });
+        }
+
+        self.check_invariant(
+            &mut violations,
+            AccountingInvariant::Blacklist,
+            BigUint::zero(),
+            BigUint::from(misplaced_contracts),
+        );
+
+        // shares are minted and redeemed at the exchange rate rounded in favor of the protocol, so the EGLD backing the
+        // sEGLD supply can only grow relative to it
+        let ls_token_supply = self.ls_token_supply().get();
+        let backing = self.cash_reserve().get() - self.get_unvested_rewards();
+        if backing < ls_token_supply {
+            violations.push(InvariantViolation {
+                invariant: AccountingInvariant::ExchangeRate,
+                expected: ls_token_supply,
+                actual: backing,
+            });
+        }
+
+        violations
+    }
+
+    fn check_invariant(
+        &self,
+        violations: &mut ManagedVec<InvariantViolation<Self::Api>>,
+        invariant: AccountingInvariant,
+        expected: BigUint,
+        actual: BigUint,
+    ) {
+        if expected != actual {
+            violations.push(InvariantViolation {
+                invariant,
+                expected,
+                actual,
+            });
+        }
+    }
+}
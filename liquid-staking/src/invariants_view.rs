@@ -0,0 +1,34 @@
+multiversx_sc::imports!();
+#[cfg(feature = "invariants")]
+use super::{common, events, invariants, model::*, proxies, storage};
+
+/// Exposes the accounting invariants through a view. It is only built with the `invariants` feature, such that the
+/// production wasm does not ship a view that iterates over all Delegation smart contracts and penalties.
+#[cfg(feature = "invariants")]
+#[multiversx_sc::module]
+pub trait InvariantsViewModule:
+    admin::AdminModule
+    + common::CommonModule
+    + events::EventsModule
+    + invariants::InvariantsModule
+    + proxies::ProxyModule
+    + storage::StorageModule
+{
+    /// Reconciles the accounting of the protocol and returns the list of invariants that do not hold. An empty result
+    /// means that the accounting is consistent.
+    ///
+    /// # Notes
+    ///
+    /// - only available when the contract is built with the `invariants` feature, for monitoring and debugging
+    ///   purposes
+    /// - the invariants only hold when there are no asynchronous calls in flight
+    ///
+    #[view(getInvariantViolations)]
+    fn get_invariant_violations(&self) -> MultiValueEncoded<InvariantViolation<Self::Api>> {
+        self.check_invariants().into()
+    }
+}
+
+#[cfg(not(feature = "invariants"))]
+#[multiversx_sc::module]
+pub trait InvariantsViewModule {}
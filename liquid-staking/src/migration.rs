@@ -1,6 +1,7 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 use super::{common, errors::*, events, proxies, score, selection, storage};
+use admin::Role;
 
 #[multiversx_sc::module]
 pub trait MigrationModule:
@@ -22,11 +23,11 @@ pub trait MigrationModule:
     ///
     /// # Notes
     ///
-    /// - can only be called by the admin
+    /// - can only be called by an account with the WhitelistManager role
     ///
     #[endpoint(addToMigrationWhitelist)]
     fn add_to_migration_whitelist(&self, user: &ManagedAddress, delegation_contract: &ManagedAddress) {
-        self.require_admin();
+        self.require_role(Role::WhitelistManager);
 
         let migration_whitelist_mapper = self.migration_whitelist(user);
         require!(migration_whitelist_mapper.is_empty(), ERROR_USER_ALREADY_WHITELISTED);
@@ -57,11 +58,11 @@ pub trait MigrationModule:
     ///
     /// # Notes
     ///
-    /// - can only be called by the admin
+    /// - can only be called by an account with the WhitelistManager role
     ///
     #[endpoint(removeFromMigrationWhitelist)]
     fn remove_from_migration_whitelist(&self, user: &ManagedAddress) {
-        self.require_admin();
+        self.require_role(Role::WhitelistManager);
         self.remove_from_migration_whitelist_internal(user);
     }
 
@@ -98,6 +99,7 @@ pub trait MigrationModule:
     /// - requires that the user has an entry in the migration whitelist
     /// - it verifies that the smart contract has enough capacity for the delegation and its not outdated, it does not
     ///   check its service fee (as in `is_valid_delegation_contract`)
+    /// - the concentration limits apply to migrations as well
     ///
     fn get_whitelisted_delegation_contract_for_delegate(
         &self,
@@ -122,6 +124,10 @@ pub trait MigrationModule:
             self.has_valid_cap(&contract_data, egld_amount) && !contract_data.outdated,
             ERROR_INVALID_DELEGATION_CONTRACT
         );
+        require!(
+            self.is_within_concentration_limits(&delegation_contract, egld_amount, true),
+            ERROR_CONCENTRATION_LIMIT_EXCEEDED
+        );
 
         delegation_contract
     }
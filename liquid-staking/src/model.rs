@@ -1,5 +1,6 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
+use admin::Role;
 
 #[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Eq, Debug, ManagedVecItem)]
 pub struct DelegationContractData<M: ManagedTypeApi> {
@@ -51,10 +52,63 @@ pub struct DelegationContractData<M: ManagedTypeApi> {
     pub blacklisted: bool,
 }
 
-#[derive(TypeAbi, TopEncode, TopDecode, PartialEq, Eq, Copy, Clone, Debug)]
-pub enum State {
-    Inactive,
-    Active,
+/// The indexes of the Delegation smart contracts by capacity, which allow to find a Delegation smart contract able to
+/// serve a delegation or an undelegation without traversing the Delegation smart contracts list
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Copy, Clone, Debug)]
+pub enum CapacityIndex {
+    /// Indexed by the amount of EGLD that can still be delegated before reaching the cap
+    Delegate,
+
+    /// Indexed by the amount of delegated EGLD
+    Undelegate,
+}
+
+/// The groups of endpoints that can be paused independently
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Operation {
+    /// Delegations and delegations of pending amounts to Delegation smart contracts
+    Delegate,
+
+    /// Undelegations, instant undelegations, undelegations of pending amounts, liquidity buffer refills and rebalances
+    Undelegate,
+
+    /// Withdrawals by users and from Delegation smart contracts, and the completion of refills and rebalances
+    Withdraw,
+
+    /// Claiming and delegating rewards
+    Rewards,
+
+    /// Penalizing Delegation smart contracts and delegating or withdrawing penalties
+    Penalties,
+
+    /// Delegations from users in the migration whitelist
+    Migration,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 6] = [
+        Operation::Delegate,
+        Operation::Undelegate,
+        Operation::Withdraw,
+        Operation::Rewards,
+        Operation::Penalties,
+        Operation::Migration,
+    ];
+
+    /// The bit representing this operation in the paused operations bitmask
+    pub fn flag(&self) -> u8 {
+        1 << (*self as u8)
+    }
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Copy, Clone, Debug)]
+pub struct PauseStatus {
+    pub delegate: bool,
+    pub undelegate: bool,
+    pub withdraw: bool,
+    pub rewards: bool,
+    pub penalties: bool,
+    pub migration: bool,
 }
 
 #[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Eq, Debug)]
@@ -79,11 +133,21 @@ pub struct Penalty<M: ManagedTypeApi> {
     pub attributes: UndelegateAttributes<M>,
 }
 
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Eq, Debug)]
+pub struct Rebalance<M: ManagedTypeApi> {
+    pub id: u64,
+    pub attributes: UndelegateAttributes<M>,
+    pub delegation_contract_to: ManagedAddress<M>,
+}
+
 #[derive(TypeAbi, TopEncode, TopDecode, NestedDecode, NestedEncode, PartialEq, Eq, Copy, Clone, Debug)]
 pub enum DelegationScoreMethod {
     Tvl,
     Apr,
     Mixed,
+    TvlPerNode,
+    ServiceFee,
+    Weighted,
 }
 
 #[derive(TypeAbi, TopEncode, TopDecode, NestedDecode, NestedEncode, PartialEq, Eq, Clone, Debug)]
@@ -94,6 +158,87 @@ pub struct DelegationScoreModel<M: ManagedTypeApi> {
     pub min_apr: BigUint<M>,
     pub max_apr: BigUint<M>,
     pub omega: BigUint<M>,
+    pub curve: ScoreCurve<M>,
+}
+
+/// The Delegation Score model parameters as stored before the introduction of score curves
+#[derive(TypeAbi, TopEncode, TopDecode, NestedDecode, NestedEncode, PartialEq, Eq, Clone, Debug)]
+pub struct LegacyDelegationScoreModel<M: ManagedTypeApi> {
+    pub method: DelegationScoreMethod,
+    pub min_tvl: BigUint<M>,
+    pub max_tvl: BigUint<M>,
+    pub min_apr: BigUint<M>,
+    pub max_apr: BigUint<M>,
+    pub omega: BigUint<M>,
+}
+
+/// The curve mapping a normalized parameter to its score, both in basis points. All curves are non-decreasing and go
+/// from zero to one.
+#[derive(TypeAbi, TopEncode, TopDecode, NestedDecode, NestedEncode, PartialEq, Eq, Clone, Debug)]
+pub enum ScoreCurve<M: ManagedTypeApi> {
+    /// The score equals the normalized parameter
+    Linear,
+
+    /// The score is interpolated between the given breakpoints, in addition to the zero and one endpoints
+    PiecewiseLinear {
+        breakpoints: ManagedVec<M, ScoreCurvePoint>,
+    },
+
+    /// A logarithmic-like curve with diminishing returns, `x * (BPS + curvature) / (x + curvature)`, where lower
+    /// curvatures yield steeper curves near zero
+    Logarithmic { curvature: u64 },
+
+    /// A sigmoid-like curve made of a convex and a concave logarithmic-like curve meeting at the midpoint
+    Sigmoid { midpoint: u64, curvature: u64 },
+}
+
+#[derive(
+    TypeAbi, TopEncode, TopDecode, NestedDecode, NestedEncode, ManagedVecItem, PartialEq, Eq, Copy, Clone, Debug,
+)]
+pub struct ScoreCurvePoint {
+    /// The normalized parameter (in bps)
+    pub x: u64,
+
+    /// The score at the normalized parameter (in bps)
+    pub y: u64,
+}
+
+#[derive(
+    TypeAbi, TopEncode, TopDecode, NestedDecode, NestedEncode, ManagedVecItem, PartialEq, Eq, Copy, Clone, Debug,
+)]
+pub enum DelegationScoreFactor {
+    Tvl,
+    Apr,
+    NrNodes,
+    TvlPerNode,
+    ServiceFee,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedDecode, NestedEncode, ManagedVecItem, PartialEq, Eq, Clone, Debug)]
+pub struct DelegationScoreTerm<M: ManagedTypeApi> {
+    /// The Delegation smart contract data the term is computed from
+    pub factor: DelegationScoreFactor,
+
+    /// The weight of the term in the delegation score (in bps)
+    pub weight: u64,
+
+    /// Delegation smart contracts with lower values than this parameter share the same term score
+    pub min: BigUint<M>,
+
+    /// Delegation smart contracts with higher values than this parameter share the same term score
+    pub max: BigUint<M>,
+
+    /// Whether lower values yield higher term scores
+    pub down: bool,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedDecode, NestedEncode, PartialEq, Eq, Clone, Debug)]
+pub struct ScoreDomain<M: ManagedTypeApi> {
+    /// Delegation smart contracts with lower values than this parameter share the same score
+    pub min: BigUint<M>,
+
+    /// Delegation smart contracts with higher values than this parameter share the same score
+    pub max: BigUint<M>,
 }
 
 #[derive(TypeAbi, TopEncode, TopDecode, NestedDecode, NestedEncode, PartialEq, Eq, Clone, Debug)]
@@ -109,9 +254,263 @@ pub struct DelegationCandidate<M: ManagedTypeApi> {
     pub data: DelegationContractData<M>,
 }
 
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Clone, Debug)]
+pub struct RewardsStream<M: ManagedTypeApi> {
+    /// The amount of delegated rewards that had not been vested yet at the start round
+    pub amount: BigUint<M>,
+
+    /// The round at which the stream started or was last topped up
+    pub start_round: u64,
+
+    /// The round at which the whole amount is vested
+    pub end_round: u64,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Copy, Clone, Debug)]
+pub enum TimelockAction {
+    SetTotalFee,
+    SetDelegationScoreModelParams,
+    SetDelegationSamplingModelParams,
+    WhitelistDelegationContract,
+    BlacklistDelegationContract,
+    WithdrawReserve,
+    SetTimelockDelay,
+    SetDelegationScoreTerms,
+    SetRebalanceParams,
+    SetDataManager,
+    AddDataReporter,
+    RemoveDataReporter,
+    SetDataReportersQuorum,
+    SetMaxDataReportDeviation,
+    SetDataBounds,
+    SetMaxContractDataAge,
+    SetConcentrationLimits,
+    SetOperator,
+    RemoveOperator,
+    SetDelegationScoreCurve,
+    ReleaseQuarantine,
+    SetTvlPerNodeDomain,
+    SetLiquidityBufferTarget,
+    SetRewardsVestingPeriod,
+    SetInstantUndelegateFee,
+    ClearDelegationSamplingModel,
+}
+
+impl TimelockAction {
+    /// The role required to queue and execute the call, where `None` stands for the admin
+    pub fn role(&self) -> Option<Role> {
+        match self {
+            TimelockAction::SetTotalFee
+            | TimelockAction::SetLiquidityBufferTarget
+            | TimelockAction::SetRewardsVestingPeriod
+            | TimelockAction::SetInstantUndelegateFee => Some(Role::FeeManager),
+            TimelockAction::SetDelegationScoreModelParams
+            | TimelockAction::SetDelegationSamplingModelParams
+            | TimelockAction::SetDelegationScoreTerms
+            | TimelockAction::SetRebalanceParams
+            | TimelockAction::SetDataManager
+            | TimelockAction::AddDataReporter
+            | TimelockAction::RemoveDataReporter
+            | TimelockAction::SetDataReportersQuorum
+            | TimelockAction::SetMaxDataReportDeviation
+            | TimelockAction::SetDataBounds
+            | TimelockAction::SetMaxContractDataAge
+            | TimelockAction::SetConcentrationLimits
+            | TimelockAction::SetDelegationScoreCurve
+            | TimelockAction::ReleaseQuarantine
+            | TimelockAction::SetTvlPerNodeDomain
+            | TimelockAction::ClearDelegationSamplingModel => Some(Role::ScoreModelManager),
+            TimelockAction::WhitelistDelegationContract
+            | TimelockAction::BlacklistDelegationContract
+            | TimelockAction::SetOperator
+            | TimelockAction::RemoveOperator => Some(Role::WhitelistManager),
+            TimelockAction::WithdrawReserve => Some(Role::ReserveTreasurer),
+            TimelockAction::SetTimelockDelay => None,
+        }
+    }
+
+    /// The minimum and maximum number of arguments of the call, where optional arguments are trailing
+    pub fn num_arguments(&self) -> (usize, usize) {
+        match self {
+            TimelockAction::SetTotalFee => (1, 1),
+            TimelockAction::SetDelegationScoreModelParams => (6, 7),
+            TimelockAction::SetDelegationSamplingModelParams => (3, 3),
+            TimelockAction::WhitelistDelegationContract => (5, 6),
+            TimelockAction::BlacklistDelegationContract => (1, 1),
+            TimelockAction::WithdrawReserve => (1, 2),
+            TimelockAction::SetTimelockDelay => (1, 1),
+            TimelockAction::SetDelegationScoreTerms => (2, 2),
+            TimelockAction::SetRebalanceParams => (3, 3),
+            TimelockAction::SetDataManager => (1, 1),
+            TimelockAction::AddDataReporter => (1, 1),
+            TimelockAction::RemoveDataReporter => (1, 1),
+            TimelockAction::SetDataReportersQuorum => (1, 1),
+            TimelockAction::SetMaxDataReportDeviation => (1, 1),
+            TimelockAction::SetDataBounds => (5, 5),
+            TimelockAction::SetMaxContractDataAge => (1, 1),
+            TimelockAction::SetConcentrationLimits => (2, 2),
+            TimelockAction::SetOperator => (2, 2),
+            TimelockAction::RemoveOperator => (1, 1),
+            TimelockAction::SetDelegationScoreCurve => (2, 2),
+            TimelockAction::ReleaseQuarantine => (1, 1),
+            TimelockAction::SetTvlPerNodeDomain => (3, 3),
+            TimelockAction::SetLiquidityBufferTarget => (1, 1),
+            TimelockAction::SetRewardsVestingPeriod => (1, 1),
+            TimelockAction::SetInstantUndelegateFee => (2, 2),
+            TimelockAction::ClearDelegationSamplingModel => (0, 0),
+        }
+    }
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Clone, Debug)]
+pub struct TimelockCall<M: ManagedTypeApi> {
+    /// The governance call to perform
+    pub action: TimelockAction,
+
+    /// The top-encoded arguments of the call, in the same order as the corresponding endpoint arguments
+    pub arguments: ManagedVec<M, ManagedBuffer<M>>,
+
+    /// The account that queued the call
+    pub proposer: ManagedAddress<M>,
+
+    /// The epoch from which the call can be executed
+    pub eta_epoch: u64,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Clone, Debug)]
+pub struct DataReport<M: ManagedTypeApi> {
+    /// The epoch at which the data has been reported
+    pub epoch: u64,
+
+    /// The reported total value locked at the staking provider smart contract
+    pub total_value_locked: BigUint<M>,
+
+    /// The reported number of nodes
+    pub nr_nodes: u64,
+
+    /// The reported Staking Provider APR
+    pub apr: BigUint<M>,
+
+    /// The reported Staking Provider service fee
+    pub service_fee: BigUint<M>,
+
+    /// The reported cap amount for the staking provider smart contract if existent
+    pub cap: Option<BigUint<M>>,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Copy, Clone, Debug)]
+pub struct ListPosition {
+    /// The node identifier of the Delegation smart contract in the Delegation smart contracts list
+    pub node_id: u32,
+
+    /// The delegation score the Delegation smart contract has been inserted with
+    pub delegation_score: u64,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Copy, Clone, Debug)]
+pub enum DataBoundsAction {
+    /// Updates breaking the bounds are rejected
+    Reject,
+
+    /// Updates breaking the bounds are discarded and the Delegation smart contract is marked as outdated
+    Quarantine,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Clone, Debug)]
+pub struct DataBounds<M: ManagedTypeApi> {
+    /// The maximum relative change of the total value locked per update (in bps), unbounded if zero
+    pub max_tvl_change: u64,
+
+    /// The maximum Staking Provider APR, unbounded if zero
+    pub max_apr: BigUint<M>,
+
+    /// The maximum Staking Provider service fee, unbounded if zero
+    pub max_service_fee: BigUint<M>,
+
+    /// The maximum change of the number of nodes per update, unbounded if zero
+    pub max_nr_nodes_change: u64,
+
+    /// What happens to updates breaking any of the bounds
+    pub action: DataBoundsAction,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Clone, Debug)]
+pub struct RebalanceParams<M: ManagedTypeApi> {
+    /// The minimum excess of the source Delegation smart contract over its target allocation, relative to the target
+    /// allocation (in bps)
+    pub min_deviation: u64,
+
+    /// The minimum amount of EGLD moved by a single rebalance
+    pub min_amount: BigUint<M>,
+
+    /// The number of epochs between two rebalances from the same Delegation smart contract
+    pub cooldown_epochs: u64,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Copy, Clone, Debug)]
+pub struct ConcentrationLimits {
+    /// The maximum share of the cash reserve allocated to a single Delegation smart contract (in bps), unlimited if zero
+    pub max_contract_share: u64,
+
+    /// The maximum share of the cash reserve allocated to the Delegation smart contracts of a single operator (in bps),
+    /// unlimited if zero
+    pub max_operator_share: u64,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Clone, Debug)]
+pub struct OperatorData<M: ManagedTypeApi> {
+    /// The sum of the total value locked at the Delegation smart contracts run by the operator
+    pub total_value_locked: BigUint<M>,
+
+    /// The sum of the number of validator nodes of the Delegation smart contracts run by the operator
+    pub nr_nodes: u64,
+
+    /// The amount of EGLD allocated to the Delegation smart contracts run by the operator, i.e. delegated, pending to
+    /// be delegated or on its way to them through rebalances
+    pub allocation: BigUint<M>,
+}
+
 #[derive(TypeAbi, TopEncode, TopDecode, PartialEq, Eq, Copy, Clone, Debug)]
 pub enum UndelegationMode {
     None,
     Algorithm,
     Open,
 }
+
+#[derive(TypeAbi, TopEncode, TopDecode, PartialEq, Eq, Copy, Clone, Debug)]
+pub enum InstantUndelegateFeeRecipient {
+    ProtocolReserve,
+    BufferProviders,
+}
+
+#[derive(
+    TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Copy, Clone, Debug, ManagedVecItem,
+)]
+pub enum AccountingInvariant {
+    // The cash reserve equals the EGLD pending to be delegated or delegated across all Delegation smart contracts plus
+    // the EGLD held by penalties and by the liquidity buffer
+    CashReserve,
+
+    // The total undelegated amount equals the sum of the undelegated amounts of all Delegation smart contracts
+    TotalUndelegated,
+
+    // The total withdrawable amount equals the sum of the withdrawable amounts of all Delegation smart contracts
+    TotalWithdrawable,
+
+    // The EGLD balance of the Liquid Staking smart contract covers all the EGLD it is accountable for
+    EgldBalance,
+
+    // A blacklisted Delegation smart contract is flagged as blacklisted and is not part of the list
+    Blacklist,
+
+    // The cash reserve without the unvested rewards covers the outstanding sEGLD supply, i.e. the exchange rate never
+    // falls below its initial value of one
+    ExchangeRate,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Eq, Debug, ManagedVecItem)]
+pub struct InvariantViolation<M: ManagedTypeApi> {
+    pub invariant: AccountingInvariant,
+    pub expected: BigUint<M>,
+    pub actual: BigUint<M>,
+}
@@ -1,5 +1,6 @@
 multiversx_sc::imports!();
 use super::{common, delegation, errors::*, events, model::*, proxies, score, selection, storage};
+use admin::Role;
 
 #[multiversx_sc::module]
 pub trait PenaltyModule:
@@ -18,11 +19,11 @@ pub trait PenaltyModule:
     /// 1. By undelegating an amount of EGLD from the Delegation smart contract.
     /// 2. By reducing the pending amount of EGLD to be delegated to the Delegation smart contract.
     ///
-    /// The first mechanism can only be triggered by the admin when detecting misbehavior from the Staking Agency
+    /// The first mechanism can only be triggered by the guardian when detecting misbehavior from the Staking Agency
     /// associated with the Delegation smart contract. This penalty must be unbonded from the Delegation smart contract
     /// at a future time using the `unbondPenalty` public endpoint.
     ///
-    /// The second mechanism can be triggered by the admin in cases of Staking Agency misbehavior or when there is a
+    /// The second mechanism can be triggered by the guardian in cases of Staking Agency misbehavior or when there is a
     /// pending amount to be delegated that cannot be deposited due to current cap and total value locked values. The
     /// community may also initiate this penalty if specific conditions are met. Since this penalty affects the pending
     /// amount to be delegated, the EGLD is already present and does not need to be withdrawn. Therefore, the penalty is
@@ -46,16 +47,18 @@ pub trait PenaltyModule:
         source: PenaltySource,
         opt_egld_amount: OptionalValue<BigUint>,
     ) {
+        self.require_not_paused(Operation::Penalties);
+
         let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
         require!(!contract_data_mapper.is_empty(), ERROR_UNEXPECTED_DELEGATION_CONTRACT);
 
         match source {
             PenaltySource::FromUndelegate => {
-                self.require_admin();
+                self.require_role(Role::Guardian);
                 self.penalty_from_undelegation(delegation_contract, opt_egld_amount);
             },
             PenaltySource::FromPendingToDelegate => {
-                self.require_open_mode_or_admin();
+                self.require_open_mode_or_guardian();
                 self.penalty_from_pending_to_delegate(delegation_contract, opt_egld_amount);
             },
         }
@@ -87,7 +90,7 @@ pub trait PenaltyModule:
             },
         };
 
-        contract_data_mapper.update(|data| {
+        self.update_delegation_contract_data(&delegation_contract, |data| {
             data.total_delegated -= &egld_amount;
             data.pending_to_undelegate += &egld_amount;
         });
@@ -147,7 +150,7 @@ pub trait PenaltyModule:
             },
         };
 
-        contract_data_mapper.update(|data| {
+        self.update_delegation_contract_data(&delegation_contract, |data| {
             data.pending_to_delegate -= &egld_amount;
         });
 
@@ -186,6 +189,8 @@ pub trait PenaltyModule:
     ///
     #[endpoint(withdrawPenalty)]
     fn withdraw_penalty(&self, penalty_id: u64) {
+        self.require_not_paused(Operation::Penalties);
+
         self.require_valid_penalty_id(penalty_id);
 
         let penalty_mapper = self.penalties(penalty_id);
@@ -216,7 +221,7 @@ pub trait PenaltyModule:
     ///
     #[endpoint(delegatePenalty)]
     fn delegate_penalty(&self, penalty_id: u64, opt_egld_amount: OptionalValue<BigUint>) {
-        self.require_active_state();
+        self.require_not_paused(Operation::Penalties);
         self.require_valid_penalty_id(penalty_id);
 
         let penalty = self.penalties(penalty_id).get();
@@ -238,10 +243,11 @@ pub trait PenaltyModule:
         let delegation_contract = self.get_delegation_contract_for_delegate(
             &egld_amount,
             &OptionalValue::Some(penalty.attributes.delegation_contract),
+            false,
         );
 
         let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
-        contract_data_mapper.update(|data| {
+        self.update_delegation_contract_data(&delegation_contract, |data| {
             data.pending_to_delegate += &egld_amount;
         });
 
@@ -261,6 +267,8 @@ pub trait PenaltyModule:
     #[payable("*")]
     #[endpoint(withdrawFromPenalty)]
     fn withdraw_from_penalty(&self, penalty_id: u64) {
+        self.require_not_paused(Operation::Penalties);
+
         self.require_open_mode();
         self.require_valid_penalty_id(penalty_id);
 
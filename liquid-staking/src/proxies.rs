@@ -1,6 +1,6 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
-use super::storage;
+use super::{constants::*, storage};
 
 #[multiversx_sc::module]
 pub trait ProxyModule: storage::StorageModule {
@@ -67,6 +67,89 @@ pub trait ProxyModule: storage::StorageModule {
             .call_and_exit()
     }
 
+    fn delegate_to_delegation_contract_promise(
+        &self,
+        delegation_contract: ManagedAddress,
+        egld_amount: BigUint,
+        gas_for_async_call: u64,
+        callback: CallbackClosure<<Self as ContractBase>::Api>,
+    ) {
+        self.delegation_contract_proxy()
+            .contract(delegation_contract)
+            .delegate()
+            .with_gas_limit(gas_for_async_call)
+            .with_egld_transfer(egld_amount)
+            .async_call_promise()
+            .with_callback(callback)
+            .with_extra_gas_for_callback(MIN_GAS_FOR_CALLBACK)
+            .register_promise()
+    }
+
+    fn undelegate_from_delegation_contract_promise(
+        &self,
+        delegation_contract: ManagedAddress,
+        egld_amount: BigUint,
+        gas_for_async_call: u64,
+        callback: CallbackClosure<<Self as ContractBase>::Api>,
+    ) {
+        self.delegation_contract_proxy()
+            .contract(delegation_contract)
+            .undelegate(egld_amount)
+            .with_gas_limit(gas_for_async_call)
+            .async_call_promise()
+            .with_callback(callback)
+            .with_extra_gas_for_callback(MIN_GAS_FOR_CALLBACK)
+            .register_promise()
+    }
+
+    fn claim_rewards_from_delegation_contract_promise(
+        &self,
+        delegation_contract: ManagedAddress,
+        gas_for_async_call: u64,
+        callback: CallbackClosure<<Self as ContractBase>::Api>,
+    ) {
+        self.delegation_contract_proxy()
+            .contract(delegation_contract)
+            .claim_rewards()
+            .with_gas_limit(gas_for_async_call)
+            .async_call_promise()
+            .with_callback(callback)
+            .with_extra_gas_for_callback(MIN_GAS_FOR_CALLBACK)
+            .register_promise()
+    }
+
+    fn get_contract_config_promise(
+        &self,
+        delegation_contract: ManagedAddress,
+        gas_for_async_call: u64,
+        callback: CallbackClosure<<Self as ContractBase>::Api>,
+    ) {
+        self.delegation_contract_proxy()
+            .contract(delegation_contract)
+            .get_contract_config()
+            .with_gas_limit(gas_for_async_call)
+            .async_call_promise()
+            .with_callback(callback)
+            .with_extra_gas_for_callback(MIN_GAS_FOR_CALLBACK)
+            .register_promise()
+    }
+
+    fn get_total_active_stake_promise(
+        &self,
+        delegation_contract: ManagedAddress,
+        gas_for_async_call: u64,
+        callback: CallbackClosure<<Self as ContractBase>::Api>,
+    ) {
+        self.delegation_contract_proxy()
+            .contract(delegation_contract)
+            .get_total_active_stake()
+            .with_gas_limit(gas_for_async_call)
+            .async_call_promise()
+            .with_callback(callback)
+            .with_extra_gas_for_callback(MIN_GAS_FOR_CALLBACK)
+            .register_promise()
+    }
+
     fn get_random(&self, min: &BigUint, max: &BigUint) -> BigUint {
         let random_contract = self.random_oracle().get();
         self.random_proxy()
@@ -99,6 +182,12 @@ pub mod delegation_mod {
 
         #[endpoint(claimRewards)]
         fn claim_rewards(&self);
+
+        #[view(getContractConfig)]
+        fn get_contract_config(&self) -> MultiValueEncoded<ManagedBuffer>;
+
+        #[view(getTotalActiveStake)]
+        fn get_total_active_stake(&self) -> BigUint;
     }
 }
 
@@ -0,0 +1,248 @@
+multiversx_sc::imports!();
+use super::{common, constants::*, delegation, errors::*, events, model::*, proxies, score, selection, storage};
+
+#[multiversx_sc::module]
+pub trait RebalanceModule:
+    admin::AdminModule
+    + common::CommonModule
+    + delegation::DelegationModule
+    + events::EventsModule
+    + proxies::ProxyModule
+    + score::ScoreModule
+    + selection::SelectionModule
+    + storage::StorageModule
+{
+    /// Moves delegated EGLD from an over-weight Delegation smart contract to an under-weight one, based on the target
+    /// allocations given by the delegation scores. Similarly to `refillLiquidityBuffer`, the amount becomes pending to
+    /// be undelegated from the source Delegation smart contract, which is left to the `unDelegatePendingAmount` public
+    /// endpoint. Once the unbond period has passed and the EGLD has been brought back using `withdrawFrom`, the
+    /// rebalance can be completed using `completeRebalance`.
+    ///
+    /// # Arguments
+    ///
+    /// - `delegation_contract_from` - the Delegation smart contract to undelegate from
+    /// - `delegation_contract_to` - the Delegation smart contract to delegate to
+    /// - `egld_amount` - the amount of EGLD to move
+    ///
+    /// # Notes
+    ///
+    /// - This endpoint can be called by anyone.
+    /// - The EGLD amount can exceed neither the excess of the source Delegation smart contract over its target
+    ///   allocation nor the shortfall of the destination Delegation smart contract, which can be previewed using the
+    ///   `getMaxRebalanceAmount` view.
+    /// - The excess must be above the minimum deviation, the EGLD amount must be above the minimum amount and the
+    ///   cooldown since the last rebalance from the source Delegation smart contract must have elapsed, as given by
+    ///   `setRebalanceParams`.
+    /// - Blacklisted Delegation smart contracts have no target allocation, so their whole allocation can be moved.
+    /// - Neither undelegate NFTs nor the exchange rate are affected, since the EGLD remains in the cash reserve.
+    ///
+    #[endpoint(rebalance)]
+    fn rebalance(
+        &self,
+        delegation_contract_from: ManagedAddress,
+        delegation_contract_to: ManagedAddress,
+        egld_amount: BigUint,
+    ) -> u64 {
+        self.require_not_paused(Operation::Undelegate);
+
+        require!(
+            delegation_contract_from != delegation_contract_to,
+            ERROR_INVALID_REBALANCE_CONTRACTS
+        );
+
+        let from_data_mapper = self.delegation_contract_data(&delegation_contract_from);
+        require!(!from_data_mapper.is_empty(), ERROR_UNEXPECTED_DELEGATION_CONTRACT);
+
+        let to_data_mapper = self.delegation_contract_data(&delegation_contract_to);
+        require!(!to_data_mapper.is_empty(), ERROR_UNEXPECTED_DELEGATION_CONTRACT);
+
+        let to_data = to_data_mapper.get();
+        require!(!to_data.blacklisted, ERROR_BLACKLISTED_DELEGATION_CONTRACT);
+
+        self.require_sufficient_egld(&egld_amount);
+
+        let params_mapper = self.rebalance_params();
+        require!(!params_mapper.is_empty(), ERROR_REBALANCE_PARAMS_UNSET);
+        let params = params_mapper.get();
+        require!(egld_amount >= params.min_amount, ERROR_REBALANCE_BELOW_MIN_AMOUNT);
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        let last_epoch_mapper = self.last_rebalance_epoch(&delegation_contract_from);
+        require!(
+            last_epoch_mapper.is_empty() || current_epoch >= last_epoch_mapper.get() + params.cooldown_epochs,
+            ERROR_REBALANCE_COOLDOWN
+        );
+        last_epoch_mapper.set(current_epoch);
+
+        require!(
+            self.is_valid_undelegation_contract_relaxed(&from_data_mapper.get(), &egld_amount)
+                && self.is_valid_delegation_contract(&to_data, &egld_amount, &OptionalValue::None, false),
+            ERROR_INVALID_DELEGATION_CONTRACT
+        );
+
+        let max_egld_amount =
+            self.get_max_rebalance_amount(delegation_contract_from.clone(), delegation_contract_to.clone());
+        require!(egld_amount <= max_egld_amount, ERROR_REBALANCE_ABOVE_TARGET);
+
+        self.update_delegation_contract_data(&delegation_contract_from, |data| {
+            data.total_delegated -= &egld_amount;
+            data.pending_to_undelegate += &egld_amount;
+        });
+
+        self.pending_rebalance().update(|amount| *amount += &egld_amount);
+        self.incoming_rebalance(&delegation_contract_to)
+            .update(|amount| *amount += &egld_amount);
+        self.increase_operator_allocation(&delegation_contract_to, &egld_amount);
+
+        let unbond_epoch = current_epoch + self.unbond_period().get();
+
+        let rebalance_id = self.get_next_rebalance_id();
+        let rebalance = Rebalance {
+            id: rebalance_id,
+            attributes: UndelegateAttributes {
+                delegation_contract: delegation_contract_from,
+                egld_amount,
+                shares: BigUint::zero(),
+                undelegate_epoch: current_epoch,
+                unbond_epoch,
+            },
+            delegation_contract_to,
+        };
+
+        self.rebalances(rebalance_id).set(&rebalance);
+
+        let caller = self.blockchain().get_caller();
+        let contract_data = from_data_mapper.get();
+        self.rebalance_event(&caller, &rebalance, &contract_data);
+
+        rebalance_id
+    }
+
+    /// Completes a rebalance once the unbond period has passed, such that its EGLD becomes pending to be delegated to
+    /// the destination Delegation smart contract using the `delegatePendingAmount` public endpoint. Similarly to
+    /// `withdraw`, the public endpoint `withdrawFrom` should have been called prior to using this function.
+    ///
+    /// # Arguments
+    ///
+    /// - `rebalance_id` - the rebalance identifier
+    ///
+    /// # Notes
+    ///
+    /// - This endpoint can be called by anyone.
+    /// - If the destination Delegation smart contract cannot receive the delegation anymore, e.g. because it has been
+    ///   blacklisted in the meantime, a new one is selected by the delegation algorithm, avoiding the source one.
+    ///
+    #[endpoint(completeRebalance)]
+    fn complete_rebalance(&self, rebalance_id: u64) {
+        self.require_not_paused(Operation::Withdraw);
+
+        let rebalance_mapper = self.rebalances(rebalance_id);
+        require!(!rebalance_mapper.is_empty(), ERROR_UNEXPECTED_REBALANCE_ID);
+
+        let rebalance = rebalance_mapper.take();
+        self.withdraw_internal(&rebalance.attributes);
+
+        let egld_amount = &rebalance.attributes.egld_amount;
+        self.pending_rebalance().update(|amount| *amount -= egld_amount);
+        self.incoming_rebalance(&rebalance.delegation_contract_to)
+            .update(|amount| *amount -= egld_amount);
+        self.decrease_operator_allocation(&rebalance.delegation_contract_to, egld_amount);
+
+        let to_data = self.delegation_contract_data(&rebalance.delegation_contract_to).get();
+        let delegation_contract = if !to_data.blacklisted
+            && self.is_valid_delegation_contract(&to_data, egld_amount, &OptionalValue::None, false)
+        {
+            rebalance.delegation_contract_to
+        } else {
+            self.get_delegation_contract_for_delegate(
+                egld_amount,
+                &OptionalValue::Some(rebalance.attributes.delegation_contract.clone()),
+                false,
+            )
+        };
+
+        let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
+        self.update_delegation_contract_data(&delegation_contract, |data| {
+            data.pending_to_delegate += egld_amount;
+        });
+
+        let caller = self.blockchain().get_caller();
+        let contract_data = contract_data_mapper.get();
+        self.complete_rebalance_event(&caller, rebalance_id, &contract_data);
+    }
+
+    /// Returns the maximum amount of EGLD that can be moved from a Delegation smart contract to another one using
+    /// `rebalance`, i.e. the minimum between the excess of the source over its target allocation and the shortfall of
+    /// the destination. It is zero if the excess is not above the minimum deviation or if the rebalance parameters have
+    /// not been set.
+    ///
+    /// # Arguments
+    ///
+    /// - `delegation_contract_from` - the Delegation smart contract to undelegate from
+    /// - `delegation_contract_to` - the Delegation smart contract to delegate to
+    ///
+    #[view(getMaxRebalanceAmount)]
+    fn get_max_rebalance_amount(
+        &self,
+        delegation_contract_from: ManagedAddress,
+        delegation_contract_to: ManagedAddress,
+    ) -> BigUint {
+        let params_mapper = self.rebalance_params();
+        if params_mapper.is_empty() {
+            return BigUint::zero();
+        }
+
+        let from_allocation = self.get_allocation(delegation_contract_from.clone());
+        let from_target = self.get_target_allocation(delegation_contract_from);
+        let min_excess = &from_target * params_mapper.get().min_deviation / BPS;
+        if from_allocation <= &from_target + &min_excess {
+            return BigUint::zero();
+        }
+
+        let to_allocation = self.get_allocation(delegation_contract_to.clone());
+        let to_target = self.get_target_allocation(delegation_contract_to);
+        if to_target <= to_allocation {
+            return BigUint::zero();
+        }
+
+        core::cmp::min(from_allocation - from_target, to_target - to_allocation)
+    }
+
+    /// Returns the amount of EGLD that should be allocated to a given Delegation smart contract, which is proportional
+    /// to its delegation score. All Delegation smart contracts in the list get the same allocation if all their
+    /// delegation scores are zero, while Delegation smart contracts out of the list get none.
+    ///
+    /// # Arguments
+    ///
+    /// - `delegation_contract` - the Delegation smart contract address
+    ///
+    /// # Notes
+    ///
+    /// - The EGLD to be allocated is the cash reserve excluding the liquidity buffer and its refills. Penalties are
+    ///   included, since they are delegated again eventually.
+    ///
+    #[view(getTargetAllocation)]
+    fn get_target_allocation(&self, delegation_contract: ManagedAddress) -> BigUint {
+        let position_mapper = self.list_position(&delegation_contract);
+        if position_mapper.is_empty() {
+            return BigUint::zero();
+        }
+
+        let total_allocation =
+            self.cash_reserve().get() - self.liquidity_buffer().get() - self.pending_buffer_refill().get();
+
+        let total_score = self.total_delegation_score().get();
+        if total_score == 0 {
+            return total_allocation / self.delegation_contracts_list().len() as u64;
+        }
+
+        total_allocation * position_mapper.get().delegation_score / total_score
+    }
+
+    fn get_next_rebalance_id(&self) -> u64 {
+        let next_rebalance_id = self.next_rebalance_id();
+        let rebalance_id = next_rebalance_id.get();
+        next_rebalance_id.set(rebalance_id + 1);
+        rebalance_id
+    }
+}
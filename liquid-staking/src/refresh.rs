@@ -0,0 +1,136 @@
+multiversx_sc::imports!();
+use super::{common, constants::*, delegation, errors::*, events, model::*, proxies, score, selection, storage};
+
+/// The position of the service fee within the Delegation smart contract config
+const CONFIG_SERVICE_FEE_INDEX: usize = 1;
+
+/// The position of the maximum delegation cap within the Delegation smart contract config
+const CONFIG_MAX_DELEGATION_CAP_INDEX: usize = 2;
+
+/// The position of the flag indicating whether the delegation cap is enabled within the Delegation smart contract config
+const CONFIG_WITH_DELEGATION_CAP_INDEX: usize = 5;
+
+#[multiversx_sc::module]
+pub trait RefreshModule:
+    admin::AdminModule
+    + common::CommonModule
+    + delegation::DelegationModule
+    + events::EventsModule
+    + proxies::ProxyModule
+    + score::ScoreModule
+    + selection::SelectionModule
+    + storage::StorageModule
+{
+    /// Refreshes the service fee, the cap and the total value locked of a given Delegation smart contract by querying
+    /// the Delegation smart contract itself, which removes the need to trust an off-chain data source for them. Each
+    /// query is performed by its own asynchronous call and the data is updated in its callback, moving the Delegation
+    /// smart contract to its new position in the list if its delegation score changes.
+    ///
+    /// # Arguments
+    ///
+    /// - `delegation_contract` - the Delegation smart contract address
+    ///
+    /// # Notes
+    ///
+    /// - This endpoint can be called by anyone.
+    /// - The APR and the number of nodes are not exposed by the Delegation smart contract, so they are still updated by
+    ///   the data manager or the data reporters.
+    /// - The data bounds do not apply, since the data is read from the Delegation smart contract.
+    ///
+    #[endpoint(refreshDelegationContractData)]
+    fn refresh_delegation_contract_data(&self, delegation_contract: ManagedAddress) {
+        let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
+        require!(!contract_data_mapper.is_empty(), ERROR_UNEXPECTED_DELEGATION_CONTRACT);
+        require!(
+            !contract_data_mapper.get().blacklisted,
+            ERROR_BLACKLISTED_DELEGATION_CONTRACT
+        );
+
+        // makes sure there is gas for both asynchronous calls and their callbacks
+        require!(
+            self.blockchain().get_gas_left() > 2 * (MIN_GAS_FOR_ASYNC_CALL + MIN_GAS_FOR_CALLBACK),
+            ERROR_INSUFFICIENT_GAS
+        );
+
+        let callback = self.callbacks().refresh_contract_config_cb(&delegation_contract);
+        self.get_contract_config_promise(delegation_contract.clone(), MIN_GAS_FOR_ASYNC_CALL, callback);
+
+        let callback = self.callbacks().refresh_total_active_stake_cb(&delegation_contract);
+        self.get_total_active_stake_promise(delegation_contract, MIN_GAS_FOR_ASYNC_CALL, callback);
+    }
+
+    #[promises_callback]
+    fn refresh_contract_config_cb(
+        &self,
+        delegation_contract: &ManagedAddress,
+        #[call_result] result: ManagedAsyncCallResult<MultiValueEncoded<ManagedBuffer>>,
+    ) {
+        match result {
+            ManagedAsyncCallResult::Ok(config) => {
+                let config = config.to_vec();
+                require!(
+                    config.len() > CONFIG_WITH_DELEGATION_CAP_INDEX,
+                    ERROR_INVALID_CONTRACT_CONFIG
+                );
+
+                let service_fee = BigUint::from_bytes_be_buffer(&config.get(CONFIG_SERVICE_FEE_INDEX));
+                let max_delegation_cap = BigUint::from_bytes_be_buffer(&config.get(CONFIG_MAX_DELEGATION_CAP_INDEX));
+                let with_delegation_cap = *config.get(CONFIG_WITH_DELEGATION_CAP_INDEX) == b"true";
+
+                // a zero cap means the Delegation smart contract is uncapped
+                let cap = if with_delegation_cap && max_delegation_cap > 0 {
+                    Some(max_delegation_cap)
+                } else {
+                    None
+                };
+
+                self.update_refreshed_contract_data(delegation_contract, |data| {
+                    data.service_fee = service_fee;
+                    data.cap = cap;
+                });
+            },
+            ManagedAsyncCallResult::Err(err) => {
+                self.async_call_error_event(err.err_code, err.err_msg);
+            },
+        }
+    }
+
+    #[promises_callback]
+    fn refresh_total_active_stake_cb(
+        &self,
+        delegation_contract: &ManagedAddress,
+        #[call_result] result: ManagedAsyncCallResult<BigUint>,
+    ) {
+        match result {
+            ManagedAsyncCallResult::Ok(total_active_stake) => {
+                self.update_refreshed_contract_data(delegation_contract, |data| {
+                    data.total_value_locked = total_active_stake;
+                });
+            },
+            ManagedAsyncCallResult::Err(err) => {
+                self.async_call_error_event(err.err_code, err.err_msg);
+            },
+        }
+    }
+
+    /// Updates the data of a Delegation smart contract with the refreshed values and recomputes its delegation score,
+    /// unless it has been blacklisted in the meantime.
+    ///
+    fn update_refreshed_contract_data<F>(&self, delegation_contract: &ManagedAddress, update: F)
+    where
+        F: FnOnce(&mut DelegationContractData<Self::Api>),
+    {
+        let contract_data_mapper = self.delegation_contract_data(delegation_contract);
+        let old_contract_data = contract_data_mapper.get();
+        if old_contract_data.blacklisted {
+            return;
+        }
+
+        self.update_delegation_contract_data(delegation_contract, update);
+        self.update_operator_data(&old_contract_data, &contract_data_mapper.get());
+        self.update_delegation_score(delegation_contract);
+
+        let contract_data = contract_data_mapper.get();
+        self.refresh_delegation_contract_data_event(&contract_data);
+    }
+}
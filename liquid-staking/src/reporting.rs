@@ -0,0 +1,236 @@
+multiversx_sc::imports!();
+use super::{common, delegation, errors::*, events, governance, model::*, proxies, score, selection, storage};
+
+#[multiversx_sc::module]
+pub trait ReportingModule:
+    admin::AdminModule
+    + common::CommonModule
+    + delegation::DelegationModule
+    + events::EventsModule
+    + governance::GovernanceModule
+    + proxies::ProxyModule
+    + score::ScoreModule
+    + selection::SelectionModule
+    + storage::StorageModule
+{
+    /// Submits the data for a given Staking Provider Delegation smart contract. Once the data reporters quorum is
+    /// reached within the same epoch, the Delegation smart contract data is updated using the median of each reported
+    /// value and the reports are cleared.
+    ///
+    /// # Arguments
+    ///
+    /// - `delegation_contract` - the Delegation smart contract address
+    /// - `total_value_locked` - the liquidity locked at the Delegation smart contract
+    /// - `nr_nodes` - the number of validator nodes
+    /// - `apr` - the current APR for the validator
+    /// - `service_fee` - the service fee being charged by the validator
+    /// - `opt_cap` - the maximum amount that can be locked at the Delegation smart contract (uncapped if `None`)
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by a data reporter
+    /// - submitting again within the same epoch replaces the previous report of the caller
+    /// - reports from previous epochs are discarded
+    /// - reports deviating from the median of the submitted reports beyond the maximum data report deviation are not
+    ///   taken into account; the data is only updated once the remaining reports reach the quorum
+    /// - with an even quorum, the lower median is used
+    ///
+    #[endpoint(submitDelegationContractData)]
+    fn submit_delegation_contract_data(
+        &self,
+        delegation_contract: ManagedAddress,
+        total_value_locked: BigUint,
+        nr_nodes: u64,
+        apr: BigUint,
+        service_fee: BigUint,
+        opt_cap: OptionalValue<BigUint>,
+    ) {
+        let caller = self.blockchain().get_caller();
+        require!(self.data_reporters().contains(&caller), ERROR_ONLY_DATA_REPORTER);
+
+        let quorum = self.data_reporters_quorum().get();
+        require!(quorum > 0, ERROR_DATA_REPORTERS_QUORUM_UNSET);
+
+        let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
+        require!(!contract_data_mapper.is_empty(), ERROR_UNEXPECTED_DELEGATION_CONTRACT);
+
+        let contract_data = contract_data_mapper.get();
+        require!(!contract_data.blacklisted, ERROR_BLACKLISTED_DELEGATION_CONTRACT);
+
+        let cap = opt_cap.into_option();
+        if let Some(cap) = &cap {
+            require!(cap >= &total_value_locked, ERROR_DELEGATION_CAP);
+        }
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        let mut reports = self.data_reports(&delegation_contract);
+
+        // all reports share the same epoch, hence checking any of them is enough
+        if let Some(report) = reports.values().next() {
+            if report.epoch != current_epoch {
+                reports.clear();
+            }
+        }
+
+        let report = DataReport {
+            epoch: current_epoch,
+            total_value_locked,
+            nr_nodes,
+            apr,
+            service_fee,
+            cap,
+        };
+
+        reports.insert(caller.clone(), report.clone());
+
+        self.submit_data_report_event(&caller, &delegation_contract, &report);
+
+        self.try_apply_data_reports(&delegation_contract, quorum);
+    }
+
+    /// Updates the Delegation smart contract data with the median of the reports if the quorum has been reached. Reports
+    /// submitted by removed data reporters are not taken into account, nor are the reports deviating from the median of
+    /// all reports beyond the maximum data report deviation.
+    ///
+    fn try_apply_data_reports(&self, delegation_contract: &ManagedAddress, quorum: usize) {
+        let median_report = match self.median_data_report(delegation_contract, quorum, None) {
+            Some(median_report) => median_report,
+            None => return,
+        };
+
+        let max_deviation = self.max_data_report_deviation().get();
+        let median_report = if max_deviation > 0 {
+            // outliers are left out and the remaining reports must still reach the quorum
+            match self.median_data_report(delegation_contract, quorum, Some((&median_report, max_deviation))) {
+                Some(median_report) => median_report,
+                None => return,
+            }
+        } else {
+            median_report
+        };
+
+        self.data_reports(delegation_contract).clear();
+
+        self.change_delegation_contract_params_internal(
+            delegation_contract,
+            median_report.total_value_locked,
+            median_report.nr_nodes,
+            median_report.apr,
+            median_report.service_fee,
+            median_report.cap,
+        );
+    }
+
+    /// Returns the median of the reports submitted by the current data reporters for a given Delegation smart contract,
+    /// or `None` if the quorum has not been reached. When a reference report and a maximum deviation are given, only the
+    /// reports within that deviation from the reference are taken into account.
+    ///
+    fn median_data_report(
+        &self,
+        delegation_contract: &ManagedAddress,
+        quorum: usize,
+        opt_reference: Option<(&DataReport<Self::Api>, u64)>,
+    ) -> Option<DataReport<Self::Api>> {
+        let reporters = self.data_reporters();
+
+        let mut tvls = ManagedVec::<Self::Api, BigUint>::new();
+        let mut nr_nodes = ManagedVec::<Self::Api, BigUint>::new();
+        let mut aprs = ManagedVec::<Self::Api, BigUint>::new();
+        let mut service_fees = ManagedVec::<Self::Api, BigUint>::new();
+        let mut caps = ManagedVec::<Self::Api, BigUint>::new();
+        let mut epoch = 0;
+
+        for (reporter, report) in self.data_reports(delegation_contract).iter() {
+            if !reporters.contains(&reporter) {
+                continue;
+            }
+            if let Some((reference, max_deviation)) = opt_reference {
+                let is_consistent =
+                    self.is_within_deviation(&reference.total_value_locked, &report.total_value_locked, max_deviation)
+                        && self.is_within_deviation(
+                            &BigUint::from(reference.nr_nodes),
+                            &BigUint::from(report.nr_nodes),
+                            max_deviation,
+                        )
+                        && self.is_within_deviation(&reference.apr, &report.apr, max_deviation)
+                        && self.is_within_deviation(&reference.service_fee, &report.service_fee, max_deviation);
+                if !is_consistent {
+                    continue;
+                }
+            }
+            epoch = report.epoch;
+            tvls.push(report.total_value_locked);
+            nr_nodes.push(BigUint::from(report.nr_nodes));
+            aprs.push(report.apr);
+            service_fees.push(report.service_fee);
+            if let Some(cap) = report.cap {
+                caps.push(cap);
+            }
+        }
+
+        let nr_reports = tvls.len();
+        if nr_reports < quorum {
+            return None;
+        }
+
+        let index = (nr_reports - 1) / 2;
+
+        // uncapped reports are ranked above any capped one
+        let cap = if index < caps.len() {
+            Some(self.kth_smallest(&caps, index))
+        } else {
+            None
+        };
+
+        Some(DataReport {
+            epoch,
+            total_value_locked: self.kth_smallest(&tvls, index),
+            nr_nodes: self
+                .kth_smallest(&nr_nodes, index)
+                .to_u64()
+                .unwrap_or_else(|| sc_panic!(ERROR_INVALID_NR_NODES)),
+            apr: self.kth_smallest(&aprs, index),
+            service_fee: self.kth_smallest(&service_fees, index),
+            cap,
+        })
+    }
+
+    /// Returns the k-th smallest value (starting from zero) of a small list of values
+    ///
+    fn kth_smallest(&self, values: &ManagedVec<BigUint>, k: usize) -> BigUint {
+        for value in values.iter() {
+            let mut nr_smaller = 0;
+            let mut nr_smaller_or_equal = 0;
+            for other in values.iter() {
+                if *other < *value {
+                    nr_smaller += 1;
+                }
+                if *other <= *value {
+                    nr_smaller_or_equal += 1;
+                }
+            }
+            if nr_smaller <= k && k < nr_smaller_or_equal {
+                return value.clone_value();
+            }
+        }
+        sc_panic!(ERROR_INVALID_DATA_REPORTERS_QUORUM)
+    }
+
+    /// Returns the data reports submitted for a given Delegation smart contract, by reporter.
+    ///
+    /// # Arguments
+    ///
+    /// - `delegation_contract` - the Delegation smart contract address
+    ///
+    #[view(getDataReports)]
+    fn get_data_reports(
+        &self,
+        delegation_contract: ManagedAddress,
+    ) -> MultiValueEncoded<MultiValue2<ManagedAddress, DataReport<Self::Api>>> {
+        let mut reports = MultiValueEncoded::new();
+        for (reporter, report) in self.data_reports(&delegation_contract).iter() {
+            reports.push((reporter, report).into());
+        }
+        reports
+    }
+}
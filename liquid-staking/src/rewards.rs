@@ -1,6 +1,8 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
-use super::{common, constants::*, delegation, errors::*, events, proxies, score, selection, storage};
+use super::{
+    common, constants::*, delegation, errors::*, events, model::Operation, proxies, score, selection, storage,
+};
 
 #[multiversx_sc::module]
 pub trait RewardsModule:
@@ -21,7 +23,7 @@ pub trait RewardsModule:
     ///
     #[endpoint(claimRewardsFrom)]
     fn claim_rewards_from(&self, delegation_contract: ManagedAddress) {
-        self.require_active_state();
+        self.require_not_paused(Operation::Rewards);
 
         let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
         require!(!contract_data_mapper.is_empty(), ERROR_UNEXPECTED_DELEGATION_CONTRACT);
@@ -43,6 +45,18 @@ pub trait RewardsModule:
         caller: &ManagedAddress,
         delegation_contract: &ManagedAddress,
         #[call_result] result: ManagedAsyncCallResult<()>,
+    ) {
+        self.claim_rewards_from_result(caller, delegation_contract, result);
+    }
+
+    /// Splits the claimed rewards between the rewards reserve and the protocol reserve once the claim has finished, or
+    /// logs the error if it failed.
+    ///
+    fn claim_rewards_from_result(
+        &self,
+        caller: &ManagedAddress,
+        delegation_contract: &ManagedAddress,
+        result: ManagedAsyncCallResult<()>,
     ) {
         match result {
             ManagedAsyncCallResult::Ok(()) => {
@@ -94,7 +108,7 @@ pub trait RewardsModule:
     ///
     #[endpoint(delegateRewards)]
     fn delegate_rewards(&self, opt_egld_amount: OptionalValue<BigUint>) {
-        self.require_active_state();
+        self.require_not_paused(Operation::Rewards);
 
         let caller = self.blockchain().get_caller();
 
@@ -114,7 +128,7 @@ pub trait RewardsModule:
 
         self.rewards_reserve().update(|amount| *amount -= &egld_amount);
 
-        let delegation_contract = self.get_delegation_contract_for_delegate(&egld_amount, &OptionalValue::None);
+        let delegation_contract = self.get_delegation_contract_for_delegate(&egld_amount, &OptionalValue::None, true);
 
         let gas_for_async_call = self.get_gas_for_async_call();
         let callback = self
@@ -130,20 +144,34 @@ pub trait RewardsModule:
         delegation_contract: &ManagedAddress,
         egld_amount: &BigUint,
         #[call_result] result: ManagedAsyncCallResult<()>,
+    ) {
+        self.delegate_rewards_result(caller, delegation_contract, egld_amount, result);
+    }
+
+    /// Moves the delegated rewards into the cash reserve once the delegation has finished, or restores the rewards
+    /// reserve and marks the Delegation smart contract as outdated if it failed.
+    ///
+    fn delegate_rewards_result(
+        &self,
+        caller: &ManagedAddress,
+        delegation_contract: &ManagedAddress,
+        egld_amount: &BigUint,
+        result: ManagedAsyncCallResult<()>,
     ) {
         match result {
             ManagedAsyncCallResult::Ok(()) => {
                 let contract_data_mapper = self.delegation_contract_data(delegation_contract);
-                contract_data_mapper.update(|data| {
+                self.update_delegation_contract_data(delegation_contract, |data| {
                     data.total_delegated += egld_amount;
                 });
                 self.cash_reserve().update(|amount| *amount += egld_amount);
+                self.stream_rewards(egld_amount);
                 let contract_data = contract_data_mapper.get();
                 self.delegate_rewards_event(caller, delegation_contract, egld_amount, &contract_data)
             },
             ManagedAsyncCallResult::Err(err) => {
                 self.rewards_reserve().update(|amount| *amount += egld_amount);
-                self.delegation_contract_data(delegation_contract).update(|data| {
+                self.update_delegation_contract_data(delegation_contract, |data| {
                     data.outdated = true;
                 });
                 self.outdated_event(delegation_contract);
@@ -151,4 +179,90 @@ pub trait RewardsModule:
             },
         }
     }
+
+    /// Claims rewards from every Delegation smart contract in the list that has not been claimed from in the current
+    /// epoch and delegates the current rewards reserve based on the current configuration of the delegation algorithm,
+    /// all within a single transaction. Each claim and the delegation are performed by their own asynchronous call and
+    /// tracked by their own callback.
+    ///
+    /// # Notes
+    ///
+    /// - This endpoint can be called by anyone.
+    /// - The rewards claimed by a call are not delegated by that same call: they only reach the rewards reserve in
+    ///   their callbacks, which cannot perform further asynchronous calls. They are delegated by a later call to this
+    ///   endpoint or to `delegateRewards`.
+    /// - The rewards reserve is only delegated if it is at least the minimum delegation amount.
+    /// - Delegation smart contracts are visited in the list order while there is enough gas left for another
+    ///   asynchronous call and its callback. The remaining ones can be claimed from in a later call.
+    ///
+    #[endpoint(claimAllAndDelegateRewards)]
+    fn claim_all_and_delegate_rewards(&self) {
+        self.require_not_paused(Operation::Rewards);
+
+        // makes sure there is gas for at least one asynchronous call
+        self.get_gas_for_async_call();
+
+        let caller = self.blockchain().get_caller();
+        let mut num_calls = 0usize;
+
+        let rewards_reserve = self.rewards_reserve().get();
+        if rewards_reserve >= MIN_DELEGATION_AMOUNT {
+            self.rewards_reserve().clear();
+
+            let delegation_contract =
+                self.get_delegation_contract_for_delegate(&rewards_reserve, &OptionalValue::None, true);
+            let callback =
+                self.callbacks()
+                    .claim_all_delegate_rewards_cb(&caller, &delegation_contract, &rewards_reserve);
+            self.delegate_to_delegation_contract_promise(
+                delegation_contract,
+                rewards_reserve,
+                MIN_GAS_FOR_ASYNC_CALL,
+                callback,
+            );
+            num_calls += 1;
+        }
+
+        for node in self.delegation_contracts_list().iter() {
+            let delegation_contract = node.into_value();
+            if self.has_claimed_rewards(&delegation_contract) {
+                continue;
+            }
+
+            let gas_for_async_call = match self.get_gas_for_batched_async_call() {
+                Some(gas) => gas,
+                None => break,
+            };
+
+            let callback = self.callbacks().claim_all_rewards_cb(&caller, &delegation_contract);
+            self.claim_rewards_from_delegation_contract_promise(delegation_contract, gas_for_async_call, callback);
+            num_calls += 1;
+        }
+
+        require!(num_calls > 0, ERROR_NOTHING_TO_CLAIM_OR_DELEGATE);
+    }
+
+    #[payable("EGLD")]
+    #[promises_callback]
+    fn claim_all_rewards_cb(
+        &self,
+        caller: &ManagedAddress,
+        delegation_contract: &ManagedAddress,
+        #[call_result] result: ManagedAsyncCallResult<()>,
+    ) {
+        self.claim_rewards_from_result(caller, delegation_contract, result);
+    }
+
+    // the EGLD is sent back along with the callback if the delegation fails
+    #[payable("EGLD")]
+    #[promises_callback]
+    fn claim_all_delegate_rewards_cb(
+        &self,
+        caller: &ManagedAddress,
+        delegation_contract: &ManagedAddress,
+        egld_amount: &BigUint,
+        #[call_result] result: ManagedAsyncCallResult<()>,
+    ) {
+        self.delegate_rewards_result(caller, delegation_contract, egld_amount, result);
+    }
 }
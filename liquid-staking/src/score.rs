@@ -5,14 +5,34 @@ use super::{common, constants::*, events, model::*, proxies, storage};
 pub trait ScoreModule:
     admin::AdminModule + common::CommonModule + events::EventsModule + proxies::ProxyModule + storage::StorageModule
 {
+    /// Computes the delegation score of a given Delegation smart contract. Delegation smart contracts run by the same
+    /// operator are scored using the total value locked and the number of nodes of the operator, such that splitting
+    /// the stake among many Delegation smart contracts does not yield higher scores.
+    ///
     fn compute_delegation_score(&self, contract_data: &DelegationContractData<Self::Api>) -> BigUint {
-        self.compute_delegation_score_internal(&contract_data.total_value_locked, &contract_data.apr)
+        let operator_mapper = self.operator_id(&contract_data.contract);
+        if operator_mapper.is_empty() {
+            return self.compute_delegation_score_internal(
+                contract_data,
+                &contract_data.total_value_locked,
+                contract_data.nr_nodes,
+            );
+        }
+
+        let operator_data = self.operator_data(operator_mapper.get()).get();
+        self.compute_delegation_score_internal(contract_data, &operator_data.total_value_locked, operator_data.nr_nodes)
     }
 
-    /// The Delegation Score is given by the weighted average between the Total Value Locked score and the Annual
-    /// Percentage Rate score.
+    /// The Delegation Score is given by the score of a single Delegation smart contract parameter, the weighted
+    /// average between the Total Value Locked score and the Annual Percentage Rate score, or the weighted sum of the
+    /// scores of the delegation score terms.
     ///
-    fn compute_delegation_score_internal(&self, total_value_locked: &BigUint, apr: &BigUint) -> BigUint {
+    fn compute_delegation_score_internal(
+        &self,
+        contract_data: &DelegationContractData<Self::Api>,
+        total_value_locked: &BigUint,
+        nr_nodes: u64,
+    ) -> BigUint {
         let model = self.delegation_score_model().get();
         let DelegationScoreModel {
             method,
@@ -21,31 +41,171 @@ pub trait ScoreModule:
             min_apr,
             max_apr,
             omega,
+            curve,
         } = model;
 
+        let apr = &contract_data.apr;
         match method {
-            DelegationScoreMethod::Tvl => self.compute_tvl_score(total_value_locked, &min_tvl, &max_tvl),
-            DelegationScoreMethod::Apr => self.compute_apr_score(apr, &min_apr, &max_apr),
+            DelegationScoreMethod::Tvl => self.compute_tvl_score(total_value_locked, &min_tvl, &max_tvl, &curve),
+            DelegationScoreMethod::Apr => self.compute_apr_score(apr, &min_apr, &max_apr, &curve),
             DelegationScoreMethod::Mixed => {
                 let bps = BigUint::from(BPS);
-                let tvl_score = self.compute_tvl_score(total_value_locked, &min_tvl, &max_tvl);
-                let apr_score = self.compute_apr_score(apr, &min_apr, &max_apr);
+                let tvl_score = self.compute_tvl_score(total_value_locked, &min_tvl, &max_tvl, &curve);
+                let apr_score = self.compute_apr_score(apr, &min_apr, &max_apr, &curve);
                 (&omega * &tvl_score + (&bps - &omega) * &apr_score) / &bps
             },
+            DelegationScoreMethod::TvlPerNode => {
+                let domain = self.tvl_per_node_domain().get();
+                let tvl_per_node = self.compute_tvl_per_node(total_value_locked, nr_nodes);
+                self.compute_tvl_score(&tvl_per_node, &domain.min, &domain.max, &curve)
+            },
+            DelegationScoreMethod::ServiceFee => self.norm_curve_clamp(
+                &contract_data.service_fee,
+                &BigUint::zero(),
+                &BigUint::from(BPS),
+                true,
+                &curve,
+            ),
+            DelegationScoreMethod::Weighted => {
+                let mut score = BigUint::zero();
+                for term in self.delegation_score_terms().get().iter() {
+                    let value =
+                        self.get_delegation_score_factor(term.factor, contract_data, total_value_locked, nr_nodes);
+                    score += self.norm_curve_clamp(&value, &term.min, &term.max, term.down, &curve) * term.weight;
+                }
+                score / BPS
+            },
+        }
+    }
+
+    /// Returns the value of a delegation score factor for a given Delegation smart contract
+    ///
+    fn get_delegation_score_factor(
+        &self,
+        factor: DelegationScoreFactor,
+        contract_data: &DelegationContractData<Self::Api>,
+        total_value_locked: &BigUint,
+        nr_nodes: u64,
+    ) -> BigUint {
+        match factor {
+            DelegationScoreFactor::Tvl => total_value_locked.clone(),
+            DelegationScoreFactor::Apr => contract_data.apr.clone(),
+            DelegationScoreFactor::NrNodes => BigUint::from(nr_nodes),
+            DelegationScoreFactor::TvlPerNode => self.compute_tvl_per_node(total_value_locked, nr_nodes),
+            DelegationScoreFactor::ServiceFee => contract_data.service_fee.clone(),
         }
     }
 
+    /// Computes the Total Value Locked per node, where Delegation smart contracts without nodes count as having one
+    ///
+    fn compute_tvl_per_node(&self, total_value_locked: &BigUint, nr_nodes: u64) -> BigUint {
+        total_value_locked / core::cmp::max(nr_nodes, 1)
+    }
+
     /// Computes the Total Value Locked (TVL) score, in which lower TVLs yield higher scores. The score is capped at one
     /// at low TVLs and floored at zero at high TVLs.
     ///
-    fn compute_tvl_score(&self, total_value_locked: &BigUint, min_tvl: &BigUint, max_tvl: &BigUint) -> BigUint {
-        self.norm_linear_clamp(total_value_locked, min_tvl, max_tvl, true)
+    fn compute_tvl_score(
+        &self,
+        total_value_locked: &BigUint,
+        min_tvl: &BigUint,
+        max_tvl: &BigUint,
+        curve: &ScoreCurve<Self::Api>,
+    ) -> BigUint {
+        self.norm_curve_clamp(total_value_locked, min_tvl, max_tvl, true, curve)
     }
 
     /// Computes the Annual Percentage Rate (APR) score, in which higher APRs yield higher scores. The score is capped
     /// at one at high APRs and floored at zero at low TVLs.
     ///
-    fn compute_apr_score(&self, apr: &BigUint, min_apr: &BigUint, max_apr: &BigUint) -> BigUint {
-        self.norm_linear_clamp(apr, min_apr, max_apr, false)
+    fn compute_apr_score(
+        &self,
+        apr: &BigUint,
+        min_apr: &BigUint,
+        max_apr: &BigUint,
+        curve: &ScoreCurve<Self::Api>,
+    ) -> BigUint {
+        self.norm_curve_clamp(apr, min_apr, max_apr, false, curve)
+    }
+
+    /// Same as `norm_linear_clamp`, but maps the normalized value through a given curve before the slope is inverted
+    ///
+    fn norm_curve_clamp(
+        &self,
+        x: &BigUint,
+        min: &BigUint,
+        max: &BigUint,
+        down: bool,
+        curve: &ScoreCurve<Self::Api>,
+    ) -> BigUint {
+        let normalized = self.norm_linear_clamp(x, min, max, false).to_u64().unwrap_or(BPS);
+        let y = self.apply_score_curve(curve, normalized);
+        if down {
+            BigUint::from(BPS - y)
+        } else {
+            BigUint::from(y)
+        }
+    }
+
+    /// Tries to migrate the Delegation Score model parameters stored before the introduction of score curves, which
+    /// keep scoring linearly
+    ///
+    fn try_migrate_delegation_score_model(&self) {
+        let raw_model = self.raw_delegation_score_model().get();
+        if let Ok(model) = LegacyDelegationScoreModel::<Self::Api>::top_decode(raw_model) {
+            self.delegation_score_model().set(DelegationScoreModel {
+                method: model.method,
+                min_tvl: model.min_tvl,
+                max_tvl: model.max_tvl,
+                min_apr: model.min_apr,
+                max_apr: model.max_apr,
+                omega: model.omega,
+                curve: ScoreCurve::Linear,
+            });
+        }
+    }
+
+    /// Maps a normalized value through a given curve, where both are in basis points
+    ///
+    fn apply_score_curve(&self, curve: &ScoreCurve<Self::Api>, x: u64) -> u64 {
+        match curve {
+            ScoreCurve::Linear => x,
+            ScoreCurve::PiecewiseLinear { breakpoints } => {
+                let mut previous = ScoreCurvePoint { x: 0, y: 0 };
+                for point in breakpoints.iter() {
+                    if x <= point.x {
+                        return interpolate(&previous, &point, x);
+                    }
+                    previous = point;
+                }
+                interpolate(&previous, &ScoreCurvePoint { x: BPS, y: BPS }, x)
+            },
+            ScoreCurve::Logarithmic { curvature } => concave_curve(x, *curvature),
+            ScoreCurve::Sigmoid { midpoint, curvature } => {
+                let (midpoint, curvature) = (*midpoint, *curvature);
+                if x <= midpoint {
+                    midpoint * convex_curve(x * BPS / midpoint, curvature) / BPS
+                } else {
+                    let upper = BPS - midpoint;
+                    midpoint + upper * concave_curve((x - midpoint) * BPS / upper, curvature) / BPS
+                }
+            },
+        }
     }
 }
+
+/// Interpolates linearly between two points of a curve, where `from.x < x <= to.x`
+fn interpolate(from: &ScoreCurvePoint, to: &ScoreCurvePoint, x: u64) -> u64 {
+    from.y + (to.y - from.y) * (x - from.x) / (to.x - from.x)
+}
+
+/// A concave curve from zero to one (in bps) with diminishing returns, which approximates a logarithm
+fn concave_curve(x: u64, curvature: u64) -> u64 {
+    let (x, curvature) = (x as u128, curvature as u128);
+    (x * (BPS as u128 + curvature) / (x + curvature)) as u64
+}
+
+/// A convex curve from zero to one (in bps), given by the concave curve mirrored
+fn convex_curve(x: u64, curvature: u64) -> u64 {
+    BPS - concave_curve(BPS - x, curvature)
+}
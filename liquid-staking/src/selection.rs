@@ -17,14 +17,17 @@ pub trait SelectionModule:
     ///
     /// - `egld_amount` - the EGLD amount being delegated
     /// - `opt_address` - avoid this Delegation smart contract to be selected
+    /// - `grows_cash_reserve` - whether the delegated amount is added to the cash reserve, i.e. it is not an internal
+    ///   move of already delegated EGLD
     ///
     fn get_delegation_contract_for_delegate(
         &self,
         egld_amount: &BigUint,
         opt_address: &OptionalValue<ManagedAddress>,
+        grows_cash_reserve: bool,
     ) -> ManagedAddress<Self::Api> {
         // get the best candidate as a node from the linked list
-        let best_node = self.get_max_delegation_contract_node(egld_amount, opt_address);
+        let best_node = self.get_max_delegation_contract_node(egld_amount, opt_address, grows_cash_reserve);
 
         // return the best candidate iff there is no sampling
         if self.delegation_sampling_model().is_empty() {
@@ -32,10 +35,11 @@ pub trait SelectionModule:
         }
 
         // compute a list of candidates based on their scores
-        let (candidates, sum_weights) = self.get_delegation_candidates(&best_node, egld_amount, opt_address);
+        let (candidates, sum_weights) =
+            self.get_delegation_candidates(&best_node, egld_amount, opt_address, grows_cash_reserve);
 
         // return the best candidate iff no other candidates have been found
-        if candidates.len() == 1usize {
+        if candidates.len() <= 1usize {
             return best_node.into_value();
         }
 
@@ -68,34 +72,41 @@ pub trait SelectionModule:
     }
 
     /// Returns the Delegation smart contract with the highest score that is not outdated, can receive the delegated
-    /// amount and, optionally, does not match the provided smart contract address.
+    /// amount and, optionally, does not match the provided smart contract address. Only the first
+    /// `MAX_SELECTION_SCAN_SIZE` Delegation smart contracts are inspected. If none of them is valid, the Delegation
+    /// smart contract is looked up in the delegate capacity index instead.
+    ///
+    /// The concentration limits are binding, so the delegation is rejected when no Delegation smart contract can receive
+    /// the amount within them.
     ///
     fn get_max_delegation_contract_node(
         &self,
         egld_amount: &BigUint,
         opt_skip_address: &OptionalValue<ManagedAddress>,
+        grows_cash_reserve: bool,
     ) -> LinkedListNode<ManagedAddress<Self::Api>> {
         require!(
             !self.delegation_contracts_list().is_empty(),
             ERROR_NO_DELEGATION_CONTRACTS
         );
 
-        for node in self.delegation_contracts_list().iter() {
+        for node in self.delegation_contracts_list().iter().take(MAX_SELECTION_SCAN_SIZE) {
             let delegation_contract = node.get_value_cloned();
             let contract_data = self.delegation_contract_data(&delegation_contract).get();
-
-            if !self.is_valid_delegation_contract(&contract_data, egld_amount, opt_skip_address) {
-                continue;
+            if self.is_valid_delegation_contract(&contract_data, egld_amount, opt_skip_address, grows_cash_reserve) {
+                return node;
             }
-
-            return node;
         }
 
-        sc_panic!(ERROR_DELEGATION_CONTRACT_NOT_AVAILABLE);
+        self.find_in_capacity_index(CapacityIndex::Delegate, egld_amount, |contract_data| {
+            self.is_valid_delegation_contract(contract_data, egld_amount, opt_skip_address, grows_cash_reserve)
+        })
+        .unwrap_or_else(|| sc_panic!(ERROR_DELEGATION_CONTRACT_NOT_AVAILABLE))
     }
 
     /// Returns the undelegation smart contract with the lowest score that is not outdated and has received a sufficient
-    /// delegated amount.
+    /// delegated amount. Only the last `MAX_SELECTION_SCAN_SIZE` Delegation smart contracts are inspected. If none of
+    /// them is valid, the Delegation smart contract is looked up in the undelegate capacity index instead.
     ///
     fn get_min_delegation_contract_node(&self, egld_amount: &BigUint) -> LinkedListNode<ManagedAddress<Self::Api>> {
         require!(
@@ -106,7 +117,9 @@ pub trait SelectionModule:
         let delegation_contracts_mapper = self.delegation_contracts_list();
 
         let mut opt_node = delegation_contracts_mapper.back();
-        while opt_node.is_some() {
+        let mut nr_scanned = 0;
+        while opt_node.is_some() && nr_scanned < MAX_SELECTION_SCAN_SIZE {
+            nr_scanned += 1;
             let node = opt_node.unwrap();
             let delegation_contract = node.get_value_cloned();
             let contract_data = self.delegation_contract_data(&delegation_contract).get();
@@ -117,17 +130,62 @@ pub trait SelectionModule:
             opt_node = delegation_contracts_mapper.get_node_by_id(node.get_prev_node_id());
         }
 
-        sc_panic!(ERROR_DELEGATION_CONTRACT_NOT_AVAILABLE);
+        self.find_in_capacity_index(CapacityIndex::Undelegate, egld_amount, |contract_data| {
+            self.is_valid_undelegation_contract(contract_data, egld_amount)
+        })
+        .unwrap_or_else(|| sc_panic!(ERROR_DELEGATION_CONTRACT_NOT_AVAILABLE))
+    }
+
+    /// Returns a valid Delegation smart contract from a capacity index, inspecting its buckets from the highest
+    /// capacity down to the bucket of the given amount, below which no Delegation smart contract has enough capacity.
+    /// Only the first `MAX_SELECTION_SCAN_SIZE` indexed Delegation smart contracts are inspected, all of which but those
+    /// in the bucket of the given amount have enough capacity.
+    ///
+    fn find_in_capacity_index<F>(
+        &self,
+        index: CapacityIndex,
+        egld_amount: &BigUint,
+        is_valid: F,
+    ) -> Option<LinkedListNode<ManagedAddress<Self::Api>>>
+    where
+        F: Fn(&DelegationContractData<Self::Api>) -> bool,
+    {
+        let min_bucket = self.get_amount_bucket(egld_amount);
+        let mut buckets = self.capacity_bitmap(index).get() & (u64::MAX << min_bucket);
+        let mut nr_scanned = 0;
+        while buckets != 0 {
+            let bucket = u64::BITS - 1 - buckets.leading_zeros();
+            buckets &= !(1u64 << bucket);
+
+            for delegation_contract in self.capacity_bucket(index, bucket).iter() {
+                if nr_scanned == MAX_SELECTION_SCAN_SIZE {
+                    return None;
+                }
+                nr_scanned += 1;
+
+                let contract_data = self.delegation_contract_data(&delegation_contract).get();
+                let position_mapper = self.list_position(&delegation_contract);
+                if position_mapper.is_empty() || !is_valid(&contract_data) {
+                    continue;
+                }
+
+                let node_id = position_mapper.get().node_id;
+                return self.delegation_contracts_list().get_node_by_id(node_id);
+            }
+        }
+
+        None
     }
 
     /// Returns delegation candidates based on their closeness to the best node delegation score. It also computes their
-    /// weights.
+    /// weights. At most `MAX_SELECTION_SCAN_SIZE` Delegation smart contracts are inspected from the best node.
     ///
     fn get_delegation_candidates(
         &self,
         best_node: &LinkedListNode<ManagedAddress<Self::Api>>,
         egld_amount: &BigUint,
         opt_skip_address: &OptionalValue<ManagedAddress>,
+        grows_cash_reserve: bool,
     ) -> (ManagedVec<DelegationCandidate<Self::Api>>, BigUint<Self::Api>) {
         let best_node_id = best_node.get_node_id();
         let best_delegation_contract = best_node.get_value_cloned();
@@ -138,7 +196,11 @@ pub trait SelectionModule:
         let cutoff_score = self.get_max_cutoff_score(&best_delegation_score);
 
         let mut sum_weights = BigUint::zero();
-        for node in self.delegation_contracts_list().iter_from_node_id(best_node_id) {
+        for node in self
+            .delegation_contracts_list()
+            .iter_from_node_id(best_node_id)
+            .take(MAX_SELECTION_SCAN_SIZE)
+        {
             let delegation_contract = node.get_value_cloned();
             let contract_data = self.delegation_contract_data(&delegation_contract).get();
 
@@ -146,7 +208,7 @@ pub trait SelectionModule:
                 break;
             }
 
-            if !self.is_valid_delegation_contract(&contract_data, egld_amount, opt_skip_address) {
+            if !self.is_valid_delegation_contract(&contract_data, egld_amount, opt_skip_address, grows_cash_reserve) {
                 continue;
             }
 
@@ -160,11 +222,58 @@ pub trait SelectionModule:
             sum_weights += weight;
         }
 
-        (candidates, sum_weights)
+        self.share_weights_by_operator(candidates, sum_weights)
+    }
+
+    /// Divides the weight of each delegation candidate by the number of candidates run by its operator, such that an
+    /// operator running many Delegation smart contracts is not more likely to be selected than any other one. The
+    /// number of candidates is bounded by `MAX_SELECTION_SCAN_SIZE`, so counting them pairwise stays cheap.
+    ///
+    fn share_weights_by_operator(
+        &self,
+        candidates: ManagedVec<DelegationCandidate<Self::Api>>,
+        sum_weights: BigUint,
+    ) -> (ManagedVec<DelegationCandidate<Self::Api>>, BigUint<Self::Api>) {
+        let mut operator_ids = ManagedVec::<Self::Api, u64>::new();
+        let mut has_operators = false;
+        for candidate in candidates.iter() {
+            let operator_mapper = self.operator_id(&candidate.data.contract);
+            let operator_id = if operator_mapper.is_empty() {
+                0
+            } else {
+                has_operators = true;
+                operator_mapper.get()
+            };
+            operator_ids.push(operator_id);
+        }
+
+        if !has_operators {
+            return (candidates, sum_weights);
+        }
+
+        let mut shared_candidates = ManagedVec::new();
+        let mut shared_sum_weights = BigUint::zero();
+        for (index, candidate) in candidates.iter().enumerate() {
+            let operator_id = operator_ids.get(index);
+            let nr_operator_candidates = if operator_id == 0 {
+                1
+            } else {
+                operator_ids.iter().filter(|id| *id == operator_id).count()
+            };
+
+            let weight = candidate.weight / nr_operator_candidates as u64;
+            shared_sum_weights += &weight;
+            shared_candidates.push(DelegationCandidate {
+                data: candidate.data,
+                weight,
+            });
+        }
+
+        (shared_candidates, shared_sum_weights)
     }
 
     /// Returns undelegation candidates based on their closeness to the best node delegation score. It also computes
-    /// their weights.
+    /// their weights. At most `MAX_SELECTION_SCAN_SIZE` Delegation smart contracts are inspected from the best node.
     ///
     fn get_undelegation_candidates(
         &self,
@@ -181,7 +290,9 @@ pub trait SelectionModule:
         let cutoff_score = self.get_min_cutoff_score(&best_delegation_score);
         let delegation_contracts_mapper = self.delegation_contracts_list();
         let mut opt_node = delegation_contracts_mapper.get_node_by_id(best_node_id);
-        while opt_node.is_some() {
+        let mut nr_scanned = 0;
+        while opt_node.is_some() && nr_scanned < MAX_SELECTION_SCAN_SIZE {
+            nr_scanned += 1;
             let node = opt_node.unwrap();
             let address = node.get_value_cloned();
             let contract_data = self.delegation_contract_data(&address).get();
@@ -215,11 +326,16 @@ pub trait SelectionModule:
         contract_data: &DelegationContractData<Self::Api>,
         egld_amount: &BigUint,
         opt_skip_address: &OptionalValue<ManagedAddress>,
+        grows_cash_reserve: bool,
     ) -> bool {
         if contract_data.outdated {
             return false;
         }
 
+        if self.is_stale_contract_data(&contract_data.contract) {
+            return false;
+        }
+
         if opt_skip_address.is_some() && opt_skip_address.clone().into_option().unwrap() == contract_data.contract {
             return false;
         }
@@ -232,7 +348,7 @@ pub trait SelectionModule:
             return false;
         }
 
-        true
+        self.is_within_concentration_limits(&contract_data.contract, egld_amount, grows_cash_reserve)
     }
 
     fn has_valid_cap(&self, contract_data: &DelegationContractData<Self::Api>, egld_amount: &BigUint) -> bool {
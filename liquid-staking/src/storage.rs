@@ -4,10 +4,10 @@ use super::model::*;
 
 #[multiversx_sc::module]
 pub trait StorageModule {
-    /// The current state of the Liquid Staking module
-    #[view(getState)]
-    #[storage_mapper("state")]
-    fn state(&self) -> SingleValueMapper<State>;
+    /// A bitmask of the paused operations, see `Operation::flag`
+    #[view(getPausedOperations)]
+    #[storage_mapper("pausedOperations")]
+    fn paused_operations(&self) -> SingleValueMapper<u8>;
 
     /// The Liquid Staking token, i.e. the sEGLD ESDT token
     #[storage_mapper("lsTokenMapper")]
@@ -38,6 +38,16 @@ pub trait StorageModule {
     #[storage_mapper("rewardsReserve")]
     fn rewards_reserve(&self) -> SingleValueMapper<BigUint>;
 
+    /// The number of rounds over which delegated rewards are vested into the exchange rate
+    #[view(getRewardsVestingPeriod)]
+    #[storage_mapper("rewardsVestingPeriod")]
+    fn rewards_vesting_period(&self) -> SingleValueMapper<u64>;
+
+    /// The delegated rewards that are being vested into the exchange rate
+    #[view(getRewardsStream)]
+    #[storage_mapper("rewardsStream")]
+    fn rewards_stream(&self) -> SingleValueMapper<RewardsStream<Self::Api>>;
+
     /// The current amount of EGLD that belongs to the protocol
     #[view(getProtocolReserves)]
     #[storage_mapper("protocolReserves")]
@@ -68,6 +78,44 @@ pub trait StorageModule {
     #[storage_mapper("delegationContractsList")]
     fn delegation_contracts_list(&self) -> LinkedListMapper<ManagedAddress>;
 
+    /// The position of each Delegation smart contract in the Delegation smart contracts list, which allows to remove it
+    /// without traversing the list
+    #[view(getListPosition)]
+    #[storage_mapper("listPosition")]
+    fn list_position(&self, delegation_contract: &ManagedAddress) -> SingleValueMapper<ListPosition>;
+
+    /// The node identifier of the first Delegation smart contract in the list with a given delegation score
+    #[storage_mapper("scoreHeadNodeId")]
+    fn score_head_node_id(&self, delegation_score: u64) -> SingleValueMapper<u32>;
+
+    /// A bitmap of the delegation scores held by at least one Delegation smart contract in the list, split in words of
+    /// `SCORE_BITMAP_WORD_SIZE` scores
+    #[storage_mapper("scoreBitmap")]
+    fn score_bitmap(&self, word: u64) -> SingleValueMapper<u64>;
+
+    /// A bitmap of the non-empty words of the delegation scores bitmap, split in words of `SCORE_BITMAP_WORD_SIZE` words
+    #[storage_mapper("scoreSummaryBitmap")]
+    fn score_summary_bitmap(&self, word: u64) -> SingleValueMapper<u64>;
+
+    /// The Delegation smart contracts in a given bucket of a capacity index
+    #[storage_mapper("capacityBucket")]
+    fn capacity_bucket(&self, index: CapacityIndex, bucket: u32) -> UnorderedSetMapper<ManagedAddress>;
+
+    /// A bitmap of the non-empty buckets of a capacity index
+    #[storage_mapper("capacityBitmap")]
+    fn capacity_bitmap(&self, index: CapacityIndex) -> SingleValueMapper<u64>;
+
+    /// The node of the Delegation smart contracts list from which the pending rescoring resumes after a delegation score
+    /// model change, or zero if there is none
+    #[view(getSortCursor)]
+    #[storage_mapper("sortCursor")]
+    fn sort_cursor(&self) -> SingleValueMapper<u32>;
+
+    /// The sum of the delegation scores of the Delegation smart contracts in the list
+    #[view(getTotalDelegationScore)]
+    #[storage_mapper("totalDelegationScore")]
+    fn total_delegation_score(&self) -> SingleValueMapper<u64>;
+
     /// Allows users to delegate their EGLD to a given staking provider Delegation smart contract bypassing the Delegation
     /// Algorithm
     #[view(getMigrationWhitelist)]
@@ -106,6 +154,18 @@ pub trait StorageModule {
     #[storage_mapper("lastContractDataUpdateEpoch")]
     fn last_contract_data_update_epoch(&self) -> SingleValueMapper<u64>;
 
+    /// The last epoch the data of a given Delegation smart contract has been updated, either when whitelisting it or by
+    /// the data manager or the data reporters
+    #[view(getContractDataUpdateEpoch)]
+    #[storage_mapper("contractDataUpdateEpoch")]
+    fn contract_data_update_epoch(&self, delegation_contract: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    /// The number of epochs after which the data of a Delegation smart contract is considered stale. Zero means data
+    /// never becomes stale
+    #[view(getMaxContractDataAge)]
+    #[storage_mapper("maxContractDataAge")]
+    fn max_contract_data_age(&self) -> SingleValueMapper<u64>;
+
     /// The last epoch rewards have been claimed for a given Staking Provider Delegation smart contract
     #[view(getLastClaimRewardsEpoch)]
     #[storage_mapper("lastClaimRewardsEpoch")]
@@ -126,6 +186,20 @@ pub trait StorageModule {
     #[storage_mapper("delegationScoreModel")]
     fn delegation_score_model(&self) -> SingleValueMapper<DelegationScoreModel<Self::Api>>;
 
+    /// The raw Delegation Score model parameters, which allows migrating them from their legacy encoding
+    #[storage_mapper("delegationScoreModel")]
+    fn raw_delegation_score_model(&self) -> SingleValueMapper<ManagedBuffer>;
+
+    /// The terms of the weighted delegation score method
+    #[view(getDelegationScoreTerms)]
+    #[storage_mapper("delegationScoreTerms")]
+    fn delegation_score_terms(&self) -> SingleValueMapper<ManagedVec<DelegationScoreTerm<Self::Api>>>;
+
+    /// The domain of the TVL per node delegation score method
+    #[view(getTvlPerNodeDomain)]
+    #[storage_mapper("tvlPerNodeDomain")]
+    fn tvl_per_node_domain(&self) -> SingleValueMapper<ScoreDomain<Self::Api>>;
+
     /// The Delegation Sampling model parameters
     #[view(getDelegationSamplingModel)]
     #[storage_mapper("delegationSamplingModel")]
@@ -136,8 +210,156 @@ pub trait StorageModule {
     #[storage_mapper("dataManager")]
     fn data_manager(&self) -> SingleValueMapper<ManagedAddress>;
 
+    /// The accounts entitled to report the data associated to each Delegation smart contract
+    #[view(getDataReporters)]
+    #[storage_mapper("dataReporters")]
+    fn data_reporters(&self) -> UnorderedSetMapper<ManagedAddress>;
+
+    /// The number of matching data reports required to update the data of a Delegation smart contract. Zero means the
+    /// data is updated by the data manager instead
+    #[view(getDataReportersQuorum)]
+    #[storage_mapper("dataReportersQuorum")]
+    fn data_reporters_quorum(&self) -> SingleValueMapper<usize>;
+
+    /// The maximum deviation (in bps) of a data report from the median of the reports submitted within the same epoch.
+    /// Zero means reports are not bounded
+    #[view(getMaxDataReportDeviation)]
+    #[storage_mapper("maxDataReportDeviation")]
+    fn max_data_report_deviation(&self) -> SingleValueMapper<u64>;
+
+    /// The bounds on Delegation smart contract data updates, if any
+    #[view(getDataBounds)]
+    #[storage_mapper("dataBounds")]
+    fn data_bounds(&self) -> SingleValueMapper<DataBounds<Self::Api>>;
+
+    /// The data update discarded for a given Delegation smart contract because it broke the data bounds, if any
+    #[view(getQuarantinedData)]
+    #[storage_mapper("quarantinedData")]
+    fn quarantined_data(&self, delegation_contract: &ManagedAddress) -> SingleValueMapper<DataReport<Self::Api>>;
+
+    /// The data reports submitted during the current epoch for a given Delegation smart contract, by reporter
+    #[storage_mapper("dataReports")]
+    fn data_reports(&self, delegation_contract: &ManagedAddress) -> MapMapper<ManagedAddress, DataReport<Self::Api>>;
+
     /// Stores the random oracle address, used only for testing purposes
     #[view(getRandomOracle)]
     #[storage_mapper("randomOracle")]
     fn random_oracle(&self) -> SingleValueMapper<ManagedAddress>;
+
+    /// The amount of EGLD kept liquid at the Liquid Staking smart contract, used to serve instant undelegations
+    #[view(getLiquidityBuffer)]
+    #[storage_mapper("liquidityBuffer")]
+    fn liquidity_buffer(&self) -> SingleValueMapper<BigUint>;
+
+    /// The target size of the liquidity buffer as a fraction of the cash reserve (in bps)
+    #[view(getLiquidityBufferTarget)]
+    #[storage_mapper("liquidityBufferTarget")]
+    fn liquidity_buffer_target(&self) -> SingleValueMapper<BigUint>;
+
+    /// The fee charged on instant undelegations (in bps)
+    #[view(getInstantUndelegateFee)]
+    #[storage_mapper("instantUndelegateFee")]
+    fn instant_undelegate_fee(&self) -> SingleValueMapper<BigUint>;
+
+    /// Who receives the fee charged on instant undelegations
+    #[view(getInstantUndelegateFeeRecipient)]
+    #[storage_mapper("instantUndelegateFeeRecipient")]
+    fn instant_undelegate_fee_recipient(&self) -> SingleValueMapper<InstantUndelegateFeeRecipient>;
+
+    /// Undelegations made to refill the liquidity buffer by their identifiers
+    #[view(getBufferRefillById)]
+    #[storage_mapper("bufferRefills")]
+    fn buffer_refills(&self, id: u64) -> SingleValueMapper<UndelegateAttributes<Self::Api>>;
+
+    /// The next buffer refill identifier
+    #[view(getNextBufferRefillId)]
+    #[storage_mapper("nextBufferRefillId")]
+    fn next_buffer_refill_id(&self) -> SingleValueMapper<u64>;
+
+    /// The amount of EGLD being undelegated in order to refill the liquidity buffer
+    #[view(getPendingBufferRefill)]
+    #[storage_mapper("pendingBufferRefill")]
+    fn pending_buffer_refill(&self) -> SingleValueMapper<BigUint>;
+
+    /// The maximum shares of the cash reserve that can be allocated to a single Delegation smart contract or operator
+    #[view(getConcentrationLimits)]
+    #[storage_mapper("concentrationLimits")]
+    fn concentration_limits(&self) -> SingleValueMapper<ConcentrationLimits>;
+
+    /// The operator running a given Delegation smart contract, if registered
+    #[view(getOperatorId)]
+    #[storage_mapper("operatorId")]
+    fn operator_id(&self, delegation_contract: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    /// The Delegation smart contracts run by a given operator
+    #[view(getOperatorContracts)]
+    #[storage_mapper("operatorContracts")]
+    fn operator_contracts(&self, operator_id: u64) -> UnorderedSetMapper<ManagedAddress>;
+
+    /// The aggregated data of the Delegation smart contracts run by a given operator
+    #[view(getOperatorData)]
+    #[storage_mapper("operatorData")]
+    fn operator_data(&self, operator_id: u64) -> SingleValueMapper<OperatorData<Self::Api>>;
+
+    /// Undelegations made to move EGLD between Delegation smart contracts by their identifiers
+    #[view(getRebalanceById)]
+    #[storage_mapper("rebalances")]
+    fn rebalances(&self, id: u64) -> SingleValueMapper<Rebalance<Self::Api>>;
+
+    /// The parameters bounding permissionless rebalances
+    #[view(getRebalanceParams)]
+    #[storage_mapper("rebalanceParams")]
+    fn rebalance_params(&self) -> SingleValueMapper<RebalanceParams<Self::Api>>;
+
+    /// The epoch of the last rebalance from a given Delegation smart contract
+    #[view(getLastRebalanceEpoch)]
+    #[storage_mapper("lastRebalanceEpoch")]
+    fn last_rebalance_epoch(&self, delegation_contract: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    /// The next rebalance identifier
+    #[view(getNextRebalanceId)]
+    #[storage_mapper("nextRebalanceId")]
+    fn next_rebalance_id(&self) -> SingleValueMapper<u64>;
+
+    /// The amount of EGLD being undelegated in order to be delegated to another Delegation smart contract
+    #[view(getPendingRebalance)]
+    #[storage_mapper("pendingRebalance")]
+    fn pending_rebalance(&self) -> SingleValueMapper<BigUint>;
+
+    /// The amount of EGLD being undelegated in order to be delegated to a given Delegation smart contract
+    #[view(getIncomingRebalance)]
+    #[storage_mapper("incomingRebalance")]
+    fn incoming_rebalance(&self, delegation_contract: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    /// The number of epochs between queueing a timelocked call and executing it
+    #[view(getTimelockDelay)]
+    #[storage_mapper("timelockDelay")]
+    fn timelock_delay(&self) -> SingleValueMapper<u64>;
+
+    /// Queued timelocked calls by their identifiers
+    #[view(getTimelockCall)]
+    #[storage_mapper("timelockCalls")]
+    fn timelock_calls(&self, id: u64) -> SingleValueMapper<TimelockCall<Self::Api>>;
+
+    /// The identifiers of the timelocked calls that have been queued and neither executed nor cancelled
+    #[view(getQueuedTimelockCallIds)]
+    #[storage_mapper("queuedTimelockCallIds")]
+    fn queued_timelock_call_ids(&self) -> UnorderedSetMapper<u64>;
+
+    /// The next timelocked call identifier
+    #[view(getNextTimelockCallId)]
+    #[storage_mapper("nextTimelockCallId")]
+    fn next_timelock_call_id(&self) -> SingleValueMapper<u64>;
+
+    /// The number of times the pending amount has been undelegated from a given Delegation smart contract
+    #[view(getUndelegationBatch)]
+    #[storage_mapper("undelegationBatch")]
+    fn undelegation_batch(&self, delegation_contract: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    /// The undelegation batch of an undelegate NFT whose amount might still be pending to undelegate, i.e. one plus the
+    /// undelegation batch of its Delegation smart contract at the time of minting. It is empty once the NFT has been
+    /// withdrawn or cancelled
+    #[view(getUndelegateNftBatch)]
+    #[storage_mapper("undelegateNftBatch")]
+    fn undelegate_nft_batch(&self, undelegate_token_nonce: u64) -> SingleValueMapper<u64>;
 }
@@ -0,0 +1,274 @@
+multiversx_sc::imports!();
+use super::{common, delegation, errors::*, events, governance, model::*, proxies, score, selection, storage};
+use admin::Role;
+
+#[multiversx_sc::module]
+pub trait TimelockModule:
+    admin::AdminModule
+    + common::CommonModule
+    + delegation::DelegationModule
+    + events::EventsModule
+    + governance::GovernanceModule
+    + proxies::ProxyModule
+    + score::ScoreModule
+    + selection::SelectionModule
+    + storage::StorageModule
+{
+    /// Queues a governance call, which can be executed once the timelock delay has elapsed.
+    ///
+    /// # Arguments
+    ///
+    /// - `action` - the governance call to perform
+    /// - `arguments` - the top-encoded arguments of the call, in the same order as the corresponding endpoint
+    ///   arguments. Trailing optional arguments can be omitted.
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the role required by the call, or by the admin for
+    ///   `SetTimelockDelay`
+    ///
+    #[endpoint(queueCall)]
+    fn queue_call(&self, action: TimelockAction, arguments: MultiValueEncoded<ManagedBuffer>) -> u64 {
+        self.require_timelock_action_caller(action);
+
+        let arguments = arguments.to_vec();
+        let (min_arguments, max_arguments) = action.num_arguments();
+        require!(
+            arguments.len() >= min_arguments && arguments.len() <= max_arguments,
+            ERROR_INVALID_TIMELOCK_ARGUMENTS
+        );
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        let call = TimelockCall {
+            action,
+            arguments,
+            proposer: self.blockchain().get_caller(),
+            eta_epoch: current_epoch + self.timelock_delay().get(),
+        };
+
+        let id = self.next_timelock_call_id().get();
+        self.next_timelock_call_id().set(id + 1);
+        self.timelock_calls(id).set(&call);
+        self.queued_timelock_call_ids().insert(id);
+
+        self.queue_timelock_call_event(id, &call);
+
+        id
+    }
+
+    /// Executes a queued governance call once its timelock has elapsed.
+    ///
+    /// # Arguments
+    ///
+    /// - `id` - the timelocked call identifier
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the role required by the call, or by the admin for
+    ///   `SetTimelockDelay`
+    ///
+    #[endpoint(executeCall)]
+    fn execute_call(&self, id: u64) {
+        let call = self.take_timelock_call(id);
+        self.require_timelock_action_caller(call.action);
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        require!(current_epoch >= call.eta_epoch, ERROR_TIMELOCK_NOT_ELAPSED);
+
+        let arguments = &call.arguments;
+        match call.action {
+            TimelockAction::SetTotalFee => {
+                let fee: BigUint = self.timelock_argument(arguments, 0);
+                self.set_total_fee_internal(&fee);
+            },
+            TimelockAction::SetDelegationScoreModelParams => {
+                self.set_delegation_score_model_params_internal(
+                    self.timelock_argument(arguments, 0),
+                    self.timelock_argument(arguments, 1),
+                    self.timelock_argument(arguments, 2),
+                    self.timelock_argument(arguments, 3),
+                    self.timelock_argument(arguments, 4),
+                    self.timelock_argument(arguments, 5),
+                    self.optional_timelock_argument(arguments, 6),
+                );
+            },
+            TimelockAction::SetDelegationSamplingModelParams => {
+                self.set_delegation_sampling_model_params_internal(
+                    self.timelock_argument(arguments, 0),
+                    self.timelock_argument(arguments, 1),
+                    self.timelock_argument(arguments, 2),
+                );
+            },
+            TimelockAction::WhitelistDelegationContract => {
+                self.whitelist_delegation_contract_internal(
+                    self.timelock_argument(arguments, 0),
+                    self.timelock_argument(arguments, 1),
+                    self.timelock_argument(arguments, 2),
+                    self.timelock_argument(arguments, 3),
+                    self.timelock_argument(arguments, 4),
+                    self.optional_timelock_argument(arguments, 5),
+                );
+            },
+            TimelockAction::BlacklistDelegationContract => {
+                self.blacklist_delegation_contract_internal(self.timelock_argument(arguments, 0));
+            },
+            TimelockAction::WithdrawReserve => {
+                self.withdraw_reserve_internal(
+                    self.timelock_argument(arguments, 0),
+                    self.optional_timelock_argument(arguments, 1),
+                );
+            },
+            TimelockAction::SetTimelockDelay => {
+                self.set_timelock_delay_internal(self.timelock_argument(arguments, 0));
+            },
+            TimelockAction::SetDelegationScoreTerms => {
+                self.set_delegation_score_terms_internal(
+                    self.timelock_argument(arguments, 0),
+                    self.timelock_argument(arguments, 1),
+                );
+            },
+            TimelockAction::SetRebalanceParams => {
+                self.set_rebalance_params_internal(
+                    self.timelock_argument(arguments, 0),
+                    self.timelock_argument(arguments, 1),
+                    self.timelock_argument(arguments, 2),
+                );
+            },
+            TimelockAction::SetDataManager => {
+                self.set_data_manager_internal(self.timelock_argument(arguments, 0));
+            },
+            TimelockAction::AddDataReporter => {
+                self.add_data_reporter_internal(self.timelock_argument(arguments, 0));
+            },
+            TimelockAction::RemoveDataReporter => {
+                self.remove_data_reporter_internal(self.timelock_argument(arguments, 0));
+            },
+            TimelockAction::SetDataReportersQuorum => {
+                self.set_data_reporters_quorum_internal(self.timelock_argument(arguments, 0));
+            },
+            TimelockAction::SetMaxDataReportDeviation => {
+                self.set_max_data_report_deviation_internal(self.timelock_argument(arguments, 0));
+            },
+            TimelockAction::SetDataBounds => {
+                self.set_data_bounds_internal(
+                    self.timelock_argument(arguments, 0),
+                    self.timelock_argument(arguments, 1),
+                    self.timelock_argument(arguments, 2),
+                    self.timelock_argument(arguments, 3),
+                    self.timelock_argument(arguments, 4),
+                );
+            },
+            TimelockAction::SetMaxContractDataAge => {
+                self.set_max_contract_data_age_internal(self.timelock_argument(arguments, 0));
+            },
+            TimelockAction::SetConcentrationLimits => {
+                self.set_concentration_limits_internal(
+                    self.timelock_argument(arguments, 0),
+                    self.timelock_argument(arguments, 1),
+                );
+            },
+            TimelockAction::SetOperator => {
+                self.set_operator_internal(
+                    self.timelock_argument(arguments, 0),
+                    self.timelock_argument(arguments, 1),
+                );
+            },
+            TimelockAction::RemoveOperator => {
+                self.remove_operator_internal(self.timelock_argument(arguments, 0));
+            },
+            TimelockAction::SetDelegationScoreCurve => {
+                self.set_delegation_score_curve_internal(
+                    self.timelock_argument(arguments, 0),
+                    self.timelock_argument(arguments, 1),
+                );
+            },
+            TimelockAction::ReleaseQuarantine => {
+                self.release_quarantine_internal(self.timelock_argument(arguments, 0));
+            },
+            TimelockAction::SetTvlPerNodeDomain => {
+                self.set_tvl_per_node_domain_internal(
+                    self.timelock_argument(arguments, 0),
+                    self.timelock_argument(arguments, 1),
+                    self.timelock_argument(arguments, 2),
+                );
+            },
+            TimelockAction::SetLiquidityBufferTarget => {
+                self.set_liquidity_buffer_target_internal(&self.timelock_argument(arguments, 0));
+            },
+            TimelockAction::SetRewardsVestingPeriod => {
+                self.set_rewards_vesting_period_internal(self.timelock_argument(arguments, 0));
+            },
+            TimelockAction::SetInstantUndelegateFee => {
+                self.set_instant_undelegate_fee_internal(
+                    &self.timelock_argument(arguments, 0),
+                    self.timelock_argument(arguments, 1),
+                );
+            },
+            TimelockAction::ClearDelegationSamplingModel => {
+                self.clear_delegation_sampling_model_internal();
+            },
+        }
+
+        let caller = self.blockchain().get_caller();
+        self.execute_timelock_call_event(id, &caller, &call);
+    }
+
+    /// Cancels a queued governance call.
+    ///
+    /// # Arguments
+    ///
+    /// - `id` - the timelocked call identifier
+    ///
+    /// # Notes
+    ///
+    /// - can only be called by an account with the Guardian role
+    ///
+    #[endpoint(cancelCall)]
+    fn cancel_call(&self, id: u64) {
+        self.require_role(Role::Guardian);
+
+        let call = self.take_timelock_call(id);
+
+        let caller = self.blockchain().get_caller();
+        self.cancel_timelock_call_event(id, &caller, &call);
+    }
+
+    /// Removes a queued governance call from storage and returns it
+    ///
+    fn take_timelock_call(&self, id: u64) -> TimelockCall<Self::Api> {
+        require!(
+            self.queued_timelock_call_ids().swap_remove(&id),
+            ERROR_UNEXPECTED_TIMELOCK_CALL_ID
+        );
+        self.timelock_calls(id).take()
+    }
+
+    fn require_timelock_action_caller(&self, action: TimelockAction) {
+        match action.role() {
+            Some(role) => self.require_role(role),
+            None => self.require_admin(),
+        }
+    }
+
+    /// Decodes the argument of a timelocked call at the given position
+    ///
+    fn timelock_argument<T: TopDecode>(&self, arguments: &ManagedVec<ManagedBuffer>, index: usize) -> T {
+        require!(index < arguments.len(), ERROR_INVALID_TIMELOCK_ARGUMENTS);
+        T::top_decode(arguments.get(index).clone_value())
+            .unwrap_or_else(|_| sc_panic!(ERROR_INVALID_TIMELOCK_ARGUMENTS))
+    }
+
+    /// Decodes the trailing optional argument of a timelocked call at the given position, if given
+    ///
+    fn optional_timelock_argument<T: TopDecode>(
+        &self,
+        arguments: &ManagedVec<ManagedBuffer>,
+        index: usize,
+    ) -> OptionalValue<T> {
+        if index < arguments.len() {
+            OptionalValue::Some(self.timelock_argument(arguments, index))
+        } else {
+            OptionalValue::None
+        }
+    }
+}
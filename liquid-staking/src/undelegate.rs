@@ -25,25 +25,51 @@ pub trait UndelegateModule:
     ///
     /// # Arguments
     ///
-    /// - `opt_delegation_contract`: The address of the Delegation smart contract to undelegate from
+    /// - `opt_delegation_contract`: The address of the Delegation smart contract to undelegate from. The zero address
+    ///   can be given in order to keep using the delegation algorithm while providing a minimum EGLD amount.
+    /// - `opt_min_egld_out`: The optional minimum amount of EGLD the caller expects to be undelegated
+    /// - `opt_receiver`: The optional account receiving the undelegate NFT, which defaults to the caller
+    /// - `opt_endpoint`: The optional endpoint at the receiver smart contract to be called along with the undelegate NFT
+    /// - `args`: The arguments for the receiver endpoint
     ///
     /// # Notes
     ///
     /// - There is a minimum amount of 1 EGLD for undelegations, which corresponds to a minimum amount of sEGLD
     ///   depending on the current exchange rate.
+    /// - The amount of EGLD can be previewed using the `previewUnDelegate` view.
+    /// - The undelegate NFT can be forwarded to a smart contract endpoint using a transfer and execute. The receiver
+    ///   smart contract must be in the same shard, since the NFT of a failed cross-shard call would bounce back to this
+    ///   smart contract.
+    /// - Optional arguments are positional, so the zero address and a zero minimum amount of EGLD must be given in
+    ///   order to specify a receiver while using the delegation algorithm.
     ///
     #[payable("*")]
+    #[allow_multiple_var_args]
     #[endpoint(unDelegate)]
-    fn undelegate(&self, opt_delegation_contract: OptionalValue<ManagedAddress>) -> EsdtTokenPayment {
+    fn undelegate(
+        &self,
+        opt_delegation_contract: OptionalValue<ManagedAddress>,
+        opt_min_egld_out: OptionalValue<BigUint>,
+        opt_receiver: OptionalValue<ManagedAddress>,
+        opt_endpoint: OptionalValue<ManagedBuffer>,
+        args: MultiValueEncoded<ManagedBuffer>,
+    ) -> EsdtTokenPayment {
+        self.require_not_paused(Operation::Undelegate);
+
         let (ls_token_id, shares) = self.call_value().single_fungible_esdt();
         self.require_valid_shares_payment(&ls_token_id, &shares);
 
         let egld_amount = self.shares_to_egld(&shares);
         self.require_sufficient_egld(&egld_amount);
+        self.require_min_amount_out(&egld_amount, opt_min_egld_out, ERROR_EGLD_OUT_BELOW_MIN);
+
+        let opt_delegation_contract = opt_delegation_contract
+            .into_option()
+            .filter(|contract| !contract.is_zero());
 
         let delegation_contract = match opt_delegation_contract {
-            OptionalValue::None => self.get_delegation_contract_for_undelegate(&egld_amount),
-            OptionalValue::Some(contract) => {
+            None => self.get_delegation_contract_for_undelegate(&egld_amount),
+            Some(contract) => {
                 self.require_open_mode();
 
                 let contract_data_mapper = self.delegation_contract_data(&contract);
@@ -60,7 +86,7 @@ pub trait UndelegateModule:
         };
 
         let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
-        contract_data_mapper.update(|data| {
+        self.update_delegation_contract_data(&delegation_contract, |data| {
             // update `total_delegated` here, such that it is taken into consideration when computing the delegation
             // contract at a next call to `get_delegation_contract_for_undelegate` above
             data.total_delegated -= &egld_amount;
@@ -81,15 +107,232 @@ pub trait UndelegateModule:
             unbond_epoch,
         };
 
-        let (nft_id, nft_nonce, nft_amount) = self.mint_undelegate_nft(&attrs).into_tuple();
+        let payment = self.mint_undelegate_nft(&attrs);
+
+        // keeps track of the batch in which the pending amount is undelegated, so that it can be cancelled before
+        let batch = self.get_current_undelegation_batch(&attrs.delegation_contract);
+        self.undelegate_nft_batch(payment.token_nonce).set(batch);
+
+        let caller = self.blockchain().get_caller();
+        let receiver = opt_receiver.into_option().unwrap_or_else(|| caller.clone());
+
+        let contract_data = contract_data_mapper.get();
+        self.undelegate_event(&caller, &receiver, payment.token_nonce, &attrs, &contract_data);
+
+        self.send_or_transfer_execute(&receiver, payment.clone().into(), opt_endpoint, args);
+
+        payment
+    }
+
+    /// Splits an undelegate NFT into many undelegate NFTs, such that unbonding positions can be partially transferred.
+    /// Each new NFT gets the given EGLD amount and a proportional amount of shares, while keeping the same Delegation
+    /// smart contract and epochs. The paid NFT is burned.
+    ///
+    /// # Arguments
+    ///
+    /// - `amounts`: The EGLD amounts of the new undelegate NFTs, which must be positive and add up to the EGLD amount
+    ///   of the paid NFT.
+    ///
+    /// # Notes
+    ///
+    /// - At least two amounts must be given.
+    /// - The new NFTs are sent to the caller in the same order as the given amounts.
+    ///
+    #[payable("*")]
+    #[endpoint(splitUndelegateNft)]
+    fn split_undelegate_nft(&self, amounts: MultiValueEncoded<BigUint>) -> MultiValueEncoded<EsdtTokenPayment> {
+        let payment = self.call_value().single_esdt();
+        require!(
+            payment.token_identifier == self.undelegate_token().get_token_id(),
+            ERROR_UNEXPECTED_PAYMENT
+        );
+        require!(amounts.len() > 1, ERROR_INVALID_SPLIT_AMOUNTS);
+
+        let token_nonce = payment.token_nonce;
+        let attributes: UndelegateAttributes<Self::Api> = self.undelegate_token().get_token_attributes(token_nonce);
+
+        let batch_mapper = self.undelegate_nft_batch(token_nonce);
+        let opt_batch = if batch_mapper.is_empty() {
+            None
+        } else {
+            Some(batch_mapper.take())
+        };
+
+        let mut egld_left = attributes.egld_amount.clone();
+        let mut shares_left = attributes.shares.clone();
+        let mut payments = ManagedVec::new();
+        let mut new_token_nonces = ManagedVec::new();
+
+        let last_index = amounts.len() - 1;
+        for (index, egld_amount) in amounts.into_iter().enumerate() {
+            require!(egld_amount > 0 && egld_amount <= egld_left, ERROR_INVALID_SPLIT_AMOUNTS);
+
+            // the last NFT takes the remaining shares such that no shares are lost due to rounding
+            let shares = if index == last_index {
+                require!(egld_amount == egld_left, ERROR_INVALID_SPLIT_AMOUNTS);
+                shares_left.clone()
+            } else {
+                &attributes.shares * &egld_amount / &attributes.egld_amount
+            };
+
+            egld_left -= &egld_amount;
+            shares_left -= &shares;
+
+            let new_attributes = UndelegateAttributes {
+                delegation_contract: attributes.delegation_contract.clone(),
+                egld_amount,
+                shares,
+                undelegate_epoch: attributes.undelegate_epoch,
+                unbond_epoch: attributes.unbond_epoch,
+            };
+
+            let new_payment = self.mint_undelegate_nft(&new_attributes);
+            if let Some(batch) = opt_batch {
+                self.undelegate_nft_batch(new_payment.token_nonce).set(batch);
+            }
+            new_token_nonces.push(new_payment.token_nonce);
+            payments.push(new_payment);
+        }
+
+        self.burn_undelegate_nft(token_nonce);
+
+        let caller = self.blockchain().get_caller();
+        self.send().direct_multi(&caller, &payments);
+
+        self.split_undelegate_nft_event(&caller, token_nonce, &new_token_nonces);
+
+        payments.into()
+    }
+
+    /// Merges many undelegate NFTs into a single undelegate NFT, whose EGLD amount and shares are the sum of the ones of
+    /// the paid NFTs. The paid NFTs are burned.
+    ///
+    /// # Notes
+    ///
+    /// - At least two undelegate NFTs must be paid.
+    /// - Only undelegate NFTs with the same Delegation smart contract and unbond epoch can be merged. The undelegate
+    ///   epoch of the new NFT is the earliest one.
+    /// - The new NFT can only be cancelled if all the merged NFTs could be cancelled.
+    ///
+    #[payable("*")]
+    #[endpoint(mergeUndelegateNfts)]
+    fn merge_undelegate_nfts(&self) -> EsdtTokenPayment {
+        let payments = self.call_value().all_esdt_transfers();
+        require!(payments.len() > 1, ERROR_NOT_ENOUGH_NFTS_TO_MERGE);
+
+        let undelegate_token_id = self.undelegate_token().get_token_id();
+        let mut opt_merged_attributes: Option<UndelegateAttributes<Self::Api>> = None;
+        let mut token_nonces = ManagedVec::new();
+        let mut is_cancellable = true;
+        let mut batch = u64::MAX;
+
+        for payment in payments.iter() {
+            require!(
+                payment.token_identifier == undelegate_token_id,
+                ERROR_UNEXPECTED_PAYMENT
+            );
+
+            let token_nonce = payment.token_nonce;
+            let attributes: UndelegateAttributes<Self::Api> = self.undelegate_token().get_token_attributes(token_nonce);
+
+            opt_merged_attributes = match opt_merged_attributes {
+                None => Some(attributes),
+                Some(mut merged) => {
+                    require!(
+                        merged.delegation_contract == attributes.delegation_contract
+                            && merged.unbond_epoch == attributes.unbond_epoch,
+                        ERROR_NFTS_CANNOT_BE_MERGED
+                    );
+                    merged.egld_amount += attributes.egld_amount;
+                    merged.shares += attributes.shares;
+                    merged.undelegate_epoch = core::cmp::min(merged.undelegate_epoch, attributes.undelegate_epoch);
+                    Some(merged)
+                },
+            };
+
+            let batch_mapper = self.undelegate_nft_batch(token_nonce);
+            if batch_mapper.is_empty() {
+                is_cancellable = false;
+            } else {
+                batch = core::cmp::min(batch, batch_mapper.take());
+            }
+
+            self.burn_undelegate_nft(token_nonce);
+            token_nonces.push(token_nonce);
+        }
+
+        let merged_attributes = opt_merged_attributes.unwrap_or_else(|| sc_panic!(ERROR_NOT_ENOUGH_NFTS_TO_MERGE));
+        let new_payment = self.mint_undelegate_nft(&merged_attributes);
+        if is_cancellable {
+            self.undelegate_nft_batch(new_payment.token_nonce).set(batch);
+        }
+
+        let caller = self.blockchain().get_caller();
+        self.send().direct_esdt(
+            &caller,
+            &new_payment.token_identifier,
+            new_payment.token_nonce,
+            &new_payment.amount,
+        );
+
+        self.merge_undelegate_nfts_event(&caller, &token_nonces, new_payment.token_nonce, &merged_attributes);
+
+        new_payment
+    }
+
+    /// Allows users to cancel an undelegation as long as its amount is still pending to be undelegated from the Delegation
+    /// smart contract. The EGLD amount goes back to the Delegation smart contract delegated amount, and sEGLD is minted
+    /// at the current exchange rate. The paid undelegate NFT is burned.
+    ///
+    /// # Arguments
+    ///
+    /// - `opt_min_shares_out`: The optional minimum amount of sEGLD the caller expects to receive
+    ///
+    /// # Notes
+    ///
+    /// - It fails once `unDelegatePendingAmount` has been called for the Delegation smart contract after the
+    ///   undelegation, even if the asynchronous call eventually fails.
+    ///
+    #[payable("*")]
+    #[endpoint(cancelUnDelegate)]
+    fn cancel_undelegate(&self, opt_min_shares_out: OptionalValue<BigUint>) -> EsdtTokenPayment {
+        self.require_not_paused(Operation::Undelegate);
+
+        let payment = self.call_value().single_esdt();
+        require!(
+            payment.token_identifier == self.undelegate_token().get_token_id(),
+            ERROR_UNEXPECTED_PAYMENT
+        );
+
+        let token_nonce = payment.token_nonce;
+        let attributes: UndelegateAttributes<Self::Api> = self.undelegate_token().get_token_attributes(token_nonce);
+        let delegation_contract = &attributes.delegation_contract;
+        let egld_amount = &attributes.egld_amount;
+
+        let batch_mapper = self.undelegate_nft_batch(token_nonce);
+        require!(
+            !batch_mapper.is_empty() && batch_mapper.take() == self.get_current_undelegation_batch(delegation_contract),
+            ERROR_UNDELEGATION_ALREADY_SENT
+        );
+
+        let contract_data_mapper = self.delegation_contract_data(delegation_contract);
+        self.update_delegation_contract_data(delegation_contract, |data| {
+            data.pending_to_undelegate -= egld_amount;
+            data.total_delegated += egld_amount;
+        });
+
+        self.burn_undelegate_nft(token_nonce);
+
+        let (ls_token_id, _, shares) = self.mint_shares(egld_amount).into_tuple();
+        self.require_min_amount_out(&shares, opt_min_shares_out, ERROR_SHARES_OUT_BELOW_MIN);
 
         let caller = self.blockchain().get_caller();
-        self.send().direct_esdt(&caller, &nft_id, nft_nonce, &nft_amount);
+        self.send().direct_esdt(&caller, &ls_token_id, 0, &shares);
 
         let contract_data = contract_data_mapper.get();
-        self.undelegate_event(&caller, nft_nonce, &attrs, &contract_data);
+        self.cancel_undelegate_event(&caller, token_nonce, &shares, &attributes, &contract_data);
 
-        EsdtTokenPayment::new(nft_id, nft_nonce, nft_amount)
+        EsdtTokenPayment::new(ls_token_id, 0, shares)
     }
 
     /// Initiates the undelegation of the pending amount from the specified Delegation smart contract. This endpoint
@@ -110,6 +353,8 @@ pub trait UndelegateModule:
     ///
     #[endpoint(unDelegatePendingAmount)]
     fn undelegate_pending_amount(&self, delegation_contract: ManagedAddress) {
+        self.require_not_paused(Operation::Undelegate);
+
         let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
         require!(!contract_data_mapper.is_empty(), ERROR_UNEXPECTED_DELEGATION_CONTRACT);
 
@@ -118,10 +363,14 @@ pub trait UndelegateModule:
         require!(egld_amount > BigUint::zero(), ERROR_NO_PENDING_TO_UNDELEGATE);
 
         // update smart contract data to handle concurrent calls to this endpoint
-        self.delegation_contract_data(&delegation_contract).update(|data| {
+        self.update_delegation_contract_data(&delegation_contract, |data| {
             data.pending_to_undelegate -= &egld_amount;
         });
 
+        // undelegations made so far cannot be cancelled anymore
+        self.undelegation_batch(&delegation_contract)
+            .update(|batch| *batch += 1);
+
         let caller = self.blockchain().get_caller();
         let gas_for_async_call = self.get_gas_for_async_call();
         let callback = self
@@ -130,6 +379,63 @@ pub trait UndelegateModule:
         self.undelegate_from_delegation_contract(delegation_contract, egld_amount, gas_for_async_call, callback)
     }
 
+    /// Initiates the undelegation of the pending amounts of all the Delegation smart contracts in the list within a
+    /// single transaction. It performs one asynchronous call per Delegation smart contract with a non-zero pending
+    /// amount, each one with its own callback.
+    ///
+    /// # Notes
+    ///
+    /// - This endpoint can be called by anyone.
+    /// - Delegation smart contracts are visited in the list order while there is enough gas left for another
+    ///   asynchronous call and its callback. The remaining ones can be undelegated in a later call.
+    /// - A failing undelegation only reverts the pending amount and marks as outdated its own Delegation smart contract.
+    ///
+    #[endpoint(unDelegateAllPending)]
+    fn undelegate_all_pending(&self) {
+        self.require_not_paused(Operation::Undelegate);
+
+        // makes sure there is gas for at least one asynchronous call
+        self.get_gas_for_async_call();
+
+        let caller = self.blockchain().get_caller();
+        let mut num_calls = 0usize;
+        for node in self.delegation_contracts_list().iter() {
+            let delegation_contract = node.into_value();
+            let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
+            let egld_amount = contract_data_mapper.get().pending_to_undelegate;
+            if egld_amount == BigUint::zero() {
+                continue;
+            }
+
+            let gas_for_async_call = match self.get_gas_for_batched_async_call() {
+                Some(gas) => gas,
+                None => break,
+            };
+
+            // update smart contract data to handle concurrent calls to this endpoint
+            self.update_delegation_contract_data(&delegation_contract, |data| {
+                data.pending_to_undelegate -= &egld_amount;
+            });
+
+            // undelegations made so far cannot be cancelled anymore
+            self.undelegation_batch(&delegation_contract)
+                .update(|batch| *batch += 1);
+
+            let callback = self
+                .callbacks()
+                .undelegate_all_pending_cb(&caller, &delegation_contract, &egld_amount);
+            self.undelegate_from_delegation_contract_promise(
+                delegation_contract,
+                egld_amount,
+                gas_for_async_call,
+                callback,
+            );
+            num_calls += 1;
+        }
+
+        require!(num_calls > 0, ERROR_NO_PENDING_TO_UNDELEGATE);
+    }
+
     #[callback]
     fn undelegate_pending_amount_cb(
         &self,
@@ -137,10 +443,34 @@ pub trait UndelegateModule:
         delegation_contract: &ManagedAddress,
         egld_amount: &BigUint,
         #[call_result] result: ManagedAsyncCallResult<()>,
+    ) {
+        self.undelegate_pending_amount_result(caller, delegation_contract, egld_amount, result);
+    }
+
+    #[promises_callback]
+    fn undelegate_all_pending_cb(
+        &self,
+        caller: &ManagedAddress,
+        delegation_contract: &ManagedAddress,
+        egld_amount: &BigUint,
+        #[call_result] result: ManagedAsyncCallResult<()>,
+    ) {
+        self.undelegate_pending_amount_result(caller, delegation_contract, egld_amount, result);
+    }
+
+    /// Updates the Delegation smart contract data once the undelegation of a pending amount has finished, either
+    /// successfully or not.
+    ///
+    fn undelegate_pending_amount_result(
+        &self,
+        caller: &ManagedAddress,
+        delegation_contract: &ManagedAddress,
+        egld_amount: &BigUint,
+        result: ManagedAsyncCallResult<()>,
     ) {
         match result {
             ManagedAsyncCallResult::Ok(()) => {
-                self.delegation_contract_data(delegation_contract).update(|data| {
+                self.update_delegation_contract_data(delegation_contract, |data| {
                     data.total_undelegated += egld_amount;
                 });
                 self.total_undelegated().update(|amount| *amount += egld_amount);
@@ -152,7 +482,7 @@ pub trait UndelegateModule:
                 self.undelegate_pending_amount_event(caller, delegation_contract, egld_amount);
             },
             ManagedAsyncCallResult::Err(err) => {
-                self.delegation_contract_data(delegation_contract).update(|data| {
+                self.update_delegation_contract_data(delegation_contract, |data| {
                     data.pending_to_undelegate += egld_amount;
                     data.outdated = true;
                 });
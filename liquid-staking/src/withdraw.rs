@@ -7,33 +7,71 @@ pub trait WithdrawModule:
 {
     /// Allows users to redeem undelegate NFTs in exchange for EGLD once the unbond period has passed. To successfully
     /// redeem the EGLD, it must already be in the liquid staking smart contract. Therefore, the public endpoint
-    /// `withdrawFrom` should have been called prior to using this function. If the redemption is successful, the NFT is
-    /// burned, and the corresponding EGLD amount is sent to the caller.
+    /// `withdrawFrom` should have been called prior to using this function. If the redemption is successful, the NFTs
+    /// are burned, and the aggregated EGLD amount is sent to the caller in a single transfer.
+    ///
+    /// # Arguments
+    ///
+    /// - `opt_receiver`: The optional account receiving the EGLD, which defaults to the caller
+    /// - `opt_endpoint`: The optional endpoint at the receiver smart contract to be called along with the EGLD
+    /// - `args`: The arguments for the receiver endpoint
+    ///
+    /// # Notes
+    ///
+    /// - Many undelegate NFTs can be redeemed at once using a multi-ESDT payment. The whole transaction fails if any of
+    ///   them cannot be redeemed yet.
+    /// - The EGLD can be forwarded to a smart contract endpoint using a transfer and execute. The receiver smart
+    ///   contract must be in the same shard, since the EGLD of a failed cross-shard call would bounce back to this
+    ///   smart contract.
     ///
     #[payable("*")]
+    #[allow_multiple_var_args]
     #[endpoint(withdraw)]
-    fn withdraw(&self) -> BigUint {
-        let payment = self.call_value().single_esdt();
-        require!(
-            payment.token_identifier == self.undelegate_token().get_token_id(),
-            ERROR_UNEXPECTED_PAYMENT
-        );
+    fn withdraw(
+        &self,
+        opt_receiver: OptionalValue<ManagedAddress>,
+        opt_endpoint: OptionalValue<ManagedBuffer>,
+        args: MultiValueEncoded<ManagedBuffer>,
+    ) -> BigUint {
+        self.require_not_paused(Operation::Withdraw);
 
-        let token_nonce = payment.token_nonce;
-        let undelegate_attributes: UndelegateAttributes<Self::Api> =
-            self.undelegate_token().get_token_attributes(token_nonce);
+        let payments = self.call_value().all_esdt_transfers();
+        require!(!payments.is_empty(), ERROR_UNEXPECTED_PAYMENT);
 
-        self.withdraw_internal(&undelegate_attributes);
+        let caller = self.blockchain().get_caller();
+        let receiver = opt_receiver.into_option().unwrap_or_else(|| caller.clone());
 
-        self.burn_undelegate_nft(token_nonce);
+        let undelegate_token_id = self.undelegate_token().get_token_id();
+        let mut token_nonces = ManagedVec::new();
+        let mut egld_amount = BigUint::zero();
 
-        let egld_amount = undelegate_attributes.egld_amount;
-        let caller = self.blockchain().get_caller();
-        self.send().direct_egld(&caller, &egld_amount);
+        for payment in payments.iter() {
+            require!(
+                payment.token_identifier == undelegate_token_id,
+                ERROR_UNEXPECTED_PAYMENT
+            );
 
-        let delegation_contract = undelegate_attributes.delegation_contract;
-        let contract_data = self.delegation_contract_data(&delegation_contract).get();
-        self.withdraw_event(&caller, token_nonce, &contract_data);
+            let token_nonce = payment.token_nonce;
+            let undelegate_attributes: UndelegateAttributes<Self::Api> =
+                self.undelegate_token().get_token_attributes(token_nonce);
+
+            self.withdraw_internal(&undelegate_attributes);
+
+            self.burn_undelegate_nft(token_nonce);
+            self.undelegate_nft_batch(token_nonce).clear();
+
+            egld_amount += &undelegate_attributes.egld_amount;
+            token_nonces.push(token_nonce);
+
+            let delegation_contract = undelegate_attributes.delegation_contract;
+            let contract_data = self.delegation_contract_data(&delegation_contract).get();
+            self.withdraw_event(&caller, &receiver, token_nonce, &contract_data);
+        }
+
+        self.batch_withdraw_event(&caller, &receiver, &token_nonces, &egld_amount);
+
+        let payment = EgldOrEsdtTokenPayment::new(EgldOrEsdtTokenIdentifier::egld(), 0, egld_amount.clone());
+        self.send_or_transfer_execute(&receiver, payment, opt_endpoint, args);
 
         egld_amount
     }
@@ -54,6 +92,8 @@ pub trait WithdrawModule:
     ///
     #[endpoint(withdrawFrom)]
     fn withdraw_from(&self, delegation_contract: ManagedAddress) {
+        self.require_not_paused(Operation::Withdraw);
+
         let contract_data_mapper = self.delegation_contract_data(&delegation_contract);
         require!(!contract_data_mapper.is_empty(), ERROR_UNEXPECTED_DELEGATION_CONTRACT);
 
@@ -73,7 +113,7 @@ pub trait WithdrawModule:
         match result {
             ManagedAsyncCallResult::Ok(()) => {
                 let withdrawn_amount = self.call_value().egld_value().clone_value();
-                self.delegation_contract_data(delegation_contract).update(|data| {
+                self.update_delegation_contract_data(delegation_contract, |data| {
                     data.total_withdrawable += &withdrawn_amount;
                     data.total_undelegated -= &withdrawn_amount;
                 });
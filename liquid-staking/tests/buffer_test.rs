@@ -0,0 +1,190 @@
+mod setup;
+
+use liquid_staking::{
+    constants::{BPS, WAD},
+    errors::*,
+    governance::ProxyTrait as _,
+    model::InstantUndelegateFeeRecipient,
+};
+use multiversx_sc::types::BigUint;
+use multiversx_sc_scenario::{
+    api::StaticApi,
+    scenario_model::{ScCallStep, TxExpect},
+};
+use setup::*;
+
+/// Returns the given amount of EGLD expressed in hundredths
+fn cents(amount: u64) -> RustBigUint {
+    RustBigUint::from(amount) * RustBigUint::from(WAD / 100)
+}
+
+#[test]
+fn delegate_retains_liquidity_buffer() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.set_liquidity_buffer_target(2_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &egld(8), &zero, &zero, &zero, &zero);
+    assert_eq!(setup.liquidity_buffer(), egld(2));
+    assert_eq!(setup.cash_reserve(), egld(10));
+    assert_eq!(setup.ls_token_supply(), egld(10));
+
+    // the buffer is on target, so the whole amount is delegated
+    setup.delegate(USER_ADDRESS_EXPR, &egld(5));
+    assert_eq!(setup.liquidity_buffer(), egld(3));
+    assert_eq!(setup.contract_data(0).pending_to_delegate, to_managed(&egld(12)));
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn delegate_retains_whole_amount_instead_of_leaving_dust() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.set_liquidity_buffer_target(9_500);
+
+    // there are no delegation contracts, but none is needed since the whole amount is retained
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+
+    assert_eq!(setup.liquidity_buffer(), egld(10));
+    assert_eq!(setup.cash_reserve(), egld(10));
+    setup.check_ls_token_balance(USER_ADDRESS_EXPR, &egld(10));
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn instant_undelegate_fee_to_protocol_reserve() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.set_liquidity_buffer_target(5_000);
+    setup.set_instant_undelegate_fee(100, InstantUndelegateFeeRecipient::ProtocolReserve);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    let egld_amount = setup.instant_undelegate(USER_ADDRESS_EXPR, &egld(2));
+
+    assert_eq!(egld_amount, cents(198));
+    assert_eq!(setup.protocol_reserve(), cents(2));
+    assert_eq!(setup.liquidity_buffer(), egld(3));
+    assert_eq!(setup.cash_reserve(), egld(8));
+    assert_eq!(setup.ls_token_supply(), egld(8));
+    setup.check_egld_balance(USER_ADDRESS_EXPR, &(egld(INITIAL_BALANCE - 10) + cents(198)));
+    setup.check_ls_token_balance(USER_ADDRESS_EXPR, &egld(8));
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn instant_undelegate_fee_to_buffer_providers() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.set_liquidity_buffer_target(5_000);
+    setup.set_instant_undelegate_fee(100, InstantUndelegateFeeRecipient::BufferProviders);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    let egld_amount = setup.instant_undelegate(USER_ADDRESS_EXPR, &egld(2));
+
+    // the fee stays in the cash reserve, so the remaining sEGLD is worth more
+    assert_eq!(egld_amount, cents(198));
+    assert_eq!(setup.protocol_reserve(), RustBigUint::default());
+    assert_eq!(setup.liquidity_buffer(), cents(302));
+    assert_eq!(setup.cash_reserve(), cents(802));
+    assert_eq!(setup.ls_token_supply(), egld(8));
+    assert!(setup.exchange_rate() > RustBigUint::from(WAD));
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn instant_undelegate_requires_liquidity_buffer() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.instant_undelegate_expect_err(USER_ADDRESS_EXPR, &egld(1), ERROR_INSUFFICIENT_LIQUIDITY_BUFFER);
+
+    setup.set_liquidity_buffer_target(2_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.instant_undelegate_expect_err(USER_ADDRESS_EXPR, &egld(5), ERROR_INSUFFICIENT_LIQUIDITY_BUFFER);
+}
+
+#[test]
+fn refill_liquidity_buffer_from_withdrawn_funds() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.set_liquidity_buffer_target(2_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+    setup.instant_undelegate(USER_ADDRESS_EXPR, &egld(2));
+
+    // the cash reserve is now 8 EGLD, so the buffer needs 1.6 EGLD
+    assert_eq!(setup.liquidity_buffer(), RustBigUint::default());
+    assert_eq!(setup.liquidity_buffer_deficit(), cents(160));
+
+    setup.refill_liquidity_buffer(None, TxExpect::ok());
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &cents(640), &cents(160), &zero, &zero);
+    assert_eq!(setup.pending_buffer_refill(), cents(160));
+    assert_eq!(setup.liquidity_buffer_deficit(), zero);
+    setup.refill_liquidity_buffer(None, user_error(ERROR_LIQUIDITY_BUFFER_ON_TARGET));
+
+    setup.undelegate_pending_amount(0);
+    setup.withdraw_buffer_refill(0, user_error(ERROR_UNBOND_PERIOD_NOT_ENDED));
+
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.withdraw_buffer_refill(0, user_error(ERROR_TOO_MUCH_EGLD_AMOUNT));
+    setup.withdraw_from(0);
+    setup.withdraw_buffer_refill(0, TxExpect::ok());
+
+    assert_eq!(setup.liquidity_buffer(), cents(160));
+    assert_eq!(setup.pending_buffer_refill(), zero);
+    assert_eq!(setup.total_withdrawable(), zero);
+    setup.withdraw_buffer_refill(0, user_error(ERROR_UNEXPECTED_BUFFER_REFILL_ID));
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn refill_liquidity_buffer_requires_minimum_amount() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+    setup.refill_liquidity_buffer(None, user_error(ERROR_LIQUIDITY_BUFFER_ON_TARGET));
+
+    setup.set_liquidity_buffer_target(500);
+    setup.refill_liquidity_buffer(None, user_error(ERROR_INSUFFICIENT_EGLD_AMOUNT));
+
+    setup.set_liquidity_buffer_target(2_000);
+    setup.refill_liquidity_buffer(Some(&egld(3)), user_error(ERROR_TOO_MUCH_EGLD_AMOUNT));
+    setup.refill_liquidity_buffer(Some(&egld(1)), TxExpect::ok());
+    assert_eq!(setup.liquidity_buffer_deficit(), egld(1));
+}
+
+#[test]
+fn liquidity_buffer_params_are_validated() {
+    let mut setup = LiquidStakingSetup::new();
+
+    let contract_call = setup
+        .ls_contract
+        .set_liquidity_buffer_target(BigUint::<StaticApi>::from(BPS + 1));
+    setup.world.sc_call(
+        ScCallStep::new()
+            .from(ADMIN_ADDRESS_EXPR)
+            .call(contract_call)
+            .expect(user_error(ERROR_VALUE_EXCEEDS_BPS)),
+    );
+
+    let contract_call = setup.ls_contract.set_instant_undelegate_fee(
+        BigUint::<StaticApi>::from(BPS / 10),
+        InstantUndelegateFeeRecipient::ProtocolReserve,
+    );
+    setup.world.sc_call(
+        ScCallStep::new()
+            .from(USER_ADDRESS_EXPR)
+            .call(contract_call)
+            .expect(user_error(b"caller is missing the required role")),
+    );
+}
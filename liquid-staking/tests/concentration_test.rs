@@ -0,0 +1,102 @@
+mod setup;
+
+use liquid_staking::errors::*;
+use multiversx_sc_scenario::scenario_model::TxExpect;
+use setup::*;
+
+#[test]
+fn contract_concentration_limit_moves_delegations() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.add_delegation_contract(200, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(100));
+    setup.set_concentration_limits(6_000, 0);
+
+    // the best contract would hold the whole cash reserve
+    setup.delegate(USER_ADDRESS_EXPR, &egld(100));
+    assert_eq!(setup.contract_data(1).pending_to_delegate, to_managed(&egld(100)));
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(50));
+    assert_eq!(setup.contract_data(0).pending_to_delegate, to_managed(&egld(150)));
+    assert_eq!(setup.concentration(0), RustBigUint::from(6_000u64));
+    assert_eq!(setup.concentration(1), RustBigUint::from(4_000u64));
+
+    // neither contract can receive the delegation without exceeding the limit
+    setup.delegate_expect_err(USER_ADDRESS_EXPR, &egld(200), ERROR_DELEGATION_CONTRACT_NOT_AVAILABLE);
+    assert_eq!(setup.contract_data(0).pending_to_delegate, to_managed(&egld(150)));
+    assert_eq!(setup.contract_data(1).pending_to_delegate, to_managed(&egld(100)));
+}
+
+#[test]
+fn concentration_limits_apply_once_the_cash_reserve_is_bootstrapped() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.add_delegation_contract(200, 800, 1_000);
+
+    // two contracts can never hold the whole cash reserve with a share of 40% each
+    setup.set_concentration_limits(4_000, 0);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(50));
+    setup.delegate(USER_ADDRESS_EXPR, &egld(40));
+    assert_eq!(setup.contract_data(0).pending_to_delegate, to_managed(&egld(90)));
+
+    // the cash reserve reaches the bootstrap threshold
+    setup.delegate(USER_ADDRESS_EXPR, &egld(20));
+    assert_eq!(setup.contract_data(0).pending_to_delegate, to_managed(&egld(90)));
+    assert_eq!(setup.contract_data(1).pending_to_delegate, to_managed(&egld(20)));
+
+    setup.delegate_expect_err(USER_ADDRESS_EXPR, &egld(100), ERROR_DELEGATION_CONTRACT_NOT_AVAILABLE);
+}
+
+#[test]
+fn contract_at_its_limit_is_never_selected() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.add_delegation_contract(200, 800, 1_000);
+    setup.add_delegation_contract(300, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(100));
+    setup.set_concentration_limits(5_000, 0);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(100));
+    assert_eq!(setup.contract_data(0).pending_to_delegate, to_managed(&egld(100)));
+    assert_eq!(setup.concentration(0), RustBigUint::from(5_000u64));
+
+    setup.set_delegation_sampling_model_params(10_000, 1_500, 100);
+
+    // neither the best scored contract nor the sampling exceed the limit
+    for _ in 0..20 {
+        setup.delegate(USER_ADDRESS_EXPR, &egld(1));
+        assert!(setup.concentration(0) <= RustBigUint::from(5_000u64));
+    }
+}
+
+#[test]
+fn operator_concentration_limit_applies_to_migrations() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.add_delegation_contract(200, 800, 1_000);
+    setup.add_delegation_contract(300, 800, 1_000);
+
+    setup.set_operator(0, 0, user_error(ERROR_INVALID_OPERATOR_ID));
+    setup.set_operator(1, 7, TxExpect::ok());
+    setup.set_operator(0, 7, TxExpect::ok());
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(100));
+    setup.set_concentration_limits(0, 5_000);
+
+    // both contracts of the operator are skipped
+    setup.delegate(USER_ADDRESS_EXPR, &egld(100));
+    assert_eq!(setup.contract_data(2).pending_to_delegate, to_managed(&egld(100)));
+    assert_eq!(setup.operator_concentration(7), RustBigUint::from(5_000u64));
+
+    setup.add_to_migration_whitelist(OTHER_USER_ADDRESS_EXPR, 1);
+    setup.delegate_expect_err(OTHER_USER_ADDRESS_EXPR, &egld(50), ERROR_CONCENTRATION_LIMIT_EXCEEDED);
+
+    // once removed from the operator, the contract is only bound by its own share
+    setup.remove_operator(1, TxExpect::ok());
+    setup.remove_operator(1, user_error(ERROR_UNEXPECTED_OPERATOR));
+    setup.delegate(OTHER_USER_ADDRESS_EXPR, &egld(50));
+    assert_eq!(setup.contract_data(1).pending_to_delegate, to_managed(&egld(50)));
+    assert_eq!(setup.operator_concentration(7), RustBigUint::from(4_000u64));
+}
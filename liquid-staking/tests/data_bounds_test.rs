@@ -0,0 +1,86 @@
+mod setup;
+
+use liquid_staking::{errors::*, model::DataBoundsAction};
+use multiversx_sc::types::BigUint;
+use multiversx_sc_scenario::scenario_model::TxExpect;
+use setup::*;
+
+#[test]
+fn out_of_bounds_updates_are_rejected() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.set_data_bounds(5_000, 2_000, 2_000, DataBoundsAction::Reject);
+
+    setup.change_delegation_contract_params_expect(0, 151, 800, 1_000, user_error(ERROR_DATA_OUT_OF_BOUNDS));
+    setup.change_delegation_contract_params_expect(0, 100, 2_001, 1_000, user_error(ERROR_DATA_OUT_OF_BOUNDS));
+    setup.change_delegation_contract_params_expect(0, 100, 800, 2_001, user_error(ERROR_DATA_OUT_OF_BOUNDS));
+
+    setup.change_delegation_contract_params(0, 150, 2_000, 2_000);
+    let contract_data = setup.contract_data(0);
+    assert_eq!(contract_data.total_value_locked, to_managed(&egld(150)));
+    assert_eq!(contract_data.apr, BigUint::from(2_000u64));
+}
+
+#[test]
+fn out_of_bounds_updates_quarantine_the_contract() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.add_delegation_contract(100, 700, 1_000);
+    setup.set_data_bounds(5_000, 2_000, 2_000, DataBoundsAction::Quarantine);
+
+    // a huge APR would move the contract to the top of the list
+    setup.change_delegation_contract_params(1, 100, 10_000, 1_000);
+    let contract_data = setup.contract_data(1);
+    assert!(contract_data.outdated);
+    assert_eq!(contract_data.apr, BigUint::from(700u64));
+
+    // delegations skip the quarantined contract
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    assert_eq!(setup.contract_data(0).pending_to_delegate, to_managed(&egld(10)));
+    assert_eq!(setup.contract_data(1).pending_to_delegate, BigUint::zero());
+
+    // an update within bounds lifts the quarantine
+    setup.change_delegation_contract_params(1, 100, 900, 1_000);
+    let contract_data = setup.contract_data(1);
+    assert!(!contract_data.outdated);
+    assert_eq!(contract_data.apr, BigUint::from(900u64));
+}
+
+#[test]
+fn quarantined_updates_can_be_released() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.set_data_bounds(5_000, 2_000, 2_000, DataBoundsAction::Quarantine);
+
+    setup.release_quarantine(0, user_error(ERROR_DELEGATION_CONTRACT_NOT_QUARANTINED));
+
+    // the discarded update is kept aside, the latest one replacing any previous one
+    setup.change_delegation_contract_params(0, 300, 800, 1_000);
+    setup.change_delegation_contract_params(0, 250, 900, 1_000);
+    let quarantined_data = setup.quarantined_data(0);
+    assert_eq!(quarantined_data.total_value_locked, to_managed(&egld(250)));
+    assert_eq!(quarantined_data.apr, BigUint::from(900u64));
+    assert!(setup.contract_data(0).outdated);
+
+    setup.release_quarantine(0, TxExpect::ok());
+    let contract_data = setup.contract_data(0);
+    assert!(!contract_data.outdated);
+    assert_eq!(contract_data.total_value_locked, to_managed(&egld(250)));
+    assert_eq!(contract_data.apr, BigUint::from(900u64));
+
+    setup.release_quarantine(0, user_error(ERROR_DELEGATION_CONTRACT_NOT_QUARANTINED));
+}
+
+#[test]
+fn updates_within_bounds_drop_the_quarantined_update() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.set_data_bounds(5_000, 2_000, 2_000, DataBoundsAction::Quarantine);
+
+    setup.change_delegation_contract_params(0, 300, 800, 1_000);
+    setup.change_delegation_contract_params(0, 120, 800, 1_000);
+    assert!(!setup.contract_data(0).outdated);
+
+    setup.release_quarantine(0, user_error(ERROR_DELEGATION_CONTRACT_NOT_QUARANTINED));
+    assert_eq!(setup.contract_data(0).total_value_locked, to_managed(&egld(120)));
+}
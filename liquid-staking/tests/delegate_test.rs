@@ -0,0 +1,176 @@
+mod setup;
+
+use delegation_mock::MockEndpoint;
+use liquid_staking::{constants::INITIAL_EXCHANGE_RATE, errors::*, model::Operation};
+use multiversx_sc_scenario::scenario_model::TxExpect;
+use setup::*;
+
+#[test]
+fn delegate_mints_shares_at_initial_exchange_rate() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    let payment = setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    assert_eq!(payment.amount, to_managed(&egld(10)));
+
+    assert_eq!(setup.cash_reserve(), egld(10));
+    assert_eq!(setup.ls_token_supply(), egld(10));
+    assert_eq!(setup.exchange_rate(), RustBigUint::from(INITIAL_EXCHANGE_RATE));
+    setup.check_ls_token_balance(USER_ADDRESS_EXPR, &egld(10));
+    setup.check_egld_balance(USER_ADDRESS_EXPR, &egld(INITIAL_BALANCE - 10));
+
+    let contract_data = setup.contract_data(0);
+    let zero = RustBigUint::default();
+    assert_contract_amounts(&contract_data, &egld(10), &zero, &zero, &zero, &zero);
+}
+
+#[test]
+fn delegate_selects_the_highest_score() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(500, 800, 1_000);
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(5));
+
+    assert_eq!(setup.contract_data(0).pending_to_delegate, to_managed(&egld(0)));
+    assert_eq!(setup.contract_data(1).pending_to_delegate, to_managed(&egld(5)));
+}
+
+#[test]
+fn delegate_requires_minimum_amount() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    let amount = egld(1) - RustBigUint::from(1u64);
+    setup.delegate_expect_err(USER_ADDRESS_EXPR, &amount, ERROR_INSUFFICIENT_EGLD_AMOUNT);
+}
+
+#[test]
+fn delegate_requires_unpaused_delegations() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.pause(&[Operation::Delegate]);
+
+    setup.delegate_expect_err(USER_ADDRESS_EXPR, &egld(1), ERROR_OPERATION_PAUSED);
+}
+
+#[test]
+fn delegate_requires_delegation_contracts() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.delegate_expect_err(USER_ADDRESS_EXPR, &egld(1), ERROR_NO_DELEGATION_CONTRACTS);
+}
+
+#[test]
+fn delegate_skips_outdated_contracts() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.set_failure(0, MockEndpoint::Delegate, true);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(5));
+    setup.delegate_pending_amount(0, None);
+    assert!(setup.contract_data(0).outdated);
+
+    setup.delegate_expect_err(USER_ADDRESS_EXPR, &egld(1), ERROR_DELEGATION_CONTRACT_NOT_AVAILABLE);
+}
+
+#[test]
+fn delegate_pending_amount_success() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &egld(10), &zero, &zero, &zero);
+    assert!(!contract_data.outdated);
+    assert_eq!(setup.delegated_at(0), egld(10));
+}
+
+#[test]
+fn delegate_pending_amount_partial() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, Some(&egld(4)));
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &egld(6), &egld(4), &zero, &zero, &zero);
+    assert_eq!(setup.delegated_at(0), egld(4));
+
+    // leaving dust or delegating more than pending is not allowed
+    let dust = egld(6) - RustBigUint::from(1u64);
+    setup.delegate_pending_amount_expect(0, Some(&dust), user_error(ERROR_WOULD_LEAVE_DUST));
+    setup.delegate_pending_amount_expect(0, Some(&egld(7)), user_error(ERROR_TOO_MUCH_EGLD_AMOUNT));
+}
+
+#[test]
+fn delegate_pending_amount_without_pending_amount() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate_pending_amount_expect(0, None, user_error(ERROR_NO_PENDING_TO_DELEGATE));
+}
+
+#[test]
+fn delegate_pending_amount_failure_reverts_pending_amount() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.set_failure(0, MockEndpoint::Delegate, true);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &egld(10), &zero, &zero, &zero, &zero);
+    assert!(contract_data.outdated);
+    assert_eq!(setup.delegated_at(0), zero);
+
+    // the EGLD never left the Liquid Staking smart contract, so it can be retried once the provider recovers
+    setup.check_egld_balance(LIQUID_STAKING_ADDRESS_EXPR, &egld(10));
+    setup.set_failure(0, MockEndpoint::Delegate, false);
+    setup.delegate_pending_amount(0, None);
+
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &egld(10), &zero, &zero, &zero);
+}
+
+#[test]
+fn delegate_all_pending_isolates_failures() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+
+    // the new Delegation smart contract has a higher score, so it receives the next delegation
+    setup.add_delegation_contract(50, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(6));
+    assert_eq!(setup.contract_data(1).pending_to_delegate, to_managed(&egld(6)));
+
+    setup.set_failure(1, MockEndpoint::Delegate, true);
+    setup.delegate_all_pending(TxExpect::ok());
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &egld(10), &zero, &zero, &zero);
+    assert!(!contract_data.outdated);
+    assert_eq!(setup.delegated_at(0), egld(10));
+
+    let contract_data = setup.contract_data(1);
+    assert_contract_amounts(&contract_data, &egld(6), &zero, &zero, &zero, &zero);
+    assert!(contract_data.outdated);
+    assert_eq!(setup.delegated_at(1), zero);
+    setup.check_egld_balance(LIQUID_STAKING_ADDRESS_EXPR, &egld(6));
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn delegate_all_pending_without_pending_amount() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate_all_pending(user_error(ERROR_NO_PENDING_TO_DELEGATE));
+}
@@ -0,0 +1,692 @@
+mod setup;
+
+use delegation_mock::{MockEndpoint, ProxyTrait as _};
+use liquid_staking::{
+    buffer::ProxyTrait as _, delegate::ProxyTrait as _, governance::ProxyTrait as _, model::*,
+    penalty::ProxyTrait as _, rewards::ProxyTrait as _, storage::StorageModule, undelegate::ProxyTrait as _,
+    withdraw::ProxyTrait as _,
+};
+use multiversx_sc::{
+    codec::multi_types::OptionalValue,
+    types::{BigUint, ManagedAddress, MultiValueEncoded},
+};
+use multiversx_sc_scenario::{api::StaticApi, scenario_model::*, *};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use setup::*;
+
+const NUM_DELEGATION_CONTRACTS: usize = 3;
+const USERS: [&str; 2] = [USER_ADDRESS_EXPR, OTHER_USER_ADDRESS_EXPR];
+const ENDPOINTS: [MockEndpoint; 4] = [
+    MockEndpoint::Delegate,
+    MockEndpoint::Undelegate,
+    MockEndpoint::Withdraw,
+    MockEndpoint::ClaimRewards,
+];
+
+/// The default number of random sequences and operations per sequence, which can be overridden with the
+/// `INVARIANTS_RUNS` and `INVARIANTS_STEPS` environment variables. A single sequence can be replayed by setting
+/// `INVARIANTS_SEED`.
+const DEFAULT_RUNS: u64 = 12;
+const DEFAULT_STEPS: usize = 100;
+
+#[derive(Debug, Clone)]
+enum Operation {
+    Delegate {
+        user: usize,
+        amount: RustBigUint,
+    },
+    DelegatePendingAmount {
+        index: usize,
+    },
+    Undelegate {
+        user: usize,
+        percentage: u64,
+    },
+    InstantUndelegate {
+        user: usize,
+        percentage: u64,
+    },
+    UndelegatePendingAmount {
+        index: usize,
+    },
+    WithdrawFrom {
+        index: usize,
+    },
+    Withdraw {
+        nft: usize,
+    },
+    SplitNft {
+        nft: usize,
+    },
+    MergeNfts {
+        nft: usize,
+        other: usize,
+    },
+    CancelUndelegate {
+        nft: usize,
+    },
+    ClaimRewardsFrom {
+        index: usize,
+        rewards: RustBigUint,
+    },
+    DelegateRewards,
+    Penalize {
+        index: usize,
+        source: PenaltySource,
+    },
+    WithdrawPenalty {
+        penalty_id: u64,
+    },
+    DelegatePenalty {
+        penalty_id: u64,
+    },
+    RefillLiquidityBuffer,
+    WithdrawBufferRefill {
+        refill_id: u64,
+    },
+    ChangeParams {
+        index: usize,
+        total_value_locked: u64,
+    },
+    Blacklist {
+        index: usize,
+    },
+    ToggleFailure {
+        index: usize,
+        endpoint: MockEndpoint,
+        fail: bool,
+    },
+    AdvanceEpoch {
+        epochs: u64,
+    },
+}
+
+/// An undelegate NFT held by one of the users
+struct UndelegateNft {
+    owner: usize,
+    nonce: u64,
+    egld_amount: RustBigUint,
+}
+
+/// Keeps track of what the users own, which cannot be derived from the Liquid Staking smart contract storage
+struct Model {
+    shares: Vec<RustBigUint>,
+    nfts: Vec<UndelegateNft>,
+    next_nft_nonce: u64,
+    epoch: u64,
+    exchange_rate: RustBigUint,
+}
+
+fn random_egld(rng: &mut StdRng, max: u64) -> RustBigUint {
+    // adds some wei on top of the EGLD amount in order to exercise rounding
+    egld(rng.gen_range(1..=max)) + RustBigUint::from(rng.gen_range(0..1_000_000u64))
+}
+
+/// Picks a Delegation smart contract, most of the time among the ones for which the operation makes sense
+fn random_contract<F>(rng: &mut StdRng, contracts: &[DelegationContractData<StaticApi>], filter: F) -> usize
+where
+    F: Fn(&DelegationContractData<StaticApi>) -> bool,
+{
+    let candidates = (0..contracts.len())
+        .filter(|index| filter(&contracts[*index]))
+        .collect::<Vec<_>>();
+    if candidates.is_empty() || rng.gen_bool(0.2) {
+        rng.gen_range(0..contracts.len())
+    } else {
+        candidates[rng.gen_range(0..candidates.len())]
+    }
+}
+
+fn random_operation(
+    rng: &mut StdRng,
+    model: &Model,
+    contracts: &[DelegationContractData<StaticApi>],
+    next_penalty_id: u64,
+    next_refill_id: u64,
+) -> Operation {
+    let index = rng.gen_range(0..NUM_DELEGATION_CONTRACTS);
+    let penalty_id = rng.gen_range(0..next_penalty_id.max(1));
+    let refill_id = rng.gen_range(0..next_refill_id.max(1));
+    match rng.gen_range(0..124u32) {
+        0..=15 => Operation::Delegate {
+            user: rng.gen_range(0..USERS.len()),
+            amount: random_egld(rng, 50),
+        },
+        16..=27 => Operation::DelegatePendingAmount {
+            index: random_contract(rng, contracts, |data| data.pending_to_delegate > 0u64),
+        },
+        28..=39 => Operation::Undelegate {
+            user: rng.gen_range(0..USERS.len()),
+            percentage: rng.gen_range(1..=100),
+        },
+        40..=48 => Operation::UndelegatePendingAmount {
+            index: random_contract(rng, contracts, |data| data.pending_to_undelegate > 0u64),
+        },
+        49..=56 => Operation::WithdrawFrom {
+            index: random_contract(rng, contracts, |data| data.total_undelegated > 0u64),
+        },
+        57..=65 => Operation::Withdraw {
+            nft: rng.gen_range(0..model.nfts.len().max(1)),
+        },
+        66..=71 => Operation::ClaimRewardsFrom {
+            index,
+            rewards: random_egld(rng, 5),
+        },
+        72..=74 => Operation::DelegateRewards,
+        75..=77 => Operation::Penalize {
+            index,
+            source: if rng.gen_bool(0.5) {
+                PenaltySource::FromUndelegate
+            } else {
+                PenaltySource::FromPendingToDelegate
+            },
+        },
+        78..=79 => Operation::WithdrawPenalty { penalty_id },
+        80..=81 => Operation::DelegatePenalty { penalty_id },
+        82..=86 => Operation::ChangeParams {
+            index,
+            total_value_locked: rng.gen_range(1..1_000),
+        },
+        87 => Operation::Blacklist { index },
+        88..=91 => Operation::ToggleFailure {
+            index,
+            endpoint: ENDPOINTS[rng.gen_range(0..ENDPOINTS.len())],
+            fail: rng.gen_bool(0.3),
+        },
+        92..=95 => Operation::SplitNft {
+            nft: rng.gen_range(0..model.nfts.len().max(1)),
+        },
+        96..=99 => Operation::MergeNfts {
+            nft: rng.gen_range(0..model.nfts.len().max(1)),
+            other: rng.gen_range(0..model.nfts.len().max(1)),
+        },
+        100..=107 => Operation::InstantUndelegate {
+            user: rng.gen_range(0..USERS.len()),
+            percentage: rng.gen_range(1..=30),
+        },
+        108..=111 => Operation::RefillLiquidityBuffer,
+        112..=115 => Operation::WithdrawBufferRefill { refill_id },
+        116..=119 => Operation::CancelUndelegate {
+            nft: rng.gen_range(0..model.nfts.len().max(1)),
+        },
+        _ => Operation::AdvanceEpoch {
+            epochs: rng.gen_range(1..=3),
+        },
+    }
+}
+
+/// Executes a transaction without expecting any particular outcome and returns whether it succeeded
+fn try_call(setup: &mut LiquidStakingSetup, step: impl Into<ScCallStep>) -> bool {
+    let step: ScCallStep = step.into();
+    let mut success = false;
+    setup.world.sc_call_use_raw_response(step.no_expect(), |response| {
+        success = response.is_success();
+    });
+    success
+}
+
+fn execute(setup: &mut LiquidStakingSetup, model: &mut Model, operation: &Operation) -> bool {
+    match operation {
+        Operation::Delegate { user, amount } => {
+            let supply_before = setup.ls_token_supply();
+            let step = ScCallStep::new()
+                .from(USERS[*user])
+                .egld_value(amount)
+                .call(setup.ls_contract.delegate(
+                    OptionalValue::<BigUint<StaticApi>>::None,
+                    no_receiver(),
+                    no_endpoint(),
+                    no_args(),
+                ));
+            let success = try_call(setup, step);
+            if success {
+                model.shares[*user] += setup.ls_token_supply() - supply_before;
+            }
+            success
+        },
+        Operation::DelegatePendingAmount { index } => {
+            let delegation_contract = setup.delegation_contract(*index);
+            let step = ScCallStep::new().from(USER_ADDRESS_EXPR).gas_limit(GAS_LIMIT).call(
+                setup
+                    .ls_contract
+                    .delegate_pending_amount(delegation_contract, OptionalValue::<BigUint<StaticApi>>::None),
+            );
+            try_call(setup, step)
+        },
+        Operation::Undelegate { user, percentage } => {
+            let shares = &model.shares[*user] * *percentage / 100u64;
+            if shares == RustBigUint::default() {
+                return false;
+            }
+            let step = ScCallStep::new()
+                .from(USERS[*user])
+                .esdt_transfer(LS_TOKEN_ID_EXPR, 0, &shares)
+                .call(setup.ls_contract.undelegate(
+                    OptionalValue::<ManagedAddress<StaticApi>>::None,
+                    OptionalValue::<BigUint<StaticApi>>::None,
+                    no_receiver(),
+                    no_endpoint(),
+                    no_args(),
+                ));
+            let success = try_call(setup, step);
+            if success {
+                model.shares[*user] -= shares;
+                let nonce = model.next_nft_nonce;
+                model.next_nft_nonce += 1;
+                let attributes = setup.undelegate_attributes(nonce);
+                model.nfts.push(UndelegateNft {
+                    owner: *user,
+                    nonce,
+                    egld_amount: to_rust(&attributes.egld_amount),
+                });
+            }
+            success
+        },
+        Operation::InstantUndelegate { user, percentage } => {
+            let shares = &model.shares[*user] * *percentage / 100u64;
+            if shares == RustBigUint::default() {
+                return false;
+            }
+            let step = ScCallStep::new()
+                .from(USERS[*user])
+                .esdt_transfer(LS_TOKEN_ID_EXPR, 0, &shares)
+                .call(
+                    setup
+                        .ls_contract
+                        .instant_undelegate(OptionalValue::<BigUint<StaticApi>>::None),
+                );
+            let success = try_call(setup, step);
+            if success {
+                model.shares[*user] -= shares;
+            }
+            success
+        },
+        Operation::UndelegatePendingAmount { index } => {
+            let delegation_contract = setup.delegation_contract(*index);
+            let step = ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .gas_limit(GAS_LIMIT)
+                .call(setup.ls_contract.undelegate_pending_amount(delegation_contract));
+            try_call(setup, step)
+        },
+        Operation::WithdrawFrom { index } => {
+            let delegation_contract = setup.delegation_contract(*index);
+            let step = ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .gas_limit(GAS_LIMIT)
+                .call(setup.ls_contract.withdraw_from(delegation_contract));
+            try_call(setup, step)
+        },
+        Operation::Withdraw { nft } => {
+            if *nft >= model.nfts.len() {
+                return false;
+            }
+            let owner = model.nfts[*nft].owner;
+            let nonce = model.nfts[*nft].nonce;
+            let step = ScCallStep::new()
+                .from(USERS[owner])
+                .esdt_transfer(UNDELEGATE_TOKEN_ID_EXPR, nonce, "1")
+                .call(setup.ls_contract.withdraw(no_receiver(), no_endpoint(), no_args()));
+            let success = try_call(setup, step);
+            if success {
+                model.nfts.swap_remove(*nft);
+            }
+            success
+        },
+        Operation::SplitNft { nft } => {
+            if *nft >= model.nfts.len() {
+                return false;
+            }
+            let owner = model.nfts[*nft].owner;
+            let nonce = model.nfts[*nft].nonce;
+            let first_amount = &model.nfts[*nft].egld_amount / 3u64;
+            let second_amount = &model.nfts[*nft].egld_amount - &first_amount;
+            let amounts =
+                MultiValueEncoded::<StaticApi, _>::from_iter([to_managed(&first_amount), to_managed(&second_amount)]);
+            let step = ScCallStep::new()
+                .from(USERS[owner])
+                .esdt_transfer(UNDELEGATE_TOKEN_ID_EXPR, nonce, "1")
+                .call(setup.ls_contract.split_undelegate_nft(amounts));
+            let success = try_call(setup, step);
+            if success {
+                model.nfts.swap_remove(*nft);
+                for egld_amount in [first_amount, second_amount] {
+                    let nonce = model.next_nft_nonce;
+                    model.next_nft_nonce += 1;
+                    model.nfts.push(UndelegateNft {
+                        owner,
+                        nonce,
+                        egld_amount,
+                    });
+                }
+            }
+            success
+        },
+        Operation::MergeNfts { nft, other } => {
+            if *nft >= model.nfts.len() || *other >= model.nfts.len() || nft == other {
+                return false;
+            }
+            let owner = model.nfts[*nft].owner;
+            if model.nfts[*other].owner != owner {
+                return false;
+            }
+            let nonces = [model.nfts[*nft].nonce, model.nfts[*other].nonce];
+            let step = ScCallStep::new()
+                .from(USERS[owner])
+                .multi_esdt_transfer(undelegate_nfts(&nonces))
+                .call(setup.ls_contract.merge_undelegate_nfts());
+            let success = try_call(setup, step);
+            if success {
+                let egld_amount = &model.nfts[*nft].egld_amount + &model.nfts[*other].egld_amount;
+                model.nfts.retain(|held| !nonces.contains(&held.nonce));
+                let nonce = model.next_nft_nonce;
+                model.next_nft_nonce += 1;
+                model.nfts.push(UndelegateNft {
+                    owner,
+                    nonce,
+                    egld_amount,
+                });
+            }
+            success
+        },
+        Operation::CancelUndelegate { nft } => {
+            if *nft >= model.nfts.len() {
+                return false;
+            }
+            let owner = model.nfts[*nft].owner;
+            let nonce = model.nfts[*nft].nonce;
+            let supply_before = setup.ls_token_supply();
+            let step = ScCallStep::new()
+                .from(USERS[owner])
+                .esdt_transfer(UNDELEGATE_TOKEN_ID_EXPR, nonce, "1")
+                .call(
+                    setup
+                        .ls_contract
+                        .cancel_undelegate(OptionalValue::<BigUint<StaticApi>>::None),
+                );
+            let success = try_call(setup, step);
+            if success {
+                model.shares[owner] += setup.ls_token_supply() - supply_before;
+                model.nfts.swap_remove(*nft);
+            }
+            success
+        },
+        Operation::ClaimRewardsFrom { index, rewards } => {
+            setup.add_rewards(*index, rewards);
+            let delegation_contract = setup.delegation_contract(*index);
+            let step = ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .gas_limit(GAS_LIMIT)
+                .call(setup.ls_contract.claim_rewards_from(delegation_contract));
+            try_call(setup, step)
+        },
+        Operation::DelegateRewards => {
+            let step = ScCallStep::new().from(USER_ADDRESS_EXPR).gas_limit(GAS_LIMIT).call(
+                setup
+                    .ls_contract
+                    .delegate_rewards(OptionalValue::<BigUint<StaticApi>>::None),
+            );
+            try_call(setup, step)
+        },
+        Operation::Penalize { index, source } => {
+            let delegation_contract = setup.delegation_contract(*index);
+            let step = ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(setup.ls_contract.penalize(
+                    delegation_contract,
+                    *source,
+                    OptionalValue::<BigUint<StaticApi>>::None,
+                ));
+            try_call(setup, step)
+        },
+        Operation::WithdrawPenalty { penalty_id } => {
+            let step = ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .call(setup.ls_contract.withdraw_penalty(*penalty_id));
+            try_call(setup, step)
+        },
+        Operation::DelegatePenalty { penalty_id } => {
+            let step = ScCallStep::new().from(USER_ADDRESS_EXPR).call(
+                setup
+                    .ls_contract
+                    .delegate_penalty(*penalty_id, OptionalValue::<BigUint<StaticApi>>::None),
+            );
+            try_call(setup, step)
+        },
+        Operation::RefillLiquidityBuffer => {
+            let step = ScCallStep::new().from(USER_ADDRESS_EXPR).call(
+                setup
+                    .ls_contract
+                    .refill_liquidity_buffer(OptionalValue::<BigUint<StaticApi>>::None),
+            );
+            try_call(setup, step)
+        },
+        Operation::WithdrawBufferRefill { refill_id } => {
+            let step = ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .call(setup.ls_contract.withdraw_buffer_refill(*refill_id));
+            try_call(setup, step)
+        },
+        Operation::ChangeParams {
+            index,
+            total_value_locked,
+        } => {
+            if setup.contract_data(*index).blacklisted {
+                return false;
+            }
+            setup.change_delegation_contract_params(*index, *total_value_locked, 800, 1_000);
+            true
+        },
+        Operation::Blacklist { index } => {
+            let delegation_contract = setup.delegation_contract(*index);
+            let step = ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(setup.ls_contract.blacklist_delegation_contract(delegation_contract));
+            try_call(setup, step)
+        },
+        Operation::ToggleFailure { index, endpoint, fail } => {
+            let contract_call = setup.delegation_contracts[*index].set_failure(*endpoint, *fail);
+            let step = ScCallStep::new().from(ADMIN_ADDRESS_EXPR).call(contract_call);
+            try_call(setup, step)
+        },
+        Operation::AdvanceEpoch { epochs } => {
+            model.epoch += epochs;
+            setup.set_block_epoch(model.epoch);
+            true
+        },
+    }
+}
+
+/// Checks the on-chain invariants together with the ones that involve balances owned by the users
+fn check_invariants(setup: &mut LiquidStakingSetup, model: &mut Model, trace: &[(Operation, bool)]) {
+    let violations = setup.invariant_violations();
+    assert!(violations.is_empty(), "{violations:?} after {trace:#?}");
+
+    // every sEGLD in circulation is accounted for
+    let total_shares = model.shares.iter().sum::<RustBigUint>();
+    let ls_token_supply = setup.ls_token_supply();
+    assert_eq!(total_shares, ls_token_supply, "sEGLD supply after {trace:#?}");
+    for (user, shares) in model.shares.iter().enumerate() {
+        setup.check_ls_token_balance(USERS[user], shares);
+    }
+
+    // the EGLD backing the sEGLD supply covers it at the initial exchange rate
+    let backing = setup.cash_reserve() - setup.unvested_rewards();
+    assert!(backing >= ls_token_supply, "exchange rate identity after {trace:#?}");
+
+    // every undelegated amount is owed to an undelegate NFT holder, to a penalty that has not been withdrawn or to the
+    // liquidity buffer
+    let mut undelegated = RustBigUint::default();
+    for index in 0..NUM_DELEGATION_CONTRACTS {
+        let contract_data = setup.contract_data(index);
+        let amounts = [
+            contract_data.pending_to_undelegate,
+            contract_data.total_undelegated,
+            contract_data.total_withdrawable,
+        ];
+        for amount in amounts {
+            undelegated += to_rust(&amount);
+        }
+    }
+    let mut owed = model.nfts.iter().map(|nft| &nft.egld_amount).sum::<RustBigUint>();
+    for penalty_id in 0..setup.next_penalty_id() {
+        if setup.is_penalty_cleared(penalty_id) {
+            continue;
+        }
+        let penalty = setup.penalty(penalty_id);
+        if !penalty.withdrawn {
+            owed += to_rust(&penalty.attributes.egld_amount);
+        }
+    }
+    owed += setup.pending_buffer_refill();
+    assert_eq!(undelegated, owed, "undelegated amounts after {trace:#?}");
+
+    // the exchange rate never decreases, otherwise some users would be able to extract value from others
+    let exchange_rate = setup.exchange_rate();
+    assert!(exchange_rate >= model.exchange_rate, "exchange rate after {trace:#?}");
+    model.exchange_rate = exchange_rate;
+}
+
+fn run_random_operations(seed: u64, steps: usize) {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut setup = LiquidStakingSetup::new();
+    for _ in 0..NUM_DELEGATION_CONTRACTS {
+        let total_value_locked = rng.gen_range(1..1_000);
+        setup.add_delegation_contract(total_value_locked, 800, 1_000);
+    }
+
+    setup.set_liquidity_buffer_target(rng.gen_range(0..3_000));
+    let recipient = if rng.gen_bool(0.5) {
+        InstantUndelegateFeeRecipient::ProtocolReserve
+    } else {
+        InstantUndelegateFeeRecipient::BufferProviders
+    };
+    setup.set_instant_undelegate_fee(rng.gen_range(0..500), recipient);
+
+    let mut model = Model {
+        shares: vec![RustBigUint::default(); USERS.len()],
+        nfts: Vec::new(),
+        next_nft_nonce: 1,
+        epoch: 1,
+        exchange_rate: setup.exchange_rate(),
+    };
+
+    let mut trace = Vec::with_capacity(steps);
+    for _ in 0..steps {
+        let contracts = (0..NUM_DELEGATION_CONTRACTS)
+            .map(|index| setup.contract_data(index))
+            .collect::<Vec<_>>();
+        let next_penalty_id = setup.next_penalty_id();
+        let next_refill_id = setup.next_buffer_refill_id();
+        let operation = random_operation(&mut rng, &model, &contracts, next_penalty_id, next_refill_id);
+        let success = execute(&mut setup, &mut model, &operation);
+        trace.push((operation, success));
+        check_invariants(&mut setup, &mut model, &trace);
+    }
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+#[test]
+fn random_operations_preserve_invariants() {
+    let steps = env_or("INVARIANTS_STEPS", DEFAULT_STEPS);
+    if let Some(seed) = std::env::var("INVARIANTS_SEED").ok().and_then(|seed| seed.parse().ok()) {
+        run_random_operations(seed, steps);
+        return;
+    }
+
+    for seed in 0..env_or("INVARIANTS_RUNS", DEFAULT_RUNS) {
+        let result = std::panic::catch_unwind(|| run_random_operations(seed, steps));
+        assert!(result.is_ok(), "invariants broken, replay with INVARIANTS_SEED={seed}");
+    }
+}
+
+#[test]
+fn invariants_hold_after_full_cycle() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+    setup.add_rewards(0, &egld(2));
+    setup.claim_rewards_from(0);
+    setup.delegate_rewards(None);
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(4));
+    setup.undelegate_pending_amount(0);
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.withdraw_from(0);
+
+    assert!(setup.invariant_violations().is_empty());
+
+    setup.withdraw(USER_ADDRESS_EXPR, 1);
+
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn invariants_detect_accounting_mismatch() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+
+    // the delegated amount is overridden without updating the cash reserve
+    setup.set_delegated_amount(0, &egld(12));
+
+    let violations = setup.invariant_violations();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].invariant, AccountingInvariant::CashReserve);
+    assert_eq!(violations[0].expected, to_managed(&egld(12)));
+    assert_eq!(violations[0].actual, to_managed(&egld(10)));
+
+    // moving the whole delegated amount to the withdrawable amount is not backed by any EGLD balance
+    setup
+        .world
+        .whitebox_call(&setup.ls_whitebox, ScCallStep::new().from(ADMIN_ADDRESS_EXPR), |sc| {
+            let contract = ManagedAddress::from(&setup.delegation_contracts[0].to_address());
+            sc.delegation_contract_data(&contract).update(|data| {
+                data.total_withdrawable = data.total_delegated.clone();
+                data.total_delegated = BigUint::zero();
+            });
+            sc.total_withdrawable().set(BigUint::from(&egld(12)));
+        });
+
+    let invariants = setup
+        .invariant_violations()
+        .into_iter()
+        .map(|violation| violation.invariant)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        invariants,
+        vec![AccountingInvariant::CashReserve, AccountingInvariant::EgldBalance,]
+    );
+}
+
+#[test]
+fn invariants_detect_unbacked_supply() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+
+    // the sEGLD supply is overridden without minting, so it is no longer backed
+    setup
+        .world
+        .whitebox_call(&setup.ls_whitebox, ScCallStep::new().from(ADMIN_ADDRESS_EXPR), |sc| {
+            sc.ls_token_supply().set(BigUint::from(&egld(11)));
+        });
+
+    let violations = setup.invariant_violations();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].invariant, AccountingInvariant::ExchangeRate);
+    assert_eq!(violations[0].expected, to_managed(&egld(11)));
+    assert_eq!(violations[0].actual, to_managed(&egld(10)));
+}
@@ -0,0 +1,166 @@
+mod setup;
+
+use liquid_staking::{
+    constants::MAX_SELECTION_SCAN_SIZE, errors::*, governance::ProxyTrait as _, model::DelegationScoreMethod,
+};
+use multiversx_sc::codec::multi_types::OptionalValue;
+use multiversx_sc_scenario::scenario_model::*;
+use setup::*;
+
+const NR_DELEGATION_CONTRACTS: usize = 120;
+
+// every 30th contract shares the same total value locked, hence the same delegation score
+fn add_delegation_contract(setup: &mut LiquidStakingSetup, index: u64) {
+    setup.add_delegation_contract(100 + index % 30 * 100, 800, 1_000);
+}
+
+fn assert_sorted(scores: &[u64]) {
+    assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]));
+}
+
+#[test]
+fn list_holds_more_than_one_hundred_contracts() {
+    let mut setup = LiquidStakingSetup::new();
+    for index in 0..NR_DELEGATION_CONTRACTS as u64 {
+        add_delegation_contract(&mut setup, index);
+    }
+
+    let scores = setup.indexed_delegation_scores();
+    assert_eq!(scores.len(), NR_DELEGATION_CONTRACTS);
+    assert_sorted(&scores);
+
+    // the newest contract has priority over older contracts with the same score
+    let list = setup.delegation_contracts_list();
+    let newest = list.iter().position(|index| *index == 91).unwrap();
+    assert_eq!(list[newest..newest + 4], [91, 61, 31, 1]);
+}
+
+#[test]
+fn list_stays_sorted_when_contracts_move() {
+    let mut setup = LiquidStakingSetup::new();
+    for index in 0..NR_DELEGATION_CONTRACTS as u64 {
+        add_delegation_contract(&mut setup, index);
+    }
+
+    // move contracts to the top, to the bottom and next to an existing score
+    setup.change_delegation_contract_params(5, 0, 800, 1_000);
+    setup.change_delegation_contract_params(0, 100_000, 800, 1_000);
+    setup.change_delegation_contract_params(119, 200, 800, 1_000);
+    setup.blacklist_delegation_contract(60);
+
+    let scores = setup.indexed_delegation_scores();
+    assert_eq!(scores.len(), NR_DELEGATION_CONTRACTS - 1);
+    assert_sorted(&scores);
+
+    let list = setup.delegation_contracts_list();
+    assert_eq!(list.first(), Some(&5));
+    assert_eq!(list.last(), Some(&0));
+    assert!(!list.contains(&60));
+
+    let moved = list.iter().position(|index| *index == 119).unwrap();
+    assert_eq!(list[moved..moved + 5], [119, 91, 61, 31, 1]);
+}
+
+#[test]
+fn list_orders_scores_far_apart() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(999_000, 800, 1_000);
+    setup.add_delegation_contract(0, 800, 1_000);
+    setup.add_delegation_contract(500_000, 800, 1_000);
+    setup.add_delegation_contract(700_000, 800, 1_000);
+
+    assert_eq!(setup.indexed_delegation_scores(), vec![10_000, 5_000, 3_000, 10]);
+    assert_eq!(setup.delegation_contracts_list(), vec![1, 2, 3, 0]);
+
+    // the lowest score becomes free once its only contract leaves the list
+    setup.blacklist_delegation_contract(0);
+    setup.change_delegation_contract_params(2, 999_000, 800, 1_000);
+    assert_eq!(setup.indexed_delegation_scores(), vec![10_000, 3_000, 10]);
+    assert_eq!(setup.delegation_contracts_list(), vec![1, 3, 2]);
+}
+
+#[test]
+fn list_is_sorted_in_batches() {
+    let mut setup = LiquidStakingSetup::new();
+    for index in 0..NR_DELEGATION_CONTRACTS as u64 {
+        add_delegation_contract(&mut setup, index);
+    }
+    setup.change_delegation_contract_nr_nodes(0, 10);
+
+    // only the first batch is rescored along with the model change
+    setup.set_tvl_per_node_domain(0, 10_000, TxExpect::ok());
+    setup.set_delegation_score_model_params(DelegationScoreMethod::TvlPerNode, 0, 0, TxExpect::ok());
+    assert_ne!(setup.sort_cursor(), 0);
+    assert_sorted(&setup.indexed_delegation_scores());
+
+    setup.sort_delegation_contracts_list(40, TxExpect::ok());
+    assert_ne!(setup.sort_cursor(), 0);
+
+    // contracts moved beyond the cursor are visited twice
+    setup.sort_delegation_contracts_list(2 * NR_DELEGATION_CONTRACTS, TxExpect::ok());
+    assert_eq!(setup.sort_cursor(), 0);
+    setup.sort_delegation_contracts_list(100, user_error(ERROR_LIST_ALREADY_SORTED));
+
+    // every contract holds its score under the new model
+    let scores = setup.indexed_delegation_scores();
+    assert_eq!(scores.len(), NR_DELEGATION_CONTRACTS);
+    assert_sorted(&scores);
+    assert_eq!(scores[0], 9_990);
+    for index in 1..NR_DELEGATION_CONTRACTS {
+        let total_value_locked = 100 + index as u64 % 30 * 100;
+        let score = 10_000 - total_value_locked;
+        assert_eq!(
+            to_rust(&setup.contract_data(index).delegation_score),
+            RustBigUint::from(score)
+        );
+    }
+}
+
+#[test]
+fn selection_finds_contracts_beyond_the_scanned_ones() {
+    let mut setup = LiquidStakingSetup::new();
+    let nr_delegation_contracts = 2 * MAX_SELECTION_SCAN_SIZE + 5;
+    for index in 0..nr_delegation_contracts as u64 {
+        setup.add_delegation_contract(100 + index * 100, 800, 1_000);
+    }
+
+    // neither the best nor the worst scored contracts are inspected from the list
+    let target = setup.delegation_contracts_list()[MAX_SELECTION_SCAN_SIZE + 2];
+
+    // every other contract is full
+    for index in (0..nr_delegation_contracts).filter(|index| *index != target) {
+        let contract_data = setup.contract_data(index);
+        let delegation_contract = setup.delegation_contract(index);
+        let contract_call = setup.ls_contract.change_delegation_contract_params(
+            delegation_contract,
+            contract_data.total_value_locked.clone(),
+            1u64,
+            contract_data.apr,
+            contract_data.service_fee,
+            OptionalValue::Some(contract_data.total_value_locked),
+        );
+        setup
+            .world
+            .sc_call(ScCallStep::new().from(DATA_MANAGER_ADDRESS_EXPR).call(contract_call));
+    }
+
+    let list = setup.delegation_contracts_list();
+    let position = list.iter().position(|index| *index == target).unwrap();
+    assert!(position >= MAX_SELECTION_SCAN_SIZE && position < list.len() - MAX_SELECTION_SCAN_SIZE);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    assert_eq!(setup.contract_data(target).pending_to_delegate, to_managed(&egld(10)));
+    setup.delegate_pending_amount(target, None);
+
+    // the only contract with delegated EGLD serves the undelegation
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(4));
+    assert_eq!(setup.contract_data(target).total_delegated, to_managed(&egld(6)));
+    assert_eq!(setup.contract_data(target).pending_to_undelegate, to_managed(&egld(4)));
+
+    // amounts no contract can serve are still rejected, e.g. when they would leave dust behind
+    setup.undelegate_expect_err(
+        USER_ADDRESS_EXPR,
+        &(egld(11) / 2u64),
+        ERROR_DELEGATION_CONTRACT_NOT_AVAILABLE,
+    );
+}
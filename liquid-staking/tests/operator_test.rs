@@ -0,0 +1,122 @@
+mod setup;
+
+use liquid_staking::{constants::MAX_OPERATOR_CONTRACTS, errors::*};
+use multiversx_sc_scenario::scenario_model::TxExpect;
+use setup::*;
+
+#[test]
+fn operator_data_aggregates_contracts() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.add_delegation_contract(200, 800, 1_000);
+    setup.add_delegation_contract(300, 800, 1_000);
+
+    setup.set_operator(0, 7, TxExpect::ok());
+    setup.set_operator(1, 7, TxExpect::ok());
+    let operator_data = setup.operator_data(7);
+    assert_eq!(operator_data.total_value_locked, to_managed(&egld(300)));
+    assert_eq!(operator_data.nr_nodes, 2);
+
+    // the aggregates follow the data of the contracts
+    setup.change_delegation_contract_params(1, 500, 800, 1_000);
+    assert_eq!(setup.operator_data(7).total_value_locked, to_managed(&egld(600)));
+
+    // a contract moving to another operator leaves its previous operator
+    setup.set_operator(1, 8, TxExpect::ok());
+    assert_eq!(setup.operator_data(7).total_value_locked, to_managed(&egld(100)));
+    assert_eq!(setup.operator_data(8).total_value_locked, to_managed(&egld(500)));
+
+    setup.remove_operator(0, TxExpect::ok());
+    assert!(!setup.has_operator_data(7));
+}
+
+#[test]
+fn operator_contracts_are_scored_by_operator_data() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.add_delegation_contract(200, 800, 1_000);
+    setup.add_delegation_contract(300, 800, 1_000);
+    assert_eq!(setup.delegation_contracts_list(), vec![0, 1, 2]);
+
+    // splitting 400 EGLD among two contracts does not beat a single contract holding 300 EGLD
+    setup.set_operator(0, 7, TxExpect::ok());
+    setup.set_operator(2, 7, TxExpect::ok());
+    assert_eq!(
+        setup.contract_data(0).delegation_score,
+        to_managed(&RustBigUint::from(9_996u64))
+    );
+    assert_eq!(
+        setup.contract_data(2).delegation_score,
+        to_managed(&RustBigUint::from(9_996u64))
+    );
+    assert_eq!(setup.delegation_contracts_list(), vec![1, 2, 0]);
+
+    // the whole operator is rescored when one of its contracts changes
+    setup.change_delegation_contract_params(2, 0, 800, 1_000);
+    assert_eq!(
+        setup.contract_data(0).delegation_score,
+        to_managed(&RustBigUint::from(9_999u64))
+    );
+    assert_eq!(setup.delegation_contracts_list(), vec![2, 0, 1]);
+
+    setup.remove_operator(2, TxExpect::ok());
+    assert_eq!(
+        setup.contract_data(0).delegation_score,
+        to_managed(&RustBigUint::from(9_999u64))
+    );
+    assert_eq!(
+        setup.contract_data(2).delegation_score,
+        to_managed(&RustBigUint::from(10_000u64))
+    );
+}
+
+#[test]
+fn operator_allocation_follows_delegations_and_rebalances() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(30));
+    setup.delegate_pending_amount(0, None);
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.set_operator(0, 7, TxExpect::ok());
+    setup.set_operator(1, 8, TxExpect::ok());
+    assert_eq!(setup.operator_data(7).allocation, to_managed(&egld(30)));
+    assert_eq!(setup.operator_data(8).allocation, to_managed(&egld(0)));
+
+    // the in-flight amount counts for the destination operator
+    setup.set_rebalance_params(0, &egld(1), 0);
+    setup.rebalance(0, 1, &egld(10), TxExpect::ok());
+    assert_eq!(setup.operator_data(7).allocation, to_managed(&egld(20)));
+    assert_eq!(setup.operator_data(8).allocation, to_managed(&egld(10)));
+
+    setup.undelegate_pending_amount(0);
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.withdraw_from(0);
+    setup.complete_rebalance(0, TxExpect::ok());
+    setup.delegate_pending_amount(1, None);
+    assert_eq!(setup.operator_data(7).allocation, to_managed(&egld(20)));
+    assert_eq!(setup.operator_data(8).allocation, to_managed(&egld(10)));
+    assert_eq!(setup.operator_concentration(8), RustBigUint::from(3_333u64));
+
+    // a contract moving to another operator takes its allocation along
+    setup.set_operator(1, 7, TxExpect::ok());
+    assert_eq!(setup.operator_data(7).allocation, to_managed(&egld(30)));
+    assert!(!setup.has_operator_data(8));
+}
+
+#[test]
+fn operator_runs_a_bounded_number_of_contracts() {
+    let mut setup = LiquidStakingSetup::new();
+    for index in 0..=MAX_OPERATOR_CONTRACTS {
+        setup.add_delegation_contract(100, 800, 1_000);
+        if index < MAX_OPERATOR_CONTRACTS {
+            setup.set_operator(index, 7, TxExpect::ok());
+        }
+    }
+
+    setup.set_operator(MAX_OPERATOR_CONTRACTS, 7, user_error(ERROR_TOO_MANY_OPERATOR_CONTRACTS));
+
+    // assigning a contract of the operator again does not grow it
+    setup.set_operator(0, 7, TxExpect::ok());
+    assert_eq!(setup.operator_data(7).nr_nodes, MAX_OPERATOR_CONTRACTS as u64);
+}
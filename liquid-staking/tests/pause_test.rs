@@ -0,0 +1,155 @@
+mod setup;
+
+use liquid_staking::{
+    admin::Role,
+    errors::*,
+    governance::ProxyTrait as _,
+    migration::ProxyTrait as _,
+    model::{Operation, PauseStatus},
+};
+use multiversx_sc::types::{ManagedAddress, MultiValueEncoded};
+use multiversx_sc_scenario::scenario_model::{AddressValue, ScCallStep, TxExpect};
+use setup::*;
+
+fn pause_from(setup: &mut LiquidStakingSetup, from: &str, expect: TxExpect) {
+    let contract_call = setup.ls_contract.pause(MultiValueEncoded::new());
+    setup
+        .world
+        .sc_call(ScCallStep::new().from(from).call(contract_call).expect(expect));
+}
+
+fn unpause_from(setup: &mut LiquidStakingSetup, from: &str, expect: TxExpect) {
+    let contract_call = setup.ls_contract.unpause(MultiValueEncoded::new());
+    setup
+        .world
+        .sc_call(ScCallStep::new().from(from).call(contract_call).expect(expect));
+}
+
+#[test]
+fn paused_delegations_keep_exits_open() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+
+    setup.pause(&[Operation::Delegate]);
+    assert_eq!(
+        setup.pause_status(),
+        PauseStatus {
+            delegate: true,
+            undelegate: false,
+            withdraw: false,
+            rewards: false,
+            penalties: false,
+            migration: false,
+        }
+    );
+    setup.delegate_expect_err(USER_ADDRESS_EXPR, &egld(1), ERROR_OPERATION_PAUSED);
+
+    let nonce = setup.undelegate(USER_ADDRESS_EXPR, &egld(4)).token_nonce;
+    setup.undelegate_pending_amount(0);
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.withdraw_from(0);
+    assert_eq!(setup.withdraw(USER_ADDRESS_EXPR, nonce), egld(4));
+
+    setup.unpause(&[Operation::Delegate]);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(1));
+}
+
+#[test]
+fn paused_operations_are_rejected() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+    let nonce = setup.undelegate(USER_ADDRESS_EXPR, &egld(4)).token_nonce;
+
+    setup.pause(&[Operation::Undelegate, Operation::Withdraw, Operation::Rewards]);
+    setup.undelegate_expect_err(USER_ADDRESS_EXPR, &egld(1), ERROR_OPERATION_PAUSED);
+    setup.withdraw_expect_err(USER_ADDRESS_EXPR, nonce, ERROR_OPERATION_PAUSED);
+    setup.withdraw_from_expect(0, user_error(ERROR_OPERATION_PAUSED));
+    setup.claim_rewards_from_expect(0, user_error(ERROR_OPERATION_PAUSED));
+
+    // delegations are still open
+    setup.delegate(USER_ADDRESS_EXPR, &egld(1));
+}
+
+#[test]
+fn paused_migration_only_affects_whitelisted_users() {
+    let mut setup = LiquidStakingSetup::new();
+    let delegation_contract = setup.add_delegation_contract(100, 800, 1_000);
+    let user = AddressValue::from(OTHER_USER_ADDRESS_EXPR).to_address();
+    let contract_call = setup
+        .ls_contract
+        .add_to_migration_whitelist(ManagedAddress::from(user), ManagedAddress::from(delegation_contract));
+    setup
+        .world
+        .sc_call(ScCallStep::new().from(ADMIN_ADDRESS_EXPR).call(contract_call));
+
+    setup.pause(&[Operation::Migration]);
+    setup.delegate_expect_err(OTHER_USER_ADDRESS_EXPR, &egld(1), ERROR_OPERATION_PAUSED);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(1));
+}
+
+#[test]
+fn pause_and_unpause_permissions() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.grant_role(Role::Guardian, USER_ADDRESS_EXPR, TxExpect::ok());
+
+    pause_from(
+        &mut setup,
+        OTHER_USER_ADDRESS_EXPR,
+        user_error(ERROR_NOT_PAUSER_OR_GUARDIAN),
+    );
+
+    // a guardian can pause every operation but cannot unpause them
+    pause_from(&mut setup, USER_ADDRESS_EXPR, TxExpect::ok());
+    assert_eq!(
+        setup.pause_status(),
+        PauseStatus {
+            delegate: true,
+            undelegate: true,
+            withdraw: true,
+            rewards: true,
+            penalties: true,
+            migration: true,
+        }
+    );
+    unpause_from(
+        &mut setup,
+        USER_ADDRESS_EXPR,
+        user_error(b"caller is missing the required role"),
+    );
+
+    unpause_from(&mut setup, ADMIN_ADDRESS_EXPR, TxExpect::ok());
+    assert!(!setup.pause_status().delegate);
+}
+
+#[test]
+fn upgrade_keeps_exits_open() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+    setup.pause(&[Operation::Rewards]);
+
+    setup.upgrade();
+    assert_eq!(
+        setup.pause_status(),
+        PauseStatus {
+            delegate: true,
+            undelegate: false,
+            withdraw: false,
+            rewards: true,
+            penalties: false,
+            migration: true,
+        }
+    );
+    setup.delegate_expect_err(USER_ADDRESS_EXPR, &egld(1), ERROR_OPERATION_PAUSED);
+
+    let nonce = setup.undelegate(USER_ADDRESS_EXPR, &egld(4)).token_nonce;
+    setup.undelegate_pending_amount(0);
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.withdraw_from(0);
+    assert_eq!(setup.withdraw(USER_ADDRESS_EXPR, nonce), egld(4));
+}
@@ -0,0 +1,210 @@
+mod setup;
+
+use liquid_staking::{errors::*, model::*};
+use multiversx_sc_scenario::scenario_model::TxExpect;
+use setup::*;
+
+/// Whitelists two staking providers and delegates 10 EGLD to the first one, which has the highest score
+fn setup_with_delegation() -> LiquidStakingSetup {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.add_delegation_contract(500, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+
+    setup
+}
+
+#[test]
+fn penalty_from_undelegation_requires_guardian() {
+    let mut setup = setup_with_delegation();
+
+    setup.penalize(
+        USER_ADDRESS_EXPR,
+        0,
+        PenaltySource::FromUndelegate,
+        None,
+        TxExpect::user_error("str:caller is missing the required role"),
+    );
+}
+
+#[test]
+fn penalty_from_undelegation_lifecycle() {
+    let mut setup = setup_with_delegation();
+
+    setup.penalize(
+        ADMIN_ADDRESS_EXPR,
+        0,
+        PenaltySource::FromUndelegate,
+        Some(&egld(4)),
+        TxExpect::ok(),
+    );
+
+    let penalty = setup.penalty(0);
+    assert_eq!(penalty.id, 0);
+    assert!(!penalty.withdrawn);
+    assert_eq!(penalty.attributes.egld_amount, to_managed(&egld(4)));
+    assert_eq!(penalty.attributes.unbond_epoch, 1 + UNBOND_PERIOD);
+
+    let zero = RustBigUint::default();
+    assert_contract_amounts(&setup.contract_data(0), &zero, &egld(6), &egld(4), &zero, &zero);
+
+    // the penalty cannot be delegated nor withdrawn yet
+    setup.delegate_penalty(0, None, user_error(ERROR_WITHDRAW_FIRST));
+    setup.withdraw_penalty(0, user_error(ERROR_UNBOND_PERIOD_NOT_ENDED));
+
+    setup.undelegate_pending_amount(0);
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.withdraw_penalty(0, user_error(ERROR_TOO_MUCH_EGLD_AMOUNT));
+
+    setup.withdraw_from(0);
+    setup.withdraw_penalty(0, TxExpect::ok());
+    assert!(setup.penalty(0).withdrawn);
+    assert_contract_amounts(&setup.contract_data(0), &zero, &egld(6), &zero, &zero, &zero);
+    assert_eq!(setup.total_withdrawable(), zero);
+    setup.withdraw_penalty(0, user_error(ERROR_WITHDRAWN_PENALTY));
+
+    // the penalty is delegated to a different staking provider, without changing the exchange rate
+    setup.delegate_penalty(0, None, TxExpect::ok());
+    assert!(setup.is_penalty_cleared(0));
+    assert_contract_amounts(&setup.contract_data(1), &egld(4), &zero, &zero, &zero, &zero);
+    assert_eq!(setup.cash_reserve(), egld(10));
+    assert_eq!(setup.ls_token_supply(), egld(10));
+
+    setup.delegate_pending_amount(1, None);
+    assert_eq!(setup.delegated_at(1), egld(4));
+}
+
+#[test]
+fn penalty_from_undelegation_would_leave_dust() {
+    let mut setup = setup_with_delegation();
+
+    let amount = egld(10) - RustBigUint::from(1u64);
+    setup.penalize(
+        ADMIN_ADDRESS_EXPR,
+        0,
+        PenaltySource::FromUndelegate,
+        Some(&amount),
+        user_error(ERROR_WOULD_LEAVE_DUST),
+    );
+    setup.penalize(
+        ADMIN_ADDRESS_EXPR,
+        0,
+        PenaltySource::FromUndelegate,
+        Some(&egld(11)),
+        user_error(ERROR_TOO_MUCH_EGLD_AMOUNT),
+    );
+}
+
+#[test]
+fn penalty_from_pending_to_delegate() {
+    let mut setup = setup_with_delegation();
+    setup.delegate(USER_ADDRESS_EXPR, &egld(5));
+
+    // only the guardian can penalize while the undelegation algorithm is active
+    setup.penalize(
+        USER_ADDRESS_EXPR,
+        0,
+        PenaltySource::FromPendingToDelegate,
+        None,
+        TxExpect::user_error("str:caller is missing the required role"),
+    );
+
+    setup.penalize(
+        ADMIN_ADDRESS_EXPR,
+        0,
+        PenaltySource::FromPendingToDelegate,
+        None,
+        TxExpect::ok(),
+    );
+
+    let penalty = setup.penalty(0);
+    assert!(penalty.withdrawn);
+    assert_eq!(penalty.attributes.egld_amount, to_managed(&egld(5)));
+
+    let zero = RustBigUint::default();
+    assert_contract_amounts(&setup.contract_data(0), &zero, &egld(10), &zero, &zero, &zero);
+
+    // partially delegate the penalty
+    setup.delegate_penalty(0, Some(&egld(2)), TxExpect::ok());
+    assert_eq!(setup.penalty(0).attributes.egld_amount, to_managed(&egld(3)));
+    assert_contract_amounts(&setup.contract_data(1), &egld(2), &zero, &zero, &zero, &zero);
+
+    let dust = egld(3) - RustBigUint::from(1u64);
+    setup.delegate_penalty(0, Some(&dust), user_error(ERROR_WOULD_LEAVE_DUST));
+
+    setup.delegate_penalty(0, None, TxExpect::ok());
+    assert!(setup.is_penalty_cleared(0));
+    assert_contract_amounts(&setup.contract_data(1), &egld(5), &zero, &zero, &zero, &zero);
+}
+
+#[test]
+fn penalty_from_pending_to_delegate_in_open_mode() {
+    let mut setup = setup_with_delegation();
+    setup.delegate(USER_ADDRESS_EXPR, &egld(5));
+    setup.set_undelegation_mode(UndelegationMode::Open);
+
+    setup.penalize(
+        USER_ADDRESS_EXPR,
+        0,
+        PenaltySource::FromPendingToDelegate,
+        None,
+        TxExpect::ok(),
+    );
+    assert!(setup.penalty(0).withdrawn);
+}
+
+#[test]
+fn penalty_unknown_id() {
+    let mut setup = setup_with_delegation();
+
+    setup.withdraw_penalty(0, user_error(ERROR_UNEXPECTED_PENALTY_ID));
+    setup.delegate_penalty(0, None, user_error(ERROR_UNEXPECTED_PENALTY_ID));
+}
+
+#[test]
+fn withdraw_from_penalty_requires_open_mode() {
+    let mut setup = setup_with_delegation();
+    setup.delegate(USER_ADDRESS_EXPR, &egld(5));
+    setup.penalize(
+        ADMIN_ADDRESS_EXPR,
+        0,
+        PenaltySource::FromPendingToDelegate,
+        None,
+        TxExpect::ok(),
+    );
+
+    setup.withdraw_from_penalty(
+        USER_ADDRESS_EXPR,
+        0,
+        &egld(2),
+        user_error(ERROR_ONLY_FREE_UNDELEGATION_MODE),
+    );
+}
+
+#[test]
+fn withdraw_from_penalty_success() {
+    let mut setup = setup_with_delegation();
+    setup.delegate(USER_ADDRESS_EXPR, &egld(5));
+    setup.penalize(
+        ADMIN_ADDRESS_EXPR,
+        0,
+        PenaltySource::FromPendingToDelegate,
+        None,
+        TxExpect::ok(),
+    );
+    setup.set_undelegation_mode(UndelegationMode::Open);
+
+    setup.withdraw_from_penalty(USER_ADDRESS_EXPR, 0, &egld(2), TxExpect::ok());
+    assert_eq!(setup.penalty(0).attributes.egld_amount, to_managed(&egld(3)));
+    assert_eq!(setup.cash_reserve(), egld(13));
+    assert_eq!(setup.ls_token_supply(), egld(13));
+    setup.check_ls_token_balance(USER_ADDRESS_EXPR, &egld(13));
+    setup.check_egld_balance(USER_ADDRESS_EXPR, &egld(INITIAL_BALANCE - 13));
+
+    setup.withdraw_from_penalty(USER_ADDRESS_EXPR, 0, &egld(4), user_error(ERROR_TOO_MUCH_EGLD_AMOUNT));
+
+    setup.withdraw_from_penalty(USER_ADDRESS_EXPR, 0, &egld(3), TxExpect::ok());
+    assert!(setup.is_penalty_cleared(0));
+}
@@ -0,0 +1,117 @@
+mod setup;
+
+use liquid_staking::errors::*;
+use multiversx_sc_scenario::scenario_model::TxExpect;
+use setup::*;
+
+/// Delegates 30 EGLD to a single Delegation contract and then adds the given number of Delegation contracts with the
+/// same delegation score, which are under-weight
+fn setup_over_weight(nr_new_contracts: usize) -> LiquidStakingSetup {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(30));
+    setup.delegate_pending_amount(0, None);
+
+    for _ in 0..nr_new_contracts {
+        setup.add_delegation_contract(100, 800, 1_000);
+    }
+    setup
+}
+
+/// Same as `setup_over_weight`, but rebalances are only bound by the target allocations
+fn setup_unbounded_over_weight(nr_new_contracts: usize) -> LiquidStakingSetup {
+    let mut setup = setup_over_weight(nr_new_contracts);
+    setup.set_rebalance_params(0, &egld(1), 0);
+    setup
+}
+
+#[test]
+fn rebalance_moves_stake_to_under_weight_contract() {
+    let mut setup = setup_unbounded_over_weight(1);
+    let exchange_rate = setup.exchange_rate();
+
+    assert_eq!(setup.target_allocation(0), egld(15));
+    assert_eq!(setup.target_allocation(1), egld(15));
+    assert_eq!(setup.max_rebalance_amount(0, 1), egld(15));
+    assert_eq!(setup.max_rebalance_amount(1, 0), RustBigUint::default());
+
+    setup.rebalance(1, 0, &egld(1), user_error(ERROR_INVALID_DELEGATION_CONTRACT));
+    setup.rebalance(0, 0, &egld(1), user_error(ERROR_INVALID_REBALANCE_CONTRACTS));
+    setup.rebalance(0, 1, &egld(16), user_error(ERROR_REBALANCE_ABOVE_TARGET));
+    setup.rebalance(0, 1, &egld(10), TxExpect::ok());
+
+    // the in-flight amount already counts for the destination
+    assert_eq!(setup.max_rebalance_amount(0, 1), egld(5));
+    setup.rebalance(0, 1, &egld(6), user_error(ERROR_REBALANCE_ABOVE_TARGET));
+
+    let zero = RustBigUint::default();
+    assert_contract_amounts(&setup.contract_data(0), &zero, &egld(20), &egld(10), &zero, &zero);
+    assert_eq!(setup.pending_rebalance(), egld(10));
+    assert_eq!(setup.exchange_rate(), exchange_rate);
+    assert!(setup.invariant_violations().is_empty());
+
+    setup.undelegate_pending_amount(0);
+    setup.complete_rebalance(0, user_error(ERROR_UNBOND_PERIOD_NOT_ENDED));
+
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.complete_rebalance(0, user_error(ERROR_TOO_MUCH_EGLD_AMOUNT));
+    setup.withdraw_from(0);
+    setup.complete_rebalance(0, TxExpect::ok());
+    setup.complete_rebalance(0, user_error(ERROR_UNEXPECTED_REBALANCE_ID));
+
+    assert_contract_amounts(&setup.contract_data(1), &egld(10), &zero, &zero, &zero, &zero);
+    assert_eq!(setup.pending_rebalance(), zero);
+
+    setup.delegate_pending_amount(1, None);
+    assert_contract_amounts(&setup.contract_data(1), &zero, &egld(10), &zero, &zero, &zero);
+    assert_eq!(setup.exchange_rate(), exchange_rate);
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn rebalance_drains_blacklisted_contract() {
+    let mut setup = setup_unbounded_over_weight(2);
+
+    // blacklisted contracts have no target allocation
+    setup.blacklist_delegation_contract(0);
+    assert_eq!(setup.target_allocation(0), RustBigUint::default());
+    assert_eq!(setup.max_rebalance_amount(0, 1), egld(15));
+
+    setup.rebalance(0, 1, &egld(15), TxExpect::ok());
+    setup.undelegate_pending_amount(0);
+
+    // the destination is blacklisted as well before the rebalance completes
+    setup.blacklist_delegation_contract(1);
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.withdraw_from(0);
+    setup.complete_rebalance(0, TxExpect::ok());
+
+    let zero = RustBigUint::default();
+    assert_contract_amounts(&setup.contract_data(1), &zero, &zero, &zero, &zero, &zero);
+    assert_contract_amounts(&setup.contract_data(2), &egld(15), &zero, &zero, &zero, &zero);
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn rebalance_requires_deviation_amount_and_cooldown() {
+    let mut setup = setup_over_weight(1);
+    let zero = RustBigUint::default();
+
+    assert_eq!(setup.max_rebalance_amount(0, 1), zero);
+    setup.rebalance(0, 1, &egld(5), user_error(ERROR_REBALANCE_PARAMS_UNSET));
+
+    // the source must exceed its target by more than half of it
+    setup.set_rebalance_params(5_000, &egld(2), 2);
+    assert_eq!(setup.max_rebalance_amount(0, 1), egld(15));
+    setup.rebalance(0, 1, &egld(1), user_error(ERROR_REBALANCE_BELOW_MIN_AMOUNT));
+    setup.rebalance(0, 1, &egld(5), TxExpect::ok());
+    setup.rebalance(0, 1, &egld(2), user_error(ERROR_REBALANCE_COOLDOWN));
+
+    setup.set_block_epoch(3);
+    setup.rebalance(0, 1, &egld(3), TxExpect::ok());
+
+    // an excess of 7 EGLD is below the minimum deviation of 7.5 EGLD
+    setup.set_block_epoch(5);
+    assert_eq!(setup.max_rebalance_amount(0, 1), zero);
+    setup.rebalance(0, 1, &egld(2), user_error(ERROR_REBALANCE_ABOVE_TARGET));
+}
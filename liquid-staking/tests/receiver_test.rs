@@ -0,0 +1,121 @@
+mod setup;
+
+use liquid_staking::errors::*;
+use multiversx_sc_scenario::scenario_model::TxExpect;
+use setup::*;
+
+#[test]
+fn delegate_to_receiver() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate_to(
+        USER_ADDRESS_EXPR,
+        &egld(10),
+        OTHER_USER_ADDRESS_EXPR,
+        None,
+        TxExpect::ok(),
+    );
+
+    setup.check_ls_token_balance(USER_ADDRESS_EXPR, &RustBigUint::default());
+    setup.check_ls_token_balance(OTHER_USER_ADDRESS_EXPR, &egld(10));
+    setup.check_egld_balance(USER_ADDRESS_EXPR, &egld(INITIAL_BALANCE - 10));
+}
+
+#[test]
+fn delegate_and_deposit_at_router() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate_to(
+        USER_ADDRESS_EXPR,
+        &egld(10),
+        ROUTER_ADDRESS_EXPR,
+        Some(USER_ADDRESS_EXPR),
+        TxExpect::ok(),
+    );
+
+    setup.check_ls_token_balance(ROUTER_ADDRESS_EXPR, &egld(10));
+    assert_eq!(setup.router_deposit(USER_ADDRESS_EXPR, LS_TOKEN_ID, 0), egld(10));
+}
+
+#[test]
+fn undelegate_and_deposit_at_router() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+
+    setup.undelegate_to(
+        USER_ADDRESS_EXPR,
+        &egld(4),
+        ROUTER_ADDRESS_EXPR,
+        Some(OTHER_USER_ADDRESS_EXPR),
+        TxExpect::ok(),
+    );
+
+    // the undelegate NFT has been deposited at the router on behalf of the other user
+    let one = RustBigUint::from(1u64);
+    assert_eq!(
+        setup.router_deposit(OTHER_USER_ADDRESS_EXPR, UNDELEGATE_TOKEN_ID, 1),
+        one
+    );
+    assert_eq!(to_rust(&setup.undelegate_attributes(1).egld_amount), egld(4));
+}
+
+#[test]
+fn withdraw_to_receiver_and_router() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(4));
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(2));
+    setup.undelegate_pending_amount(0);
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.withdraw_from(0);
+
+    setup.withdraw_to(USER_ADDRESS_EXPR, 1, OTHER_USER_ADDRESS_EXPR, None, TxExpect::ok());
+    setup.check_egld_balance(OTHER_USER_ADDRESS_EXPR, &egld(INITIAL_BALANCE + 4));
+
+    setup.withdraw_to(
+        USER_ADDRESS_EXPR,
+        2,
+        ROUTER_ADDRESS_EXPR,
+        Some(USER_ADDRESS_EXPR),
+        TxExpect::ok(),
+    );
+    assert_eq!(setup.router_deposit(USER_ADDRESS_EXPR, b"EGLD", 0), egld(2));
+    setup.check_egld_balance(ROUTER_ADDRESS_EXPR, &egld(2));
+    setup.check_egld_balance(USER_ADDRESS_EXPR, &egld(INITIAL_BALANCE - 10));
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn transfer_and_execute_requires_smart_contract_receiver() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate_to(
+        USER_ADDRESS_EXPR,
+        &egld(10),
+        OTHER_USER_ADDRESS_EXPR,
+        Some(USER_ADDRESS_EXPR),
+        user_error(ERROR_RECEIVER_NOT_SMART_CONTRACT),
+    );
+}
+
+#[test]
+fn transfer_and_execute_requires_same_shard_receiver() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    // the shard is given by the last byte of the address
+    setup.delegate_to(
+        USER_ADDRESS_EXPR,
+        &egld(10),
+        "sc:router-in-another-shard0",
+        Some(USER_ADDRESS_EXPR),
+        user_error(ERROR_RECEIVER_CROSS_SHARD),
+    );
+}
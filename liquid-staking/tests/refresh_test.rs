@@ -0,0 +1,53 @@
+mod setup;
+
+use delegation_mock::MockEndpoint;
+use liquid_staking::errors::*;
+use multiversx_sc::types::BigUint;
+use multiversx_sc_scenario::scenario_model::TxExpect;
+use setup::*;
+
+#[test]
+fn refresh_updates_data_from_the_delegation_contract() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.add_delegation_contract(1_000, 800, 1_000);
+    assert_eq!(setup.delegation_contracts_list(), vec![0, 1]);
+
+    setup.set_contract_config(0, 1_200, 5_000);
+    setup.set_total_active_stake(0, 2_000);
+    setup.refresh_delegation_contract_data(0, TxExpect::ok());
+
+    let contract_data = setup.contract_data(0);
+    assert_eq!(contract_data.service_fee, BigUint::from(1_200u64));
+    assert_eq!(contract_data.cap, Some(to_managed(&egld(5_000))));
+    assert_eq!(contract_data.total_value_locked, to_managed(&egld(2_000)));
+    assert_eq!(contract_data.apr, BigUint::from(800u64));
+
+    // a higher TVL lowers the delegation score
+    assert_eq!(setup.delegation_contracts_list(), vec![1, 0]);
+
+    // a zero cap means uncapped
+    setup.set_contract_config(0, 1_200, 0);
+    setup.refresh_delegation_contract_data(0, TxExpect::ok());
+    assert_eq!(setup.contract_data(0).cap, None);
+}
+
+#[test]
+fn refresh_failures_keep_the_current_data() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.set_contract_config(0, 1_200, 0);
+    setup.set_total_active_stake(0, 2_000);
+    setup.set_failure(0, MockEndpoint::ContractConfig, true);
+    setup.refresh_delegation_contract_data(0, TxExpect::ok());
+
+    // only the total value locked has been refreshed
+    let contract_data = setup.contract_data(0);
+    assert_eq!(contract_data.service_fee, BigUint::from(1_000u64));
+    assert_eq!(contract_data.total_value_locked, to_managed(&egld(2_000)));
+    assert!(!contract_data.outdated);
+
+    setup.blacklist_delegation_contract(0);
+    setup.refresh_delegation_contract_data(0, user_error(ERROR_BLACKLISTED_DELEGATION_CONTRACT));
+}
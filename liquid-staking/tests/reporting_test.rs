@@ -0,0 +1,140 @@
+mod setup;
+
+use liquid_staking::{errors::*, governance::ProxyTrait as _};
+use multiversx_sc::{codec::multi_types::OptionalValue, types::BigUint};
+use multiversx_sc_scenario::{
+    api::StaticApi,
+    scenario_model::{ScCallStep, TxExpect},
+};
+use setup::*;
+
+const REPORTERS: [&str; 3] = [USER_ADDRESS_EXPR, OTHER_USER_ADDRESS_EXPR, DATA_MANAGER_ADDRESS_EXPR];
+
+fn setup_reporters(quorum: usize) -> LiquidStakingSetup {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    for reporter in REPORTERS {
+        setup.add_data_reporter(reporter);
+    }
+    setup.set_data_reporters_quorum(quorum, TxExpect::ok());
+    setup
+}
+
+#[test]
+fn quorum_applies_the_median_report() {
+    let mut setup = setup_reporters(3);
+
+    setup.submit_delegation_contract_data(REPORTERS[0], 0, 100, 800, 1_000, Some(200), TxExpect::ok());
+    setup.submit_delegation_contract_data(REPORTERS[1], 0, 130, 900, 1_100, None, TxExpect::ok());
+
+    // the quorum has not been reached yet
+    assert_eq!(setup.data_reports(0).len(), 2);
+    assert_eq!(setup.contract_data(0).apr, BigUint::from(800u64));
+
+    setup.submit_delegation_contract_data(REPORTERS[2], 0, 120, 700, 900, Some(150), TxExpect::ok());
+
+    let contract_data = setup.contract_data(0);
+    assert_eq!(contract_data.total_value_locked, to_managed(&egld(120)));
+    assert_eq!(contract_data.apr, BigUint::from(800u64));
+    assert_eq!(contract_data.service_fee, BigUint::from(1_000u64));
+    assert_eq!(contract_data.cap, Some(to_managed(&egld(200))));
+    assert!(setup.data_reports(0).is_empty());
+}
+
+#[test]
+fn only_data_reporters_update_data_once_quorum_is_set() {
+    let mut setup = setup_reporters(2);
+
+    setup.submit_delegation_contract_data(
+        ADMIN_ADDRESS_EXPR,
+        0,
+        100,
+        800,
+        1_000,
+        None,
+        user_error(ERROR_ONLY_DATA_REPORTER),
+    );
+
+    let delegation_contract = setup.delegation_contract(0);
+    let contract_call = setup.ls_contract.change_delegation_contract_params(
+        delegation_contract,
+        to_managed(&egld(100)),
+        1u64,
+        BigUint::from(800u64),
+        BigUint::from(1_000u64),
+        OptionalValue::<BigUint<StaticApi>>::None,
+    );
+    setup.world.sc_call(
+        ScCallStep::new()
+            .from(DATA_MANAGER_ADDRESS_EXPR)
+            .call(contract_call)
+            .expect(user_error(ERROR_DATA_REPORTERS_QUORUM_SET)),
+    );
+
+    setup.set_data_reporters_quorum(4, user_error(ERROR_INVALID_DATA_REPORTERS_QUORUM));
+    setup.remove_data_reporter(REPORTERS[2], TxExpect::ok());
+    setup.remove_data_reporter(REPORTERS[1], user_error(ERROR_INVALID_DATA_REPORTERS_QUORUM));
+
+    // the data manager takes over again without a quorum
+    setup.set_data_reporters_quorum(0, TxExpect::ok());
+    setup.change_delegation_contract_params(0, 110, 850, 1_000);
+    assert_eq!(setup.contract_data(0).apr, BigUint::from(850u64));
+}
+
+#[test]
+fn outlier_reports_are_left_out() {
+    let mut setup = setup_reporters(2);
+    setup.set_max_data_report_deviation(2_000);
+
+    setup.submit_delegation_contract_data(REPORTERS[0], 0, 115, 800, 1_000, None, TxExpect::ok());
+    setup.submit_delegation_contract_data(REPORTERS[1], 0, 200, 800, 1_000, None, TxExpect::ok());
+
+    // the reports deviate from each other, so the quorum is not reached by consistent reports
+    assert_eq!(setup.data_reports(0).len(), 2);
+    assert_eq!(setup.contract_data(0).total_value_locked, to_managed(&egld(100)));
+
+    setup.submit_delegation_contract_data(REPORTERS[2], 0, 120, 900, 1_000, None, TxExpect::ok());
+
+    // the median is 120, which leaves the report of 200 out
+    let contract_data = setup.contract_data(0);
+    assert_eq!(contract_data.total_value_locked, to_managed(&egld(115)));
+    assert_eq!(contract_data.apr, BigUint::from(800u64));
+    assert!(setup.data_reports(0).is_empty());
+}
+
+#[test]
+fn data_moving_beyond_the_max_deviation_is_applied() {
+    let mut setup = setup_reporters(2);
+    setup.set_max_data_report_deviation(2_000);
+
+    // the deviation is measured between reports, not from the current data
+    setup.submit_delegation_contract_data(REPORTERS[0], 0, 300, 1_600, 2_000, None, TxExpect::ok());
+    setup.submit_delegation_contract_data(REPORTERS[1], 0, 310, 1_650, 2_000, None, TxExpect::ok());
+
+    let contract_data = setup.contract_data(0);
+    assert_eq!(contract_data.total_value_locked, to_managed(&egld(300)));
+    assert_eq!(contract_data.apr, BigUint::from(1_600u64));
+    assert_eq!(contract_data.service_fee, BigUint::from(2_000u64));
+}
+
+#[test]
+fn reports_from_previous_epochs_are_discarded() {
+    let mut setup = setup_reporters(2);
+
+    setup.submit_delegation_contract_data(REPORTERS[0], 0, 150, 900, 1_000, None, TxExpect::ok());
+
+    setup.set_block_epoch(2);
+    setup.submit_delegation_contract_data(REPORTERS[1], 0, 160, 950, 1_000, None, TxExpect::ok());
+    assert_eq!(setup.data_reports(0).len(), 1);
+    assert_eq!(setup.contract_data(0).apr, BigUint::from(800u64));
+
+    // reports from removed data reporters are ignored as well
+    setup.remove_data_reporter(REPORTERS[1], TxExpect::ok());
+    setup.submit_delegation_contract_data(REPORTERS[0], 0, 150, 900, 1_000, None, TxExpect::ok());
+    assert_eq!(setup.contract_data(0).apr, BigUint::from(800u64));
+
+    setup.submit_delegation_contract_data(REPORTERS[2], 0, 170, 1_000, 1_000, None, TxExpect::ok());
+    let contract_data = setup.contract_data(0);
+    assert_eq!(contract_data.total_value_locked, to_managed(&egld(150)));
+    assert_eq!(contract_data.apr, BigUint::from(900u64));
+}
@@ -0,0 +1,342 @@
+mod setup;
+
+use delegation_mock::MockEndpoint;
+use liquid_staking::{
+    constants::{BPS, MAX_REWARDS_VESTING_PERIOD, WAD},
+    errors::*,
+};
+use multiversx_sc_scenario::scenario_model::TxExpect;
+use setup::*;
+
+/// Delegates 100 EGLD to a staking provider with a service fee of 10%
+fn setup_with_delegation() -> LiquidStakingSetup {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(100));
+    setup.delegate_pending_amount(0, None);
+
+    setup
+}
+
+/// The rewards that go to the protocol reserve given the total fee and the staking provider service fee
+fn protocol_reserve_for(claimed_rewards: &RustBigUint, service_fee: u64) -> RustBigUint {
+    let bps = RustBigUint::from(BPS);
+    let protocol_fee = RustBigUint::from(TOTAL_FEE - service_fee) * &bps / (&bps - RustBigUint::from(service_fee));
+    protocol_fee * claimed_rewards / bps
+}
+
+#[test]
+fn claim_rewards_from_success() {
+    let mut setup = setup_with_delegation();
+    setup.add_rewards(0, &egld(10));
+
+    setup.claim_rewards_from(0);
+
+    let reserves = protocol_reserve_for(&egld(10), 1_000);
+    assert_eq!(setup.protocol_reserve(), reserves);
+    assert_eq!(setup.rewards_reserve(), egld(10) - &reserves);
+    setup.check_egld_balance(LIQUID_STAKING_ADDRESS_EXPR, &egld(10));
+
+    // rewards can be claimed only once per epoch
+    setup.claim_rewards_from_expect(0, user_error(ERROR_REWARDS_ALREADY_CLAIMED));
+
+    setup.set_block_epoch(2);
+    setup.add_rewards(0, &egld(10));
+    setup.claim_rewards_from(0);
+    assert_eq!(setup.protocol_reserve(), reserves * 2u64);
+}
+
+#[test]
+fn claim_rewards_from_service_fee_above_total_fee() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 2_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(100));
+    setup.delegate_pending_amount(0, None);
+    setup.add_rewards(0, &egld(10));
+    setup.claim_rewards_from(0);
+
+    assert_eq!(setup.protocol_reserve(), RustBigUint::default());
+    assert_eq!(setup.rewards_reserve(), egld(10));
+}
+
+#[test]
+fn claim_rewards_from_failure() {
+    let mut setup = setup_with_delegation();
+    setup.add_rewards(0, &egld(10));
+    setup.set_failure(0, MockEndpoint::ClaimRewards, true);
+
+    setup.claim_rewards_from(0);
+
+    let zero = RustBigUint::default();
+    assert_eq!(setup.protocol_reserve(), zero);
+    assert_eq!(setup.rewards_reserve(), zero);
+    assert!(!setup.contract_data(0).outdated);
+
+    // the claim epoch is not updated on failures, so it can be retried
+    setup.set_failure(0, MockEndpoint::ClaimRewards, false);
+    setup.claim_rewards_from(0);
+    assert_eq!(
+        setup.rewards_reserve(),
+        egld(10) - protocol_reserve_for(&egld(10), 1_000)
+    );
+}
+
+#[test]
+fn delegate_rewards_success() {
+    let mut setup = setup_with_delegation();
+    setup.add_rewards(0, &egld(10));
+    setup.claim_rewards_from(0);
+
+    let rewards = setup.rewards_reserve();
+    setup.delegate_rewards(None);
+
+    let zero = RustBigUint::default();
+    assert_eq!(setup.rewards_reserve(), zero);
+    assert_eq!(setup.cash_reserve(), egld(100) + &rewards);
+    assert_eq!(setup.ls_token_supply(), egld(100));
+    assert_eq!(
+        setup.exchange_rate(),
+        (egld(100) + &rewards) * RustBigUint::from(WAD) / egld(100)
+    );
+
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &(egld(100) + &rewards), &zero, &zero, &zero);
+    assert_eq!(setup.delegated_at(0), egld(100) + rewards);
+}
+
+#[test]
+fn delegate_rewards_partial() {
+    let mut setup = setup_with_delegation();
+    setup.add_rewards(0, &egld(10));
+    setup.claim_rewards_from(0);
+
+    let rewards = setup.rewards_reserve();
+    setup.delegate_rewards(Some(&egld(2)));
+    assert_eq!(setup.rewards_reserve(), &rewards - egld(2));
+    assert_eq!(setup.cash_reserve(), egld(102));
+
+    setup.delegate_rewards_expect(Some(&egld(10)), user_error(ERROR_TOO_MUCH_EGLD_AMOUNT));
+}
+
+#[test]
+fn delegate_rewards_requires_minimum_amount() {
+    let mut setup = setup_with_delegation();
+
+    setup.delegate_rewards_expect(None, user_error(ERROR_INSUFFICIENT_EGLD_AMOUNT));
+}
+
+#[test]
+fn delegate_rewards_failure() {
+    let mut setup = setup_with_delegation();
+    setup.add_rewards(0, &egld(10));
+    setup.claim_rewards_from(0);
+
+    let rewards = setup.rewards_reserve();
+    setup.set_failure(0, MockEndpoint::Delegate, true);
+    setup.delegate_rewards(None);
+
+    assert_eq!(setup.rewards_reserve(), rewards);
+    assert_eq!(setup.cash_reserve(), egld(100));
+    assert!(setup.contract_data(0).outdated);
+    assert_eq!(setup.delegated_at(0), egld(100));
+}
+
+/// Delegates 100 EGLD to a first staking provider and 50 EGLD to a second one, both with a service fee of 10%, and
+/// funds 10 EGLD and 5 EGLD of rewards respectively
+fn setup_with_two_delegations() -> LiquidStakingSetup {
+    let mut setup = setup_with_delegation();
+    setup.add_delegation_contract(50, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(50));
+    setup.delegate_pending_amount(1, None);
+
+    setup.add_rewards(0, &egld(10));
+    setup.add_rewards(1, &egld(5));
+    setup
+}
+
+#[test]
+fn claim_all_and_delegate_rewards_success() {
+    let mut setup = setup_with_two_delegations();
+
+    // claims from both staking providers, while there is nothing to delegate yet
+    setup.claim_all_and_delegate_rewards(TxExpect::ok());
+    let reserves = protocol_reserve_for(&egld(15), 1_000);
+    let rewards = egld(15) - &reserves;
+    assert_eq!(setup.protocol_reserve(), reserves);
+    assert_eq!(setup.rewards_reserve(), rewards);
+    assert_eq!(setup.cash_reserve(), egld(150));
+
+    // the claimed rewards are delegated by the next call, which does not claim again within the same epoch
+    setup.claim_all_and_delegate_rewards(TxExpect::ok());
+    assert_eq!(setup.rewards_reserve(), RustBigUint::default());
+    assert_eq!(setup.cash_reserve(), egld(150) + &rewards);
+    assert!(setup.exchange_rate() > RustBigUint::from(WAD));
+    assert_eq!(setup.delegated_at(0) + setup.delegated_at(1), egld(150) + &rewards);
+
+    setup.claim_all_and_delegate_rewards(user_error(ERROR_NOTHING_TO_CLAIM_OR_DELEGATE));
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn claim_all_and_delegate_rewards_isolates_failures() {
+    let mut setup = setup_with_two_delegations();
+    setup.set_failure(1, MockEndpoint::ClaimRewards, true);
+
+    setup.claim_all_and_delegate_rewards(TxExpect::ok());
+    let first_rewards = egld(10) - protocol_reserve_for(&egld(10), 1_000);
+    assert_eq!(setup.rewards_reserve(), first_rewards);
+
+    // the failed claim is retried, while the failed delegation restores the rewards reserve
+    setup.set_failure(1, MockEndpoint::ClaimRewards, false);
+    setup.set_failure(0, MockEndpoint::Delegate, true);
+    setup.set_failure(1, MockEndpoint::Delegate, true);
+    setup.claim_all_and_delegate_rewards(TxExpect::ok());
+
+    let second_rewards = egld(5) - protocol_reserve_for(&egld(5), 1_000);
+    assert_eq!(setup.rewards_reserve(), &first_rewards + &second_rewards);
+    assert_eq!(setup.cash_reserve(), egld(150));
+    let num_outdated = (0..2).filter(|index| setup.contract_data(*index).outdated).count();
+    assert_eq!(num_outdated, 1);
+    assert!(setup.invariant_violations().is_empty());
+}
+
+const REWARDS_VESTING_PERIOD: u64 = 100;
+
+/// Claims and delegates 10 EGLD of rewards at round 10 while vesting rewards over 100 rounds, returning the delegated
+/// rewards
+fn setup_with_streamed_rewards() -> (LiquidStakingSetup, RustBigUint) {
+    let mut setup = setup_with_delegation();
+    setup.set_rewards_vesting_period(REWARDS_VESTING_PERIOD, TxExpect::ok());
+    setup.set_block_round(10);
+
+    setup.add_rewards(0, &egld(10));
+    setup.claim_rewards_from(0);
+    let rewards = setup.rewards_reserve();
+    setup.delegate_rewards(None);
+
+    (setup, rewards)
+}
+
+fn exchange_rate_for(cash: &RustBigUint, supply: &RustBigUint) -> RustBigUint {
+    cash * RustBigUint::from(WAD) / supply
+}
+
+#[test]
+fn delegated_rewards_vest_linearly() {
+    let (mut setup, rewards) = setup_with_streamed_rewards();
+
+    // the rewards are part of the cash reserve but not of the exchange rate yet
+    assert_eq!(setup.cash_reserve(), egld(100) + &rewards);
+    assert_eq!(setup.unvested_rewards(), rewards);
+    assert_eq!(setup.exchange_rate(), RustBigUint::from(WAD));
+
+    setup.set_block_round(60);
+    let half = &rewards / 2u32;
+    assert_eq!(setup.unvested_rewards(), half);
+    assert_eq!(
+        setup.exchange_rate(),
+        exchange_rate_for(&(egld(100) + &rewards - &half), &egld(100))
+    );
+
+    setup.set_block_round(10 + REWARDS_VESTING_PERIOD);
+    assert_eq!(setup.unvested_rewards(), RustBigUint::default());
+    assert_eq!(
+        setup.exchange_rate(),
+        exchange_rate_for(&(egld(100) + &rewards), &egld(100))
+    );
+    assert!(setup.invariant_violations().is_empty());
+}
+
+/// The number of rounds left in the rewards stream after topping up the unvested rewards with new ones
+fn topped_up_remaining_rounds(unvested_rewards: &RustBigUint, remaining_rounds: u64, new_rewards: &RustBigUint) -> u64 {
+    let weighted_rounds = unvested_rewards * remaining_rounds + new_rewards * REWARDS_VESTING_PERIOD;
+    let remaining_rounds = weighted_rounds / (unvested_rewards + new_rewards);
+    remaining_rounds.to_u64_digits().first().copied().unwrap_or_default()
+}
+
+#[test]
+fn delegated_rewards_top_up_the_stream() {
+    let (mut setup, rewards) = setup_with_streamed_rewards();
+
+    // new rewards are vested together with the unvested ones, over the average of both periods
+    setup.set_block_round(60);
+    setup.add_rewards(0, &egld(10));
+    setup.set_block_epoch(2);
+    setup.claim_rewards_from(0);
+    let new_rewards = setup.rewards_reserve();
+    setup.delegate_rewards(None);
+
+    let half = &rewards / 2u32;
+    let unvested_rewards = &half + &new_rewards;
+    let remaining_rounds = topped_up_remaining_rounds(&half, 50, &new_rewards);
+    assert!(remaining_rounds > 50 && remaining_rounds < REWARDS_VESTING_PERIOD);
+    assert_eq!(setup.unvested_rewards(), unvested_rewards);
+    assert_eq!(setup.rewards_stream().end_round, 60 + remaining_rounds);
+
+    setup.set_block_round(60 + remaining_rounds / 2);
+    assert_eq!(
+        setup.unvested_rewards(),
+        &unvested_rewards * (remaining_rounds - remaining_rounds / 2) / remaining_rounds
+    );
+
+    setup.set_block_round(60 + remaining_rounds);
+    assert_eq!(setup.unvested_rewards(), RustBigUint::default());
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn repeated_top_ups_do_not_restart_the_vesting() {
+    let mut setup = setup_with_delegation();
+    setup.set_rewards_vesting_period(REWARDS_VESTING_PERIOD, TxExpect::ok());
+    setup.add_rewards(0, &egld(10));
+    setup.claim_rewards_from(0);
+
+    setup.set_block_round(10);
+    setup.delegate_rewards(Some(&egld(1)));
+    let mut end_round = 10 + REWARDS_VESTING_PERIOD;
+    assert_eq!(setup.rewards_stream().end_round, end_round);
+
+    // delegating rewards every 10 rounds within one vesting period only delays the stream by the weight of each top-up
+    for round in [20, 30, 40, 50, 60] {
+        setup.set_block_round(round);
+        let unvested_rewards = setup.unvested_rewards();
+        setup.delegate_rewards(Some(&egld(1)));
+
+        end_round = round + topped_up_remaining_rounds(&unvested_rewards, end_round - round, &egld(1));
+        assert_eq!(setup.rewards_stream().end_round, end_round);
+        assert_eq!(setup.unvested_rewards(), unvested_rewards + egld(1));
+    }
+
+    assert!(end_round < 60 + REWARDS_VESTING_PERIOD);
+
+    setup.set_block_round(end_round);
+    assert_eq!(setup.unvested_rewards(), RustBigUint::default());
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn delegating_right_before_rewards_captures_no_rewards() {
+    let mut setup = setup_with_delegation();
+    setup.set_rewards_vesting_period(REWARDS_VESTING_PERIOD, TxExpect::ok());
+    setup.add_rewards(0, &egld(10));
+    setup.claim_rewards_from(0);
+
+    let shares = setup.preview_delegate(&egld(50));
+    setup.delegate(OTHER_USER_ADDRESS_EXPR, &egld(50));
+    setup.delegate_rewards(None);
+
+    // undelegating right after the rewards are delegated returns the very same amount
+    assert_eq!(setup.preview_undelegate(&shares), egld(50));
+}
+
+#[test]
+fn set_rewards_vesting_period_too_long() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.set_rewards_vesting_period(
+        MAX_REWARDS_VESTING_PERIOD + 1,
+        user_error(ERROR_REWARDS_VESTING_PERIOD_TOO_LONG),
+    );
+    setup.set_rewards_vesting_period(MAX_REWARDS_VESTING_PERIOD, TxExpect::ok());
+}
@@ -0,0 +1,126 @@
+mod setup;
+
+use liquid_staking::{
+    admin::{ProxyTrait as _, Role},
+    constants::BPS,
+    governance::ProxyTrait as _,
+};
+use multiversx_sc::{
+    codec::multi_types::OptionalValue,
+    types::{BigUint, ManagedAddress, MultiValueEncoded},
+};
+use multiversx_sc_scenario::{
+    api::StaticApi,
+    scenario_model::{AddressValue, ScCallStep, TxExpect},
+};
+use setup::*;
+
+const MISSING_ROLE: &[u8] = b"caller is missing the required role";
+
+fn set_total_fee(setup: &mut LiquidStakingSetup, from: &str, expect: TxExpect) {
+    let contract_call = setup.ls_contract.set_total_fee(BigUint::<StaticApi>::from(BPS / 10));
+    setup
+        .world
+        .sc_call(ScCallStep::new().from(from).call(contract_call).expect(expect));
+}
+
+fn pause_all(setup: &mut LiquidStakingSetup, from: &str, expect: TxExpect) {
+    let contract_call = setup.ls_contract.pause(MultiValueEncoded::new());
+    setup
+        .world
+        .sc_call(ScCallStep::new().from(from).call(contract_call).expect(expect));
+}
+
+#[test]
+fn grant_and_revoke_role() {
+    let mut setup = LiquidStakingSetup::new();
+    assert!(!setup.has_role(Role::FeeManager, USER_ADDRESS_EXPR));
+    set_total_fee(&mut setup, USER_ADDRESS_EXPR, user_error(MISSING_ROLE));
+
+    setup.grant_role(Role::FeeManager, USER_ADDRESS_EXPR, TxExpect::ok());
+    assert!(setup.has_role(Role::FeeManager, USER_ADDRESS_EXPR));
+    assert_eq!(
+        setup.role_members(Role::FeeManager),
+        vec![
+            AddressValue::from(ADMIN_ADDRESS_EXPR).to_address(),
+            AddressValue::from(USER_ADDRESS_EXPR).to_address(),
+        ]
+    );
+    set_total_fee(&mut setup, USER_ADDRESS_EXPR, TxExpect::ok());
+
+    setup.revoke_role(Role::FeeManager, USER_ADDRESS_EXPR, TxExpect::ok());
+    assert!(!setup.has_role(Role::FeeManager, USER_ADDRESS_EXPR));
+    set_total_fee(&mut setup, USER_ADDRESS_EXPR, user_error(MISSING_ROLE));
+}
+
+#[test]
+fn roles_only_grant_their_own_endpoints() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.grant_role(Role::Pauser, USER_ADDRESS_EXPR, TxExpect::ok());
+
+    set_total_fee(&mut setup, USER_ADDRESS_EXPR, user_error(MISSING_ROLE));
+    pause_all(&mut setup, USER_ADDRESS_EXPR, TxExpect::ok());
+
+    // the admin can only perform what its roles allow
+    setup.revoke_role(Role::FeeManager, ADMIN_ADDRESS_EXPR, TxExpect::ok());
+    set_total_fee(&mut setup, ADMIN_ADDRESS_EXPR, user_error(MISSING_ROLE));
+}
+
+#[test]
+fn only_admin_manages_roles() {
+    let mut setup = LiquidStakingSetup::new();
+    let user = AddressValue::from(USER_ADDRESS_EXPR).to_address();
+
+    let contract_call = setup.ls_contract.grant_role(Role::Guardian, user.clone());
+    setup.world.sc_call(
+        ScCallStep::new()
+            .from(USER_ADDRESS_EXPR)
+            .call(contract_call)
+            .expect(user_error(b"caller must be admin")),
+    );
+
+    let contract_call = setup.ls_contract.revoke_role(Role::Guardian, user);
+    setup.world.sc_call(
+        ScCallStep::new()
+            .from(USER_ADDRESS_EXPR)
+            .call(contract_call)
+            .expect(user_error(b"caller must be admin")),
+    );
+
+    setup.grant_role(
+        Role::Guardian,
+        ADMIN_ADDRESS_EXPR,
+        user_error(b"account already has the role"),
+    );
+    setup.revoke_role(
+        Role::Guardian,
+        USER_ADDRESS_EXPR,
+        user_error(b"account does not have the role"),
+    );
+}
+
+#[test]
+fn reserve_treasurer_withdraws_to_the_admin_by_default() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(100));
+    setup.delegate_pending_amount(0, None);
+    setup.add_rewards(0, &egld(10));
+    setup.claim_rewards_from(0);
+    let protocol_reserve = setup.protocol_reserve();
+
+    setup.grant_role(Role::ReserveTreasurer, OTHER_USER_ADDRESS_EXPR, TxExpect::ok());
+    let contract_call = setup.ls_contract.withdraw_reserve(
+        to_managed(&protocol_reserve),
+        OptionalValue::<ManagedAddress<StaticApi>>::None,
+    );
+    setup
+        .world
+        .sc_call(ScCallStep::new().from(OTHER_USER_ADDRESS_EXPR).call(contract_call));
+
+    assert_eq!(setup.protocol_reserve(), RustBigUint::default());
+    setup.check_egld_balance(
+        ADMIN_ADDRESS_EXPR,
+        &(egld(INITIAL_BALANCE) - egld(10) + &protocol_reserve),
+    );
+}
@@ -0,0 +1,209 @@
+mod setup;
+
+use liquid_staking::{errors::*, model::*, score::ScoreModule, storage::StorageModule};
+use multiversx_sc::{
+    codec::TopEncode,
+    types::{BigUint, ManagedBuffer, ManagedVec},
+};
+use multiversx_sc_scenario::{api::StaticApi, scenario_model::*, DebugApi};
+use setup::*;
+
+fn delegation_score(setup: &mut LiquidStakingSetup, index: usize) -> RustBigUint {
+    to_rust(&setup.contract_data(index).delegation_score)
+}
+
+#[test]
+fn tvl_per_node_and_service_fee_methods() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(1_000, 800, 1_000);
+    setup.add_delegation_contract(1_000, 800, 500);
+    setup.add_delegation_contract(3_000, 800, 2_000);
+    setup.change_delegation_contract_nr_nodes(1, 4);
+    setup.change_delegation_contract_nr_nodes(2, 6);
+
+    setup.set_delegation_score_model_params(
+        DelegationScoreMethod::TvlPerNode,
+        0,
+        0,
+        user_error(ERROR_MISSING_TVL_PER_NODE_DOMAIN),
+    );
+    setup.set_tvl_per_node_domain(1_000, 0, user_error(ERROR_INVALID_DOMAIN));
+    setup.set_tvl_per_node_domain(0, 1_000, TxExpect::ok());
+
+    // the TVL per node does not depend on the TVL domain
+    setup.set_delegation_score_model_params(DelegationScoreMethod::TvlPerNode, 0, 0, TxExpect::ok());
+    assert_eq!(delegation_score(&mut setup, 0), RustBigUint::from(0u64));
+    assert_eq!(delegation_score(&mut setup, 1), RustBigUint::from(7_500u64));
+    assert_eq!(delegation_score(&mut setup, 2), RustBigUint::from(5_000u64));
+    assert_eq!(setup.delegation_contracts_list(), vec![1, 2, 0]);
+
+    // lower service fees yield higher scores
+    setup.set_delegation_score_model_params(DelegationScoreMethod::ServiceFee, 0, 0, TxExpect::ok());
+    assert_eq!(delegation_score(&mut setup, 0), RustBigUint::from(9_000u64));
+    assert_eq!(delegation_score(&mut setup, 1), RustBigUint::from(9_500u64));
+    assert_eq!(delegation_score(&mut setup, 2), RustBigUint::from(8_000u64));
+    assert_eq!(setup.delegation_contracts_list(), vec![1, 0, 2]);
+}
+
+#[test]
+fn weighted_method_combines_terms() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(1_000, 800, 1_000);
+    setup.add_delegation_contract(5_000, 800, 1_000);
+    setup.change_delegation_contract_nr_nodes(1, 10);
+
+    setup.set_delegation_score_model_params(
+        DelegationScoreMethod::Weighted,
+        0,
+        0,
+        user_error(ERROR_MISSING_DELEGATION_SCORE_TERMS),
+    );
+
+    let tvl_term = |weight| {
+        (
+            DelegationScoreFactor::Tvl,
+            weight,
+            RustBigUint::default(),
+            egld(10_000),
+            true,
+        )
+    };
+    let nodes_term = |weight| {
+        let max = RustBigUint::from(10u64);
+        (
+            DelegationScoreFactor::NrNodes,
+            weight,
+            RustBigUint::default(),
+            max,
+            false,
+        )
+    };
+
+    setup.set_delegation_score_terms(&[], user_error(ERROR_INVALID_DELEGATION_SCORE_TERMS));
+    setup.set_delegation_score_terms(
+        &[tvl_term(5_000), nodes_term(4_000)],
+        user_error(ERROR_INVALID_DELEGATION_SCORE_WEIGHTS),
+    );
+    setup.set_delegation_score_terms(
+        &[
+            tvl_term(5_000),
+            (DelegationScoreFactor::Apr, 5_000, egld(1), egld(1), false),
+        ],
+        user_error(ERROR_INVALID_DOMAIN),
+    );
+
+    // the terms do not apply until the method is set
+    setup.set_delegation_score_terms(&[tvl_term(5_000), nodes_term(5_000)], TxExpect::ok());
+    assert_eq!(delegation_score(&mut setup, 0), RustBigUint::from(9_990u64));
+    assert_eq!(setup.delegation_contracts_list(), vec![0, 1]);
+
+    setup.set_delegation_score_model_params(DelegationScoreMethod::Weighted, 0, 0, TxExpect::ok());
+    assert_eq!(delegation_score(&mut setup, 0), RustBigUint::from(5_000u64));
+    assert_eq!(delegation_score(&mut setup, 1), RustBigUint::from(7_500u64));
+    assert_eq!(setup.delegation_contracts_list(), vec![1, 0]);
+}
+
+fn piecewise_linear(breakpoints: &[(u64, u64)]) -> ScoreCurve<StaticApi> {
+    let mut managed_breakpoints = ManagedVec::new();
+    for (x, y) in breakpoints {
+        managed_breakpoints.push(ScoreCurvePoint { x: *x, y: *y });
+    }
+    ScoreCurve::PiecewiseLinear {
+        breakpoints: managed_breakpoints,
+    }
+}
+
+#[test]
+fn score_curves_shape_delegation_scores() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100_000, 800, 1_000);
+    setup.add_delegation_contract(550_000, 800, 1_000);
+
+    let curves = [
+        (piecewise_linear(&[(1_000, 5_000)]), 5_000u64, 2_500u64),
+        (ScoreCurve::Logarithmic { curvature: 1_000 }, 4_500, 693),
+        (
+            ScoreCurve::Sigmoid {
+                midpoint: 5_000,
+                curvature: 1_000,
+            },
+            9_889,
+            2_250,
+        ),
+        (ScoreCurve::Linear, 9_000, 4_500),
+    ];
+
+    for (curve, first_score, second_score) in curves {
+        setup.set_delegation_score_curve(curve, TxExpect::ok());
+        assert_eq!(delegation_score(&mut setup, 0), RustBigUint::from(first_score));
+        assert_eq!(delegation_score(&mut setup, 1), RustBigUint::from(second_score));
+    }
+}
+
+#[test]
+fn score_curve_is_kept_across_model_updates() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100_000, 800, 1_000);
+
+    setup.set_delegation_score_curve(ScoreCurve::Logarithmic { curvature: 1_000 }, TxExpect::ok());
+    assert_eq!(delegation_score(&mut setup, 0), RustBigUint::from(4_500u64));
+
+    // the linear curve would score half of the new domain at 5_000
+    setup.set_delegation_score_model_params(DelegationScoreMethod::Tvl, 0, 200_000, TxExpect::ok());
+    assert_eq!(delegation_score(&mut setup, 0), RustBigUint::from(834u64));
+}
+
+#[test]
+fn invalid_score_curves_are_rejected() {
+    let mut setup = LiquidStakingSetup::new();
+
+    let invalid_curves = [
+        piecewise_linear(&[]),
+        piecewise_linear(&[(5_000, 5_000), (4_000, 6_000)]),
+        piecewise_linear(&[(4_000, 6_000), (5_000, 5_000)]),
+        piecewise_linear(&[(10_000, 10_000)]),
+        ScoreCurve::Logarithmic { curvature: 0 },
+        ScoreCurve::Sigmoid {
+            midpoint: 0,
+            curvature: 1_000,
+        },
+        ScoreCurve::Sigmoid {
+            midpoint: 10_000,
+            curvature: 1_000,
+        },
+    ];
+
+    for curve in invalid_curves {
+        setup.set_delegation_score_curve(curve, user_error(ERROR_INVALID_SCORE_CURVE));
+    }
+}
+
+#[test]
+fn legacy_score_model_is_migrated() {
+    let mut setup = LiquidStakingSetup::new();
+
+    setup
+        .world
+        .whitebox_call(&setup.ls_whitebox, ScCallStep::new().from(ADMIN_ADDRESS_EXPR), |sc| {
+            let legacy_model = LegacyDelegationScoreModel::<DebugApi> {
+                method: DelegationScoreMethod::Apr,
+                min_tvl: BigUint::zero(),
+                max_tvl: BigUint::zero(),
+                min_apr: BigUint::from(100u64),
+                max_apr: BigUint::from(1_000u64),
+                omega: BigUint::zero(),
+            };
+            let mut raw_model = ManagedBuffer::new();
+            legacy_model.top_encode(&mut raw_model).unwrap();
+            sc.raw_delegation_score_model().set(&raw_model);
+
+            // migrating twice leaves the model untouched
+            sc.try_migrate_delegation_score_model();
+            sc.try_migrate_delegation_score_model();
+
+            let model = sc.delegation_score_model().get();
+            assert_eq!(model.method, DelegationScoreMethod::Apr);
+            assert_eq!(model.max_apr, BigUint::from(1_000u64));
+            assert_eq!(model.curve, ScoreCurve::Linear);
+        });
+}
@@ -0,0 +1,1709 @@
+#![allow(dead_code)]
+
+use delegation_mock::{MockEndpoint, ProxyTrait as _};
+use liquid_staking::{
+    admin::{ProxyTrait as _, Role},
+    buffer::ProxyTrait as _,
+    common::{CommonModule, ProxyTrait as _},
+    constants::WAD,
+    delegate::ProxyTrait as _,
+    delegation::DelegationModule,
+    governance::ProxyTrait as _,
+    invariants::InvariantsModule,
+    migration::ProxyTrait as _,
+    model::*,
+    penalty::ProxyTrait as _,
+    rebalance::ProxyTrait as _,
+    refresh::ProxyTrait as _,
+    reporting::ProxyTrait as _,
+    rewards::ProxyTrait as _,
+    score::ScoreModule,
+    storage::{ProxyTrait as _, StorageModule},
+    timelock::ProxyTrait as _,
+    undelegate::ProxyTrait as _,
+    withdraw::ProxyTrait as _,
+    LiquidStaking,
+};
+use multiversx_sc::{
+    codec::{
+        multi_types::{MultiValue2, MultiValueVec, OptionalValue},
+        top_encode_to_vec_u8_or_panic, TopDecode,
+    },
+    contract_base::ContractBase,
+    storage::mappers::{SingleValue, StorageTokenWrapper},
+    types::{
+        Address, BigUint, EgldOrEsdtTokenIdentifier, EsdtTokenPayment, ManagedAddress, ManagedBuffer, ManagedVec,
+        MultiValueEncoded, TokenIdentifier,
+    },
+};
+use multiversx_sc_scenario::{api::StaticApi, num_bigint, scenario_model::*, *};
+use router_mock::ProxyTrait as _;
+
+pub type RustBigUint = num_bigint::BigUint;
+pub type LiquidStakingContract = ContractInfo<liquid_staking::Proxy<StaticApi>>;
+pub type DelegationMockContract = ContractInfo<delegation_mock::Proxy<StaticApi>>;
+pub type RouterMockContract = ContractInfo<router_mock::Proxy<StaticApi>>;
+
+pub const ALL_ROLES: [Role; 6] = [
+    Role::Pauser,
+    Role::FeeManager,
+    Role::ScoreModelManager,
+    Role::WhitelistManager,
+    Role::ReserveTreasurer,
+    Role::Guardian,
+];
+
+pub const LIQUID_STAKING_PATH_EXPR: &str = "file:output/liquid-staking.wasm";
+pub const DELEGATION_MOCK_PATH_EXPR: &str = "file:../mocks/delegation-mock/output/delegation-mock.wasm";
+pub const ROUTER_MOCK_PATH_EXPR: &str = "file:../mocks/router-mock/output/router-mock.wasm";
+
+pub const ADMIN_ADDRESS_EXPR: &str = "address:admin";
+pub const DEPLOYER_ADDRESS_EXPR: &str = "address:deployer";
+pub const DATA_MANAGER_ADDRESS_EXPR: &str = "address:data-manager";
+pub const USER_ADDRESS_EXPR: &str = "address:user";
+pub const OTHER_USER_ADDRESS_EXPR: &str = "address:other-user";
+pub const LIQUID_STAKING_ADDRESS_EXPR: &str = "sc:liquid-staking";
+pub const ROUTER_ADDRESS_EXPR: &str = "sc:router";
+
+pub const LS_TOKEN_ID: &[u8] = b"SEGLD-abcdef";
+pub const LS_TOKEN_ID_EXPR: &str = "str:SEGLD-abcdef";
+pub const UNDELEGATE_TOKEN_ID: &[u8] = b"UNDELEGATE-abcdef";
+pub const UNDELEGATE_TOKEN_ID_EXPR: &str = "str:UNDELEGATE-abcdef";
+
+pub const UNBOND_PERIOD: u64 = 1;
+pub const TOTAL_FEE: u64 = 1_500;
+pub const GAS_LIMIT: u64 = 100_000_000;
+pub const INITIAL_BALANCE: u64 = 1_000;
+
+/// Returns the given amount of EGLD (or sEGLD) with 18 decimals
+pub fn egld(amount: u64) -> RustBigUint {
+    RustBigUint::from(amount) * RustBigUint::from(WAD)
+}
+
+pub fn to_managed(amount: &RustBigUint) -> BigUint<StaticApi> {
+    BigUint::from(amount)
+}
+
+pub fn to_rust(amount: &BigUint<StaticApi>) -> RustBigUint {
+    RustBigUint::from_bytes_be(amount.to_bytes_be().as_slice())
+}
+
+/// Sends the resulting tokens to the caller
+pub fn no_receiver() -> OptionalValue<ManagedAddress<StaticApi>> {
+    OptionalValue::None
+}
+
+/// Does not call any endpoint at the receiver
+pub fn no_endpoint() -> OptionalValue<ManagedBuffer<StaticApi>> {
+    OptionalValue::None
+}
+
+pub fn no_args() -> MultiValueEncoded<StaticApi, ManagedBuffer<StaticApi>> {
+    MultiValueEncoded::new()
+}
+
+pub type ReceiverArgs = (
+    OptionalValue<ManagedAddress<StaticApi>>,
+    OptionalValue<ManagedBuffer<StaticApi>>,
+    MultiValueEncoded<StaticApi, ManagedBuffer<StaticApi>>,
+);
+
+/// Sends the resulting tokens to a given receiver and, optionally, deposits them at the router on behalf of an account
+pub fn receiver_args(receiver_expr: &str, opt_deposit_account_expr: Option<&str>) -> ReceiverArgs {
+    let receiver = AddressValue::from(receiver_expr).to_address();
+    let mut args = MultiValueEncoded::new();
+    let opt_endpoint = match opt_deposit_account_expr {
+        Some(account_expr) => {
+            let account = AddressValue::from(account_expr).to_address();
+            args.push(ManagedBuffer::from(account.as_bytes()));
+            OptionalValue::Some(ManagedBuffer::from(b"deposit"))
+        },
+        None => OptionalValue::None,
+    };
+    (OptionalValue::Some(ManagedAddress::from(receiver)), opt_endpoint, args)
+}
+
+/// Returns the transfers for a list of undelegate NFTs
+pub fn undelegate_nfts(nonces: &[u64]) -> Vec<TxESDT> {
+    nonces
+        .iter()
+        .map(|nonce| TxESDT {
+            esdt_token_identifier: BytesValue::from(UNDELEGATE_TOKEN_ID_EXPR),
+            nonce: U64Value::from(*nonce),
+            esdt_value: BigUintValue::from(1u64),
+        })
+        .collect()
+}
+
+pub fn user_error(err: &[u8]) -> TxExpect {
+    TxExpect::user_error(format!("str:{}", String::from_utf8_lossy(err)))
+}
+
+pub fn delegation_contract_expr(index: usize) -> String {
+    format!("sc:delegation-{index}")
+}
+
+pub struct LiquidStakingSetup {
+    pub world: ScenarioWorld,
+    pub ls_whitebox: WhiteboxContract<liquid_staking::ContractObj<DebugApi>>,
+    pub ls_contract: LiquidStakingContract,
+    pub delegation_contracts: Vec<DelegationMockContract>,
+    pub router_contract: RouterMockContract,
+    deployer_nonce: u64,
+}
+
+impl LiquidStakingSetup {
+    /// Deploys the Liquid Staking smart contract with both tokens already issued and all the required parameters set,
+    /// but without any whitelisted Delegation smart contract.
+    pub fn new() -> Self {
+        let mut world = ScenarioWorld::new();
+        world.set_current_dir_from_workspace("liquid-staking");
+        world.register_contract(LIQUID_STAKING_PATH_EXPR, liquid_staking::ContractBuilder);
+        world.register_contract(DELEGATION_MOCK_PATH_EXPR, delegation_mock::ContractBuilder);
+        world.register_contract(ROUTER_MOCK_PATH_EXPR, router_mock::ContractBuilder);
+
+        let ls_code = world.code_expression(LIQUID_STAKING_PATH_EXPR);
+        let router_code = world.code_expression(ROUTER_MOCK_PATH_EXPR);
+        let initial_balance = egld(INITIAL_BALANCE);
+
+        world.set_state_step(
+            SetStateStep::new()
+                .block_epoch(1u64)
+                .put_account(ADMIN_ADDRESS_EXPR, Account::new().nonce(1).balance(&initial_balance))
+                .put_account(DEPLOYER_ADDRESS_EXPR, Account::new())
+                .put_account(DATA_MANAGER_ADDRESS_EXPR, Account::new().nonce(1))
+                .put_account(USER_ADDRESS_EXPR, Account::new().nonce(1).balance(&initial_balance))
+                .put_account(
+                    OTHER_USER_ADDRESS_EXPR,
+                    Account::new().nonce(1).balance(&initial_balance),
+                )
+                .put_account(ROUTER_ADDRESS_EXPR, Account::new().code(router_code))
+                .put_account(
+                    LIQUID_STAKING_ADDRESS_EXPR,
+                    Account::new()
+                        .code(ls_code)
+                        .owner(ADMIN_ADDRESS_EXPR)
+                        .esdt_roles(
+                            LS_TOKEN_ID_EXPR,
+                            vec!["ESDTRoleLocalMint".to_string(), "ESDTRoleLocalBurn".to_string()],
+                        )
+                        .esdt_roles(
+                            UNDELEGATE_TOKEN_ID_EXPR,
+                            vec!["ESDTRoleNFTCreate".to_string(), "ESDTRoleNFTBurn".to_string()],
+                        ),
+                ),
+        );
+
+        let ls_whitebox = WhiteboxContract::new(LIQUID_STAKING_ADDRESS_EXPR, liquid_staking::contract_obj);
+        let ls_contract = LiquidStakingContract::new(LIQUID_STAKING_ADDRESS_EXPR);
+
+        // the tokens are issued by the system smart contract, so they are directly set here
+        world.whitebox_call(&ls_whitebox, ScCallStep::new().from(ADMIN_ADDRESS_EXPR), |sc| {
+            sc.init(UNBOND_PERIOD, OptionalValue::None);
+            sc.ls_token().set_token_id(TokenIdentifier::from(LS_TOKEN_ID));
+            sc.undelegate_token()
+                .set_token_id(TokenIdentifier::from(UNDELEGATE_TOKEN_ID));
+            sc.undelegate_token_name().set(ManagedBuffer::from(b"Undelegate"));
+        });
+
+        let mut setup = Self {
+            world,
+            ls_whitebox,
+            ls_contract,
+            delegation_contracts: Vec::new(),
+            router_contract: RouterMockContract::new(ROUTER_ADDRESS_EXPR),
+            deployer_nonce: 0,
+        };
+
+        for role in ALL_ROLES {
+            setup.grant_role(role, ADMIN_ADDRESS_EXPR, TxExpect::ok());
+        }
+
+        let data_manager = AddressValue::from(DATA_MANAGER_ADDRESS_EXPR).to_address();
+        let contract_call = setup.ls_contract.set_data_manager(data_manager);
+        setup.admin_call(contract_call);
+        let contract_call = setup.ls_contract.set_total_fee(BigUint::from(TOTAL_FEE));
+        setup.admin_call(contract_call);
+        let contract_call = setup.ls_contract.set_delegation_score_model_params(
+            DelegationScoreMethod::Tvl,
+            to_managed(&egld(0)),
+            to_managed(&egld(1_000_000)),
+            BigUint::zero(),
+            BigUint::zero(),
+            false,
+            OptionalValue::<BigUint<StaticApi>>::None,
+        );
+        setup.admin_call(contract_call);
+        setup.unpause(&[]);
+
+        setup
+    }
+
+    fn admin_call<CC>(&mut self, contract_call: CC)
+    where
+        CC: multiversx_sc::types::ContractCall<StaticApi>,
+    {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(contract_call)
+                .expect(TxExpect::ok()),
+        );
+    }
+
+    pub fn grant_role(&mut self, role: Role, account_expr: &str, expect: TxExpect) {
+        let account = AddressValue::from(account_expr).to_address();
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(self.ls_contract.grant_role(role, account))
+                .expect(expect),
+        );
+    }
+
+    pub fn revoke_role(&mut self, role: Role, account_expr: &str, expect: TxExpect) {
+        let account = AddressValue::from(account_expr).to_address();
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(self.ls_contract.revoke_role(role, account))
+                .expect(expect),
+        );
+    }
+
+    pub fn has_role(&mut self, role: Role, account_expr: &str) -> bool {
+        let account = AddressValue::from(account_expr).to_address();
+        self.world.quick_query(self.ls_contract.has_role(role, account))
+    }
+
+    pub fn role_members(&mut self, role: Role) -> Vec<Address> {
+        let members: MultiValueVec<Address> = self.world.quick_query(self.ls_contract.get_role_members(role));
+        members.into_vec()
+    }
+
+    pub fn set_timelock_delay(&mut self, delay: u64, expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(self.ls_contract.set_timelock_delay(delay))
+                .expect(expect),
+        );
+    }
+
+    /// Queues a timelocked call from the admin and returns its identifier
+    pub fn queue_call(&mut self, action: TimelockAction, arguments: &[Vec<u8>]) -> u64 {
+        let arguments = arguments
+            .iter()
+            .map(|argument| ManagedBuffer::from(argument.as_slice()));
+        self.world.sc_call_get_result(
+            ScCallStep::new().from(ADMIN_ADDRESS_EXPR).call(
+                self.ls_contract
+                    .queue_call(action, MultiValueEncoded::from_iter(arguments)),
+            ),
+        )
+    }
+
+    pub fn queue_call_expect(&mut self, from: &str, action: TimelockAction, arguments: &[Vec<u8>], expect: TxExpect) {
+        let arguments = arguments
+            .iter()
+            .map(|argument| ManagedBuffer::from(argument.as_slice()));
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .call(
+                    self.ls_contract
+                        .queue_call(action, MultiValueEncoded::from_iter(arguments)),
+                )
+                .expect(expect),
+        );
+    }
+
+    pub fn execute_call(&mut self, from: &str, id: u64, expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .call(self.ls_contract.execute_call(id))
+                .expect(expect),
+        );
+    }
+
+    pub fn cancel_call(&mut self, from: &str, id: u64, expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .call(self.ls_contract.cancel_call(id))
+                .expect(expect),
+        );
+    }
+
+    pub fn timelock_delay(&mut self) -> u64 {
+        let value: SingleValue<u64> = self.world.quick_query(self.ls_contract.timelock_delay());
+        value.into()
+    }
+
+    pub fn queued_timelock_call_ids(&mut self) -> Vec<u64> {
+        let mut ids = Vec::new();
+        self.world.whitebox_query(&self.ls_whitebox, |sc| {
+            ids = sc.queued_timelock_call_ids().iter().collect();
+        });
+        ids
+    }
+
+    pub fn total_fee(&mut self) -> RustBigUint {
+        let value: SingleValue<RustBigUint> = self.world.quick_query(self.ls_contract.total_fee());
+        value.into()
+    }
+
+    /// Deploys a new Delegation mock and whitelists it at the Liquid Staking smart contract with the given data.
+    ///
+    /// Whitelisting is done through a whitebox call because the Rust VM never places a smart contract in the
+    /// metachain, which makes `whitelistDelegationContract` fail at the shard check.
+    pub fn add_delegation_contract(&mut self, total_value_locked: u64, apr: u64, service_fee: u64) -> Address {
+        let index = self.delegation_contracts.len() + 1;
+        let address_expr = delegation_contract_expr(index);
+
+        self.world.set_state_step(SetStateStep::new().new_address(
+            DEPLOYER_ADDRESS_EXPR,
+            self.deployer_nonce,
+            address_expr.as_str(),
+        ));
+        self.deployer_nonce += 1;
+
+        let mut delegation_contract = DelegationMockContract::new(address_expr.as_str());
+        let code = self.world.code_expression(DELEGATION_MOCK_PATH_EXPR);
+        self.world.sc_deploy(
+            ScDeployStep::new()
+                .from(DEPLOYER_ADDRESS_EXPR)
+                .code(code)
+                .call(delegation_contract.init(UNBOND_PERIOD)),
+        );
+
+        let address = delegation_contract.to_address();
+        let total_value_locked = egld(total_value_locked);
+        self.world
+            .whitebox_call(&self.ls_whitebox, ScCallStep::new().from(ADMIN_ADDRESS_EXPR), |sc| {
+                let contract = ManagedAddress::from(&address);
+                let total_value_locked = BigUint::from(&total_value_locked);
+                let apr = BigUint::from(apr);
+                let mut contract_data = DelegationContractData {
+                    contract: contract.clone(),
+                    total_value_locked,
+                    cap: None,
+                    nr_nodes: 1,
+                    apr,
+                    service_fee: BigUint::from(service_fee),
+                    delegation_score: BigUint::zero(),
+                    pending_to_delegate: BigUint::zero(),
+                    total_delegated: BigUint::zero(),
+                    pending_to_undelegate: BigUint::zero(),
+                    total_undelegated: BigUint::zero(),
+                    total_withdrawable: BigUint::zero(),
+                    outdated: false,
+                    blacklisted: false,
+                };
+                let delegation_score = sc.compute_delegation_score(&contract_data);
+                contract_data.delegation_score = delegation_score.clone();
+                sc.delegation_contract_data(&contract).set(&contract_data);
+                sc.update_capacity_indexes(None, &contract_data);
+                sc.contract_data_update_epoch(&contract)
+                    .set(sc.blockchain().get_block_epoch());
+                sc.add_and_order_delegation_contract_in_list(&contract, &delegation_score);
+            });
+
+        self.delegation_contracts.push(delegation_contract);
+        address
+    }
+
+    pub fn sort_delegation_contracts_list(&mut self, max_contracts: usize, expect: TxExpect) {
+        let contract_call = self.ls_contract.sort_delegation_contracts_list_endpoint(max_contracts);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .call(contract_call)
+                .expect(expect),
+        );
+    }
+
+    pub fn sort_cursor(&mut self) -> u32 {
+        let value: SingleValue<u32> = self.world.quick_query(self.ls_contract.sort_cursor());
+        value.into()
+    }
+
+    /// Runs the upgrade function of the Liquid Staking smart contract on its current storage
+    pub fn upgrade(&mut self) {
+        self.world
+            .whitebox_call(&self.ls_whitebox, ScCallStep::new().from(ADMIN_ADDRESS_EXPR), |sc| {
+                sc.upgrade();
+            });
+    }
+
+    pub fn delegation_contract(&self, index: usize) -> Address {
+        self.delegation_contracts[index].to_address()
+    }
+
+    pub fn set_block_epoch(&mut self, epoch: u64) {
+        self.world.set_state_step(SetStateStep::new().block_epoch(epoch));
+    }
+
+    pub fn set_block_round(&mut self, round: u64) {
+        self.world.set_state_step(SetStateStep::new().block_round(round));
+    }
+
+    /// Pauses the given operations, or all of them if none is given
+    pub fn pause(&mut self, operations: &[Operation]) {
+        let contract_call = self
+            .ls_contract
+            .pause(MultiValueEncoded::from_iter(operations.iter().copied()));
+        self.admin_call(contract_call);
+    }
+
+    /// Unpauses the given operations, or all of them if none is given
+    pub fn unpause(&mut self, operations: &[Operation]) {
+        let contract_call = self
+            .ls_contract
+            .unpause(MultiValueEncoded::from_iter(operations.iter().copied()));
+        self.admin_call(contract_call);
+    }
+
+    pub fn pause_status(&mut self) -> PauseStatus {
+        self.world.quick_query(self.ls_contract.get_pause_status())
+    }
+
+    /// Overrides the amount delegated to a Delegation smart contract without performing any async call
+    pub fn set_delegated_amount(&mut self, index: usize, amount: &RustBigUint) {
+        let delegation_contract = self.delegation_contract(index);
+        self.world
+            .whitebox_call(&self.ls_whitebox, ScCallStep::new().from(ADMIN_ADDRESS_EXPR), |sc| {
+                let contract = ManagedAddress::from(&delegation_contract);
+                sc.delegation_contract_data(&contract).update(|data| {
+                    data.total_delegated = BigUint::from(amount);
+                });
+            });
+    }
+
+    pub fn blacklist_delegation_contract(&mut self, index: usize) {
+        let delegation_contract = self.delegation_contract(index);
+        let contract_call = self.ls_contract.blacklist_delegation_contract(delegation_contract);
+        self.admin_call(contract_call);
+    }
+
+    pub fn change_delegation_contract_params(
+        &mut self,
+        index: usize,
+        total_value_locked: u64,
+        apr: u64,
+        service_fee: u64,
+    ) {
+        self.change_delegation_contract_params_expect(index, total_value_locked, apr, service_fee, TxExpect::ok());
+    }
+
+    pub fn change_delegation_contract_params_expect(
+        &mut self,
+        index: usize,
+        total_value_locked: u64,
+        apr: u64,
+        service_fee: u64,
+        expect: TxExpect,
+    ) {
+        let delegation_contract = self.delegation_contract(index);
+        let contract_call = self.ls_contract.change_delegation_contract_params(
+            delegation_contract,
+            to_managed(&egld(total_value_locked)),
+            1u64,
+            BigUint::from(apr),
+            BigUint::from(service_fee),
+            OptionalValue::<BigUint<StaticApi>>::None,
+        );
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(DATA_MANAGER_ADDRESS_EXPR)
+                .call(contract_call)
+                .expect(expect),
+        );
+    }
+
+    pub fn change_delegation_contract_nr_nodes(&mut self, index: usize, nr_nodes: u64) {
+        let contract_data = self.contract_data(index);
+        let delegation_contract = self.delegation_contract(index);
+        let contract_call = self.ls_contract.change_delegation_contract_params(
+            delegation_contract,
+            contract_data.total_value_locked,
+            nr_nodes,
+            contract_data.apr,
+            contract_data.service_fee,
+            OptionalValue::<BigUint<StaticApi>>::None,
+        );
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(DATA_MANAGER_ADDRESS_EXPR)
+                .call(contract_call)
+                .expect(TxExpect::ok()),
+        );
+    }
+
+    pub fn set_delegation_score_model_params(
+        &mut self,
+        method: DelegationScoreMethod,
+        min_tvl: u64,
+        max_tvl: u64,
+        expect: TxExpect,
+    ) {
+        let contract_call = self.ls_contract.set_delegation_score_model_params(
+            method,
+            to_managed(&egld(min_tvl)),
+            to_managed(&egld(max_tvl)),
+            BigUint::zero(),
+            BigUint::zero(),
+            true,
+            OptionalValue::<BigUint<StaticApi>>::None,
+        );
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(contract_call)
+                .expect(expect),
+        );
+    }
+
+    pub fn set_tvl_per_node_domain(&mut self, min: u64, max: u64, expect: TxExpect) {
+        let contract_call =
+            self.ls_contract
+                .set_tvl_per_node_domain(to_managed(&egld(min)), to_managed(&egld(max)), true);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(contract_call)
+                .expect(expect),
+        );
+    }
+
+    pub fn set_delegation_score_curve(&mut self, curve: ScoreCurve<StaticApi>, expect: TxExpect) {
+        let contract_call = self.ls_contract.set_delegation_score_curve(curve, true);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(contract_call)
+                .expect(expect),
+        );
+    }
+
+    /// Sets the weighted delegation score terms, each given by its factor, weight, domain and direction
+    pub fn set_delegation_score_terms(
+        &mut self,
+        terms: &[(DelegationScoreFactor, u64, RustBigUint, RustBigUint, bool)],
+        expect: TxExpect,
+    ) {
+        let mut managed_terms = ManagedVec::<StaticApi, DelegationScoreTerm<StaticApi>>::new();
+        for (factor, weight, min, max, down) in terms {
+            managed_terms.push(DelegationScoreTerm {
+                factor: *factor,
+                weight: *weight,
+                min: to_managed(min),
+                max: to_managed(max),
+                down: *down,
+            });
+        }
+
+        let contract_call = self.ls_contract.set_delegation_score_terms(managed_terms, true);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(contract_call)
+                .expect(expect),
+        );
+    }
+
+    pub fn set_data_bounds(
+        &mut self,
+        max_tvl_change: u64,
+        max_apr: u64,
+        max_service_fee: u64,
+        action: DataBoundsAction,
+    ) {
+        let contract_call = self.ls_contract.set_data_bounds(
+            max_tvl_change,
+            BigUint::from(max_apr),
+            BigUint::from(max_service_fee),
+            0u64,
+            action,
+        );
+        self.admin_call(contract_call);
+    }
+
+    pub fn delegation_score_model(&mut self) -> DelegationScoreModel<StaticApi> {
+        let value: SingleValue<DelegationScoreModel<StaticApi>> =
+            self.world.quick_query(self.ls_contract.delegation_score_model());
+        value.into()
+    }
+
+    pub fn data_bounds(&mut self) -> DataBounds<StaticApi> {
+        let value: SingleValue<DataBounds<StaticApi>> = self.world.quick_query(self.ls_contract.data_bounds());
+        value.into()
+    }
+
+    pub fn set_concentration_limits(&mut self, max_contract_share: u64, max_operator_share: u64) {
+        let contract_call = self
+            .ls_contract
+            .set_concentration_limits(max_contract_share, max_operator_share);
+        self.admin_call(contract_call);
+    }
+
+    pub fn set_delegation_sampling_model_params(&mut self, tolerance: u64, max_service_fee: u64, premium: u64) {
+        let contract_call = self.ls_contract.set_delegation_sampling_model_params(
+            BigUint::from(tolerance),
+            BigUint::from(max_service_fee),
+            BigUint::from(premium),
+        );
+        self.admin_call(contract_call);
+    }
+
+    pub fn set_operator(&mut self, index: usize, operator_id: u64, expect: TxExpect) {
+        let delegation_contract = self.delegation_contract(index);
+        let contract_call = self.ls_contract.set_operator(delegation_contract, operator_id);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(contract_call)
+                .expect(expect),
+        );
+    }
+
+    pub fn remove_operator(&mut self, index: usize, expect: TxExpect) {
+        let delegation_contract = self.delegation_contract(index);
+        let contract_call = self.ls_contract.remove_operator(delegation_contract);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(contract_call)
+                .expect(expect),
+        );
+    }
+
+    pub fn release_quarantine(&mut self, index: usize, expect: TxExpect) {
+        let delegation_contract = self.delegation_contract(index);
+        let contract_call = self.ls_contract.release_quarantine(delegation_contract);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(contract_call)
+                .expect(expect),
+        );
+    }
+
+    pub fn quarantined_data(&mut self, index: usize) -> DataReport<StaticApi> {
+        let delegation_contract = self.delegation_contract(index);
+        let value: SingleValue<DataReport<StaticApi>> = self
+            .world
+            .quick_query(self.ls_contract.quarantined_data(delegation_contract));
+        value.into()
+    }
+
+    pub fn add_to_migration_whitelist(&mut self, user_expr: &str, index: usize) {
+        let user = AddressValue::from(user_expr).to_address();
+        let delegation_contract = self.delegation_contract(index);
+        let contract_call = self
+            .ls_contract
+            .add_to_migration_whitelist(ManagedAddress::from(user), ManagedAddress::from(delegation_contract));
+        self.admin_call(contract_call);
+    }
+
+    pub fn add_data_reporter(&mut self, reporter_expr: &str) {
+        let reporter = AddressValue::from(reporter_expr).to_address();
+        let contract_call = self.ls_contract.add_data_reporter(reporter);
+        self.admin_call(contract_call);
+    }
+
+    pub fn remove_data_reporter(&mut self, reporter_expr: &str, expect: TxExpect) {
+        let reporter = AddressValue::from(reporter_expr).to_address();
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(self.ls_contract.remove_data_reporter(reporter))
+                .expect(expect),
+        );
+    }
+
+    pub fn set_data_reporters_quorum(&mut self, quorum: usize, expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(self.ls_contract.set_data_reporters_quorum(quorum))
+                .expect(expect),
+        );
+    }
+
+    pub fn set_max_data_report_deviation(&mut self, max_deviation: u64) {
+        let contract_call = self.ls_contract.set_max_data_report_deviation(max_deviation);
+        self.admin_call(contract_call);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn submit_delegation_contract_data(
+        &mut self,
+        from: &str,
+        index: usize,
+        total_value_locked: u64,
+        apr: u64,
+        service_fee: u64,
+        opt_cap: Option<u64>,
+        expect: TxExpect,
+    ) {
+        let delegation_contract = self.delegation_contract(index);
+        let opt_cap = match opt_cap {
+            Some(cap) => OptionalValue::Some(to_managed(&egld(cap))),
+            None => OptionalValue::None,
+        };
+        let contract_call = self.ls_contract.submit_delegation_contract_data(
+            delegation_contract,
+            to_managed(&egld(total_value_locked)),
+            1u64,
+            BigUint::from(apr),
+            BigUint::from(service_fee),
+            opt_cap,
+        );
+        self.world
+            .sc_call(ScCallStep::new().from(from).call(contract_call).expect(expect));
+    }
+
+    pub fn data_reports(&mut self, index: usize) -> Vec<(Address, DataReport<StaticApi>)> {
+        let delegation_contract = self.delegation_contract(index);
+        let reports: MultiValueVec<MultiValue2<ManagedAddress<StaticApi>, DataReport<StaticApi>>> = self
+            .world
+            .quick_query(self.ls_contract.get_data_reports(delegation_contract));
+        reports
+            .into_vec()
+            .into_iter()
+            .map(|report| {
+                let (reporter, report) = report.into_tuple();
+                (reporter.to_address(), report)
+            })
+            .collect()
+    }
+
+    pub fn set_liquidity_buffer_target(&mut self, target: u64) {
+        let contract_call = self.ls_contract.set_liquidity_buffer_target(BigUint::from(target));
+        self.admin_call(contract_call);
+    }
+
+    pub fn set_rewards_vesting_period(&mut self, period: u64, expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(self.ls_contract.set_rewards_vesting_period(period))
+                .expect(expect),
+        );
+    }
+
+    pub fn set_instant_undelegate_fee(&mut self, fee: u64, recipient: InstantUndelegateFeeRecipient) {
+        let contract_call = self
+            .ls_contract
+            .set_instant_undelegate_fee(BigUint::from(fee), recipient);
+        self.admin_call(contract_call);
+    }
+
+    pub fn liquidity_buffer_target(&mut self) -> RustBigUint {
+        let value: SingleValue<RustBigUint> = self.world.quick_query(self.ls_contract.liquidity_buffer_target());
+        value.into()
+    }
+
+    pub fn rewards_vesting_period(&mut self) -> u64 {
+        let value: SingleValue<u64> = self.world.quick_query(self.ls_contract.rewards_vesting_period());
+        value.into()
+    }
+
+    pub fn instant_undelegate_fee(&mut self) -> RustBigUint {
+        let value: SingleValue<RustBigUint> = self.world.quick_query(self.ls_contract.instant_undelegate_fee());
+        value.into()
+    }
+
+    pub fn instant_undelegate_fee_recipient(&mut self) -> InstantUndelegateFeeRecipient {
+        let value: SingleValue<InstantUndelegateFeeRecipient> = self
+            .world
+            .quick_query(self.ls_contract.instant_undelegate_fee_recipient());
+        value.into()
+    }
+
+    pub fn has_delegation_sampling_model(&mut self) -> bool {
+        let mut has_sampling_model = false;
+        self.world.whitebox_query(&self.ls_whitebox, |sc| {
+            has_sampling_model = !sc.delegation_sampling_model().is_empty();
+        });
+        has_sampling_model
+    }
+
+    pub fn undelegation_mode(&mut self) -> UndelegationMode {
+        let value: SingleValue<UndelegationMode> = self.world.quick_query(self.ls_contract.undelegation_mode());
+        value.into()
+    }
+
+    pub fn deactivate_undelegation_algorithm(&mut self, expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .call(self.ls_contract.deactivate_undelegation_algorithm())
+                .expect(expect),
+        );
+    }
+
+    pub fn set_max_contract_data_age(&mut self, max_age: u64) {
+        let contract_call = self.ls_contract.set_max_contract_data_age(max_age);
+        self.admin_call(contract_call);
+    }
+
+    pub fn stale_delegation_contracts(&mut self) -> Vec<Address> {
+        let contracts: MultiValueVec<Address> = self
+            .world
+            .quick_query(self.ls_contract.get_stale_delegation_contracts());
+        contracts.into_vec()
+    }
+
+    pub fn set_undelegation_mode(&mut self, mode: UndelegationMode) {
+        self.world
+            .whitebox_call(&self.ls_whitebox, ScCallStep::new().from(ADMIN_ADDRESS_EXPR), |sc| {
+                sc.set_undelegation_mode_internal(mode);
+            });
+    }
+
+    // Delegation mock
+
+    pub fn set_failure(&mut self, index: usize, endpoint: MockEndpoint, fail: bool) {
+        let contract_call = self.delegation_contracts[index].set_failure(endpoint, fail);
+        self.admin_call(contract_call);
+    }
+
+    pub fn add_rewards(&mut self, index: usize, amount: &RustBigUint) {
+        let delegator = self.ls_contract.to_address();
+        let contract_call = self.delegation_contracts[index].add_rewards(delegator);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .egld_value(amount)
+                .call(contract_call)
+                .expect(TxExpect::ok()),
+        );
+    }
+
+    /// Sets the service fee and the delegation cap (zero if uncapped) reported by a Delegation smart contract
+    pub fn set_contract_config(&mut self, index: usize, service_fee: u64, max_delegation_cap: u64) {
+        let contract_call = self.delegation_contracts[index]
+            .set_contract_config(BigUint::from(service_fee), to_managed(&egld(max_delegation_cap)));
+        self.admin_call(contract_call);
+    }
+
+    pub fn set_total_active_stake(&mut self, index: usize, total_active_stake: u64) {
+        let contract_call =
+            self.delegation_contracts[index].set_total_active_stake(to_managed(&egld(total_active_stake)));
+        self.admin_call(contract_call);
+    }
+
+    pub fn delegated_at(&mut self, index: usize) -> RustBigUint {
+        let delegator = self.ls_contract.to_address();
+        let contract_call = self.delegation_contracts[index].delegated(delegator);
+        let value: SingleValue<RustBigUint> = self.world.quick_query(contract_call);
+        value.into()
+    }
+
+    // Liquid Staking endpoints
+
+    pub fn delegate(&mut self, from: &str, amount: &RustBigUint) -> EsdtTokenPayment<StaticApi> {
+        self.world.sc_call_get_result(
+            ScCallStep::new()
+                .from(from)
+                .egld_value(amount)
+                .call(self.ls_contract.delegate(
+                    OptionalValue::<BigUint<StaticApi>>::None,
+                    no_receiver(),
+                    no_endpoint(),
+                    no_args(),
+                )),
+        )
+    }
+
+    pub fn delegate_expect_err(&mut self, from: &str, amount: &RustBigUint, err: &[u8]) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .egld_value(amount)
+                .call(self.ls_contract.delegate(
+                    OptionalValue::<BigUint<StaticApi>>::None,
+                    no_receiver(),
+                    no_endpoint(),
+                    no_args(),
+                ))
+                .expect(user_error(err)),
+        );
+    }
+
+    pub fn delegate_with_min_shares(
+        &mut self,
+        from: &str,
+        amount: &RustBigUint,
+        min_shares: &RustBigUint,
+        expect: TxExpect,
+    ) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .egld_value(amount)
+                .call(self.ls_contract.delegate(
+                    OptionalValue::Some(to_managed(min_shares)),
+                    no_receiver(),
+                    no_endpoint(),
+                    no_args(),
+                ))
+                .expect(expect),
+        );
+    }
+
+    pub fn delegate_to(
+        &mut self,
+        from: &str,
+        amount: &RustBigUint,
+        receiver_expr: &str,
+        opt_deposit_account_expr: Option<&str>,
+        expect: TxExpect,
+    ) {
+        let (opt_receiver, opt_endpoint, args) = receiver_args(receiver_expr, opt_deposit_account_expr);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .egld_value(amount)
+                .call(
+                    self.ls_contract
+                        .delegate(OptionalValue::Some(BigUint::zero()), opt_receiver, opt_endpoint, args),
+                )
+                .expect(expect),
+        );
+    }
+
+    pub fn delegate_pending_amount(&mut self, index: usize, opt_amount: Option<&RustBigUint>) {
+        self.delegate_pending_amount_expect(index, opt_amount, TxExpect::ok());
+    }
+
+    pub fn delegate_pending_amount_expect(&mut self, index: usize, opt_amount: Option<&RustBigUint>, expect: TxExpect) {
+        let delegation_contract = self.delegation_contract(index);
+        let opt_amount = OptionalValue::from(opt_amount.map(to_managed));
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .gas_limit(GAS_LIMIT)
+                .call(
+                    self.ls_contract
+                        .delegate_pending_amount(delegation_contract, opt_amount),
+                )
+                .expect(expect),
+        );
+    }
+
+    pub fn delegate_all_pending(&mut self, expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .gas_limit(GAS_LIMIT)
+                .call(self.ls_contract.delegate_all_pending())
+                .expect(expect),
+        );
+    }
+
+    pub fn undelegate(&mut self, from: &str, shares: &RustBigUint) -> EsdtTokenPayment<StaticApi> {
+        self.world.sc_call_get_result(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(LS_TOKEN_ID_EXPR, 0, shares)
+                .call(self.ls_contract.undelegate(
+                    OptionalValue::<ManagedAddress<StaticApi>>::None,
+                    OptionalValue::<BigUint<StaticApi>>::None,
+                    no_receiver(),
+                    no_endpoint(),
+                    no_args(),
+                )),
+        )
+    }
+
+    pub fn undelegate_from(&mut self, from: &str, shares: &RustBigUint, index: usize, expect: TxExpect) {
+        let delegation_contract = self.delegation_contract(index);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(LS_TOKEN_ID_EXPR, 0, shares)
+                .call(self.ls_contract.undelegate(
+                    OptionalValue::Some(delegation_contract),
+                    OptionalValue::<BigUint<StaticApi>>::None,
+                    no_receiver(),
+                    no_endpoint(),
+                    no_args(),
+                ))
+                .expect(expect),
+        );
+    }
+
+    pub fn undelegate_with_min_egld(
+        &mut self,
+        from: &str,
+        shares: &RustBigUint,
+        opt_index: Option<usize>,
+        min_egld: &RustBigUint,
+        expect: TxExpect,
+    ) {
+        // the zero address keeps the delegation algorithm
+        let delegation_contract = match opt_index {
+            Some(index) => self.delegation_contract(index),
+            None => Address::zero(),
+        };
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(LS_TOKEN_ID_EXPR, 0, shares)
+                .call(self.ls_contract.undelegate(
+                    OptionalValue::Some(delegation_contract),
+                    OptionalValue::Some(to_managed(min_egld)),
+                    no_receiver(),
+                    no_endpoint(),
+                    no_args(),
+                ))
+                .expect(expect),
+        );
+    }
+
+    pub fn undelegate_to(
+        &mut self,
+        from: &str,
+        shares: &RustBigUint,
+        receiver_expr: &str,
+        opt_deposit_account_expr: Option<&str>,
+        expect: TxExpect,
+    ) {
+        let (opt_receiver, opt_endpoint, args) = receiver_args(receiver_expr, opt_deposit_account_expr);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(LS_TOKEN_ID_EXPR, 0, shares)
+                .call(self.ls_contract.undelegate(
+                    OptionalValue::Some(ManagedAddress::zero()),
+                    OptionalValue::Some(BigUint::zero()),
+                    opt_receiver,
+                    opt_endpoint,
+                    args,
+                ))
+                .expect(expect),
+        );
+    }
+
+    pub fn undelegate_expect_err(&mut self, from: &str, shares: &RustBigUint, err: &[u8]) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(LS_TOKEN_ID_EXPR, 0, shares)
+                .call(self.ls_contract.undelegate(
+                    OptionalValue::<ManagedAddress<StaticApi>>::None,
+                    OptionalValue::<BigUint<StaticApi>>::None,
+                    no_receiver(),
+                    no_endpoint(),
+                    no_args(),
+                ))
+                .expect(user_error(err)),
+        );
+    }
+
+    pub fn split_undelegate_nft(&mut self, from: &str, nonce: u64, amounts: &[RustBigUint], expect: TxExpect) {
+        let amounts = amounts
+            .iter()
+            .map(to_managed)
+            .collect::<MultiValueEncoded<StaticApi, _>>();
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(UNDELEGATE_TOKEN_ID_EXPR, nonce, "1")
+                .call(self.ls_contract.split_undelegate_nft(amounts))
+                .expect(expect),
+        );
+    }
+
+    pub fn merge_undelegate_nfts(&mut self, from: &str, nonces: &[u64], expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .multi_esdt_transfer(undelegate_nfts(nonces))
+                .call(self.ls_contract.merge_undelegate_nfts())
+                .expect(expect),
+        );
+    }
+
+    pub fn cancel_undelegate(&mut self, from: &str, nonce: u64, expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(UNDELEGATE_TOKEN_ID_EXPR, nonce, "1")
+                .call(
+                    self.ls_contract
+                        .cancel_undelegate(OptionalValue::<BigUint<StaticApi>>::None),
+                )
+                .expect(expect),
+        );
+    }
+
+    pub fn undelegate_pending_amount(&mut self, index: usize) {
+        self.undelegate_pending_amount_expect(index, TxExpect::ok());
+    }
+
+    pub fn undelegate_pending_amount_expect(&mut self, index: usize, expect: TxExpect) {
+        let delegation_contract = self.delegation_contract(index);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .gas_limit(GAS_LIMIT)
+                .call(self.ls_contract.undelegate_pending_amount(delegation_contract))
+                .expect(expect),
+        );
+    }
+
+    pub fn undelegate_all_pending(&mut self, expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .gas_limit(GAS_LIMIT)
+                .call(self.ls_contract.undelegate_all_pending())
+                .expect(expect),
+        );
+    }
+
+    pub fn withdraw_from(&mut self, index: usize) {
+        self.withdraw_from_expect(index, TxExpect::ok());
+    }
+
+    pub fn withdraw_from_expect(&mut self, index: usize, expect: TxExpect) {
+        let delegation_contract = self.delegation_contract(index);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .gas_limit(GAS_LIMIT)
+                .call(self.ls_contract.withdraw_from(delegation_contract))
+                .expect(expect),
+        );
+    }
+
+    pub fn withdraw(&mut self, from: &str, nonce: u64) -> RustBigUint {
+        self.world.sc_call_get_result(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(UNDELEGATE_TOKEN_ID_EXPR, nonce, "1")
+                .call(self.ls_contract.withdraw(no_receiver(), no_endpoint(), no_args())),
+        )
+    }
+
+    pub fn withdraw_to(
+        &mut self,
+        from: &str,
+        nonce: u64,
+        receiver_expr: &str,
+        opt_deposit_account_expr: Option<&str>,
+        expect: TxExpect,
+    ) {
+        let (opt_receiver, opt_endpoint, args) = receiver_args(receiver_expr, opt_deposit_account_expr);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(UNDELEGATE_TOKEN_ID_EXPR, nonce, "1")
+                .call(self.ls_contract.withdraw(opt_receiver, opt_endpoint, args))
+                .expect(expect),
+        );
+    }
+
+    pub fn withdraw_many(&mut self, from: &str, nonces: &[u64]) -> RustBigUint {
+        self.world.sc_call_get_result(
+            ScCallStep::new()
+                .from(from)
+                .multi_esdt_transfer(undelegate_nfts(nonces))
+                .call(self.ls_contract.withdraw(no_receiver(), no_endpoint(), no_args())),
+        )
+    }
+
+    pub fn withdraw_many_expect_err(&mut self, from: &str, nonces: &[u64], err: &[u8]) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .multi_esdt_transfer(undelegate_nfts(nonces))
+                .call(self.ls_contract.withdraw(no_receiver(), no_endpoint(), no_args()))
+                .expect(user_error(err)),
+        );
+    }
+
+    pub fn withdraw_expect_err(&mut self, from: &str, nonce: u64, err: &[u8]) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(UNDELEGATE_TOKEN_ID_EXPR, nonce, "1")
+                .call(self.ls_contract.withdraw(no_receiver(), no_endpoint(), no_args()))
+                .expect(user_error(err)),
+        );
+    }
+
+    pub fn withdraw_with_ls_token_expect_err(&mut self, from: &str, shares: &RustBigUint, err: &[u8]) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(LS_TOKEN_ID_EXPR, 0, shares)
+                .call(self.ls_contract.withdraw(no_receiver(), no_endpoint(), no_args()))
+                .expect(user_error(err)),
+        );
+    }
+
+    pub fn instant_undelegate(&mut self, from: &str, shares: &RustBigUint) -> RustBigUint {
+        self.world.sc_call_get_result(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(LS_TOKEN_ID_EXPR, 0, shares)
+                .call(
+                    self.ls_contract
+                        .instant_undelegate(OptionalValue::<BigUint<StaticApi>>::None),
+                ),
+        )
+    }
+
+    pub fn instant_undelegate_with_min_egld(
+        &mut self,
+        from: &str,
+        shares: &RustBigUint,
+        min_egld: &RustBigUint,
+        expect: TxExpect,
+    ) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(LS_TOKEN_ID_EXPR, 0, shares)
+                .call(
+                    self.ls_contract
+                        .instant_undelegate(OptionalValue::Some(to_managed(min_egld))),
+                )
+                .expect(expect),
+        );
+    }
+
+    pub fn instant_undelegate_expect_err(&mut self, from: &str, shares: &RustBigUint, err: &[u8]) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(LS_TOKEN_ID_EXPR, 0, shares)
+                .call(
+                    self.ls_contract
+                        .instant_undelegate(OptionalValue::<BigUint<StaticApi>>::None),
+                )
+                .expect(user_error(err)),
+        );
+    }
+
+    pub fn refill_liquidity_buffer(&mut self, opt_amount: Option<&RustBigUint>, expect: TxExpect) {
+        let opt_amount = OptionalValue::from(opt_amount.map(to_managed));
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .call(self.ls_contract.refill_liquidity_buffer(opt_amount))
+                .expect(expect),
+        );
+    }
+
+    pub fn withdraw_buffer_refill(&mut self, refill_id: u64, expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .call(self.ls_contract.withdraw_buffer_refill(refill_id))
+                .expect(expect),
+        );
+    }
+
+    pub fn set_rebalance_params(&mut self, min_deviation: u64, min_amount: &RustBigUint, cooldown_epochs: u64) {
+        let contract_call =
+            self.ls_contract
+                .set_rebalance_params(min_deviation, to_managed(min_amount), cooldown_epochs);
+        self.admin_call(contract_call);
+    }
+
+    pub fn rebalance(&mut self, from_index: usize, to_index: usize, amount: &RustBigUint, expect: TxExpect) {
+        let delegation_contract_from = self.delegation_contract(from_index);
+        let delegation_contract_to = self.delegation_contract(to_index);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .call(
+                    self.ls_contract
+                        .rebalance(delegation_contract_from, delegation_contract_to, to_managed(amount)),
+                )
+                .expect(expect),
+        );
+    }
+
+    pub fn complete_rebalance(&mut self, rebalance_id: u64, expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .call(self.ls_contract.complete_rebalance(rebalance_id))
+                .expect(expect),
+        );
+    }
+
+    pub fn claim_rewards_from(&mut self, index: usize) {
+        self.claim_rewards_from_expect(index, TxExpect::ok());
+    }
+
+    pub fn claim_rewards_from_expect(&mut self, index: usize, expect: TxExpect) {
+        let delegation_contract = self.delegation_contract(index);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .gas_limit(GAS_LIMIT)
+                .call(self.ls_contract.claim_rewards_from(delegation_contract))
+                .expect(expect),
+        );
+    }
+
+    pub fn claim_all_and_delegate_rewards(&mut self, expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .gas_limit(GAS_LIMIT)
+                .call(self.ls_contract.claim_all_and_delegate_rewards())
+                .expect(expect),
+        );
+    }
+
+    pub fn refresh_delegation_contract_data(&mut self, index: usize, expect: TxExpect) {
+        let delegation_contract = self.delegation_contract(index);
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .gas_limit(GAS_LIMIT)
+                .call(self.ls_contract.refresh_delegation_contract_data(delegation_contract))
+                .expect(expect),
+        );
+    }
+
+    pub fn delegate_rewards(&mut self, opt_amount: Option<&RustBigUint>) {
+        self.delegate_rewards_expect(opt_amount, TxExpect::ok());
+    }
+
+    pub fn delegate_rewards_expect(&mut self, opt_amount: Option<&RustBigUint>, expect: TxExpect) {
+        let opt_amount = OptionalValue::from(opt_amount.map(to_managed));
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .gas_limit(GAS_LIMIT)
+                .call(self.ls_contract.delegate_rewards(opt_amount))
+                .expect(expect),
+        );
+    }
+
+    pub fn penalize(
+        &mut self,
+        from: &str,
+        index: usize,
+        source: PenaltySource,
+        opt_amount: Option<&RustBigUint>,
+        expect: TxExpect,
+    ) {
+        let delegation_contract = self.delegation_contract(index);
+        let opt_amount = OptionalValue::from(opt_amount.map(to_managed));
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .call(self.ls_contract.penalize(delegation_contract, source, opt_amount))
+                .expect(expect),
+        );
+    }
+
+    pub fn withdraw_penalty(&mut self, penalty_id: u64, expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .call(self.ls_contract.withdraw_penalty(penalty_id))
+                .expect(expect),
+        );
+    }
+
+    pub fn delegate_penalty(&mut self, penalty_id: u64, opt_amount: Option<&RustBigUint>, expect: TxExpect) {
+        let opt_amount = OptionalValue::from(opt_amount.map(to_managed));
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(USER_ADDRESS_EXPR)
+                .call(self.ls_contract.delegate_penalty(penalty_id, opt_amount))
+                .expect(expect),
+        );
+    }
+
+    pub fn withdraw_from_penalty(&mut self, from: &str, penalty_id: u64, shares: &RustBigUint, expect: TxExpect) {
+        self.world.sc_call(
+            ScCallStep::new()
+                .from(from)
+                .esdt_transfer(LS_TOKEN_ID_EXPR, 0, shares)
+                .call(self.ls_contract.withdraw_from_penalty(penalty_id))
+                .expect(expect),
+        );
+    }
+
+    // Liquid Staking views
+
+    pub fn cash_reserve(&mut self) -> RustBigUint {
+        let value: SingleValue<RustBigUint> = self.world.quick_query(self.ls_contract.cash_reserve());
+        value.into()
+    }
+
+    pub fn ls_token_supply(&mut self) -> RustBigUint {
+        let value: SingleValue<RustBigUint> = self.world.quick_query(self.ls_contract.ls_token_supply());
+        value.into()
+    }
+
+    pub fn rewards_reserve(&mut self) -> RustBigUint {
+        let value: SingleValue<RustBigUint> = self.world.quick_query(self.ls_contract.rewards_reserve());
+        value.into()
+    }
+
+    pub fn unvested_rewards(&mut self) -> RustBigUint {
+        self.world.quick_query(self.ls_contract.get_unvested_rewards())
+    }
+
+    pub fn rewards_stream(&mut self) -> RewardsStream<StaticApi> {
+        let value: SingleValue<RewardsStream<StaticApi>> = self.world.quick_query(self.ls_contract.rewards_stream());
+        value.into()
+    }
+
+    pub fn protocol_reserve(&mut self) -> RustBigUint {
+        let value: SingleValue<RustBigUint> = self.world.quick_query(self.ls_contract.protocol_reserve());
+        value.into()
+    }
+
+    pub fn total_undelegated(&mut self) -> RustBigUint {
+        let value: SingleValue<RustBigUint> = self.world.quick_query(self.ls_contract.total_undelegated());
+        value.into()
+    }
+
+    pub fn total_withdrawable(&mut self) -> RustBigUint {
+        let value: SingleValue<RustBigUint> = self.world.quick_query(self.ls_contract.total_withdrawable());
+        value.into()
+    }
+
+    pub fn liquidity_buffer(&mut self) -> RustBigUint {
+        let value: SingleValue<RustBigUint> = self.world.quick_query(self.ls_contract.liquidity_buffer());
+        value.into()
+    }
+
+    pub fn pending_buffer_refill(&mut self) -> RustBigUint {
+        let value: SingleValue<RustBigUint> = self.world.quick_query(self.ls_contract.pending_buffer_refill());
+        value.into()
+    }
+
+    pub fn concentration(&mut self, index: usize) -> RustBigUint {
+        let delegation_contract = self.delegation_contract(index);
+        self.world
+            .quick_query(self.ls_contract.get_concentration(delegation_contract))
+    }
+
+    pub fn operator_concentration(&mut self, operator_id: u64) -> RustBigUint {
+        self.world
+            .quick_query(self.ls_contract.get_operator_concentration(operator_id))
+    }
+
+    pub fn pending_rebalance(&mut self) -> RustBigUint {
+        let value: SingleValue<RustBigUint> = self.world.quick_query(self.ls_contract.pending_rebalance());
+        value.into()
+    }
+
+    pub fn target_allocation(&mut self, index: usize) -> RustBigUint {
+        let delegation_contract = self.delegation_contract(index);
+        self.world
+            .quick_query(self.ls_contract.get_target_allocation(delegation_contract))
+    }
+
+    pub fn max_rebalance_amount(&mut self, from_index: usize, to_index: usize) -> RustBigUint {
+        let delegation_contract_from = self.delegation_contract(from_index);
+        let delegation_contract_to = self.delegation_contract(to_index);
+        self.world.quick_query(
+            self.ls_contract
+                .get_max_rebalance_amount(delegation_contract_from, delegation_contract_to),
+        )
+    }
+
+    pub fn liquidity_buffer_deficit(&mut self) -> RustBigUint {
+        self.world.quick_query(self.ls_contract.get_liquidity_buffer_deficit())
+    }
+
+    pub fn preview_delegate(&mut self, amount: &RustBigUint) -> RustBigUint {
+        self.world
+            .quick_query(self.ls_contract.preview_delegate(to_managed(amount)))
+    }
+
+    pub fn preview_undelegate(&mut self, shares: &RustBigUint) -> RustBigUint {
+        self.world
+            .quick_query(self.ls_contract.preview_undelegate(to_managed(shares)))
+    }
+
+    pub fn preview_instant_undelegate(&mut self, shares: &RustBigUint) -> RustBigUint {
+        self.world
+            .quick_query(self.ls_contract.preview_instant_undelegate(to_managed(shares)))
+    }
+
+    pub fn router_deposit(&mut self, account_expr: &str, token_id: &[u8], token_nonce: u64) -> RustBigUint {
+        let account = AddressValue::from(account_expr).to_address();
+        let token_id = if token_id == b"EGLD" {
+            EgldOrEsdtTokenIdentifier::egld()
+        } else {
+            EgldOrEsdtTokenIdentifier::esdt(TokenIdentifier::from(token_id))
+        };
+        let contract_call = self.router_contract.deposits(account, token_id, token_nonce);
+        let value: SingleValue<RustBigUint> = self.world.quick_query(contract_call);
+        value.into()
+    }
+
+    pub fn exchange_rate(&mut self) -> RustBigUint {
+        self.world.quick_query(self.ls_contract.get_exchange_rate())
+    }
+
+    pub fn contract_data(&mut self, index: usize) -> DelegationContractData<StaticApi> {
+        let delegation_contract = self.delegation_contract(index);
+        let value: SingleValue<DelegationContractData<StaticApi>> = self
+            .world
+            .quick_query(self.ls_contract.delegation_contract_data(delegation_contract));
+        value.into()
+    }
+
+    pub fn operator_data(&mut self, operator_id: u64) -> OperatorData<StaticApi> {
+        let value: SingleValue<OperatorData<StaticApi>> =
+            self.world.quick_query(self.ls_contract.operator_data(operator_id));
+        value.into()
+    }
+
+    pub fn concentration_limits(&mut self) -> ConcentrationLimits {
+        let value: SingleValue<ConcentrationLimits> = self.world.quick_query(self.ls_contract.concentration_limits());
+        value.into()
+    }
+
+    pub fn has_operator_data(&mut self, operator_id: u64) -> bool {
+        let mut has_operator_data = false;
+        self.world.whitebox_query(&self.ls_whitebox, |sc| {
+            has_operator_data = !sc.operator_data(operator_id).is_empty();
+        });
+        has_operator_data
+    }
+
+    pub fn penalty(&mut self, penalty_id: u64) -> Penalty<StaticApi> {
+        let value: SingleValue<Penalty<StaticApi>> = self.world.quick_query(self.ls_contract.penalties(penalty_id));
+        value.into()
+    }
+
+    pub fn last_undelegate_epoch(&mut self) -> u64 {
+        let value: SingleValue<u64> = self.world.quick_query(self.ls_contract.last_undelegate_epoch());
+        value.into()
+    }
+
+    pub fn next_penalty_id(&mut self) -> u64 {
+        let value: SingleValue<u64> = self.world.quick_query(self.ls_contract.next_penalty_id());
+        value.into()
+    }
+
+    pub fn next_buffer_refill_id(&mut self) -> u64 {
+        let value: SingleValue<u64> = self.world.quick_query(self.ls_contract.next_buffer_refill_id());
+        value.into()
+    }
+
+    pub fn invariant_violations(&mut self) -> Vec<InvariantViolation<StaticApi>> {
+        let mut encoded = Vec::new();
+        self.world.whitebox_query(&self.ls_whitebox, |sc| {
+            encoded = top_encode_to_vec_u8_or_panic(&sc.check_invariants());
+        });
+        let violations =
+            ManagedVec::<StaticApi, InvariantViolation<StaticApi>>::top_decode(encoded.as_slice()).unwrap();
+        violations.into_iter().collect()
+    }
+
+    pub fn undelegate_attributes(&mut self, nonce: u64) -> UndelegateAttributes<StaticApi> {
+        let mut encoded = Vec::new();
+        self.world.whitebox_query(&self.ls_whitebox, |sc| {
+            let attributes: UndelegateAttributes<DebugApi> = sc.undelegate_token().get_token_attributes(nonce);
+            encoded = top_encode_to_vec_u8_or_panic(&attributes);
+        });
+        UndelegateAttributes::top_decode(encoded.as_slice()).unwrap()
+    }
+
+    /// Returns the indexes of the Delegation smart contracts in the list order
+    pub fn delegation_contracts_list(&mut self) -> Vec<usize> {
+        let mut list = Vec::new();
+        self.world.whitebox_query(&self.ls_whitebox, |sc| {
+            list = sc
+                .delegation_contracts_list()
+                .iter()
+                .map(|node| node.into_value().to_address())
+                .collect();
+        });
+        list.iter()
+            .map(|address| {
+                self.delegation_contracts
+                    .iter()
+                    .position(|contract| &contract.to_address() == address)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    /// Returns the delegation score of each Delegation smart contract in list order, checking that the list index is
+    /// consistent with the list itself
+    pub fn indexed_delegation_scores(&mut self) -> Vec<u64> {
+        let mut scores = Vec::new();
+        self.world.whitebox_query(&self.ls_whitebox, |sc| {
+            let mut previous_score = None;
+            for node in sc.delegation_contracts_list().iter() {
+                let node_id = node.get_node_id();
+                let position = sc.list_position(node.get_value_as_ref()).get();
+                assert_eq!(position.node_id, node_id);
+
+                // the first node of each delegation score is its head
+                let head_node_id = sc.score_head_node_id(position.delegation_score).get();
+                if previous_score != Some(position.delegation_score) {
+                    assert_eq!(head_node_id, node_id);
+                } else {
+                    assert_ne!(head_node_id, node_id);
+                }
+
+                previous_score = Some(position.delegation_score);
+                scores.push(position.delegation_score);
+            }
+        });
+        scores
+    }
+
+    pub fn is_penalty_cleared(&mut self, penalty_id: u64) -> bool {
+        let mut cleared = false;
+        self.world.whitebox_query(&self.ls_whitebox, |sc| {
+            cleared = sc.penalties(penalty_id).is_empty();
+        });
+        cleared
+    }
+
+    // Accounts
+
+    pub fn check_egld_balance(&mut self, address_expr: &str, balance: &RustBigUint) {
+        self.world.check_state_step(
+            CheckStateStep::new().put_account(address_expr, CheckAccount::new().balance(balance.to_string().as_str())),
+        );
+    }
+
+    pub fn check_ls_token_balance(&mut self, address_expr: &str, balance: &RustBigUint) {
+        self.world.check_state_step(CheckStateStep::new().put_account(
+            address_expr,
+            CheckAccount::new().esdt_balance(LS_TOKEN_ID_EXPR, balance.to_string().as_str()),
+        ));
+    }
+}
+
+/// Shorthand for checking both the amounts tracked by a Delegation smart contract data
+pub fn assert_contract_amounts(
+    contract_data: &DelegationContractData<StaticApi>,
+    pending_to_delegate: &RustBigUint,
+    total_delegated: &RustBigUint,
+    pending_to_undelegate: &RustBigUint,
+    total_undelegated: &RustBigUint,
+    total_withdrawable: &RustBigUint,
+) {
+    assert_eq!(contract_data.pending_to_delegate, to_managed(pending_to_delegate));
+    assert_eq!(contract_data.total_delegated, to_managed(total_delegated));
+    assert_eq!(contract_data.pending_to_undelegate, to_managed(pending_to_undelegate));
+    assert_eq!(contract_data.total_undelegated, to_managed(total_undelegated));
+    assert_eq!(contract_data.total_withdrawable, to_managed(total_withdrawable));
+}
@@ -0,0 +1,105 @@
+mod setup;
+
+use liquid_staking::{errors::*, model::InstantUndelegateFeeRecipient};
+use multiversx_sc_scenario::scenario_model::TxExpect;
+use setup::*;
+
+/// Delegates 100 EGLD and compounds 10 EGLD of rewards, so that the exchange rate is above one
+fn setup_with_rewards() -> LiquidStakingSetup {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(100));
+    setup.delegate_pending_amount(0, None);
+    setup.add_rewards(0, &egld(10));
+    setup.claim_rewards_from(0);
+    setup.delegate_rewards(None);
+
+    setup
+}
+
+#[test]
+fn previews_match_actual_amounts() {
+    let mut setup = setup_with_rewards();
+
+    let expected_shares = setup.preview_delegate(&egld(10));
+    assert!(expected_shares < egld(10));
+    let payment = setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    assert_eq!(to_rust(&payment.amount), expected_shares);
+
+    let expected_egld = setup.preview_undelegate(&egld(5));
+    assert!(expected_egld > egld(5));
+    let payment = setup.undelegate(USER_ADDRESS_EXPR, &egld(5));
+    let attributes = setup.undelegate_attributes(payment.token_nonce);
+    assert_eq!(to_rust(&attributes.egld_amount), expected_egld);
+}
+
+#[test]
+fn delegate_with_min_shares_out() {
+    let mut setup = setup_with_rewards();
+
+    let expected_shares = setup.preview_delegate(&egld(10));
+    let too_many_shares = &expected_shares + 1u64;
+    setup.delegate_with_min_shares(
+        USER_ADDRESS_EXPR,
+        &egld(10),
+        &too_many_shares,
+        user_error(ERROR_SHARES_OUT_BELOW_MIN),
+    );
+    setup.delegate_with_min_shares(USER_ADDRESS_EXPR, &egld(10), &expected_shares, TxExpect::ok());
+
+    setup.check_ls_token_balance(USER_ADDRESS_EXPR, &(egld(100) + expected_shares));
+}
+
+#[test]
+fn undelegate_with_min_egld_out() {
+    let mut setup = setup_with_rewards();
+
+    let expected_egld = setup.preview_undelegate(&egld(5));
+    let too_much_egld = &expected_egld + 1u64;
+
+    // both using the delegation algorithm and a specific delegation contract
+    setup.undelegate_with_min_egld(
+        USER_ADDRESS_EXPR,
+        &egld(5),
+        None,
+        &too_much_egld,
+        user_error(ERROR_EGLD_OUT_BELOW_MIN),
+    );
+    setup.undelegate_with_min_egld(
+        USER_ADDRESS_EXPR,
+        &egld(5),
+        Some(0),
+        &too_much_egld,
+        user_error(ERROR_EGLD_OUT_BELOW_MIN),
+    );
+    setup.undelegate_with_min_egld(USER_ADDRESS_EXPR, &egld(5), None, &expected_egld, TxExpect::ok());
+
+    let attributes = setup.undelegate_attributes(1);
+    assert_eq!(to_rust(&attributes.egld_amount), expected_egld);
+    assert_eq!(attributes.delegation_contract, setup.delegation_contract(0).into());
+}
+
+#[test]
+fn instant_undelegate_with_min_egld_out() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.set_liquidity_buffer_target(5_000);
+    setup.set_instant_undelegate_fee(100, InstantUndelegateFeeRecipient::ProtocolReserve);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+
+    // the preview discounts the instant undelegate fee
+    let expected_egld = setup.preview_instant_undelegate(&egld(2));
+    assert_eq!(expected_egld, setup.preview_undelegate(&egld(2)) * 99u64 / 100u64);
+
+    setup.instant_undelegate_with_min_egld(
+        USER_ADDRESS_EXPR,
+        &egld(2),
+        &egld(2),
+        user_error(ERROR_EGLD_OUT_BELOW_MIN),
+    );
+    setup.instant_undelegate_with_min_egld(USER_ADDRESS_EXPR, &egld(2), &expected_egld, TxExpect::ok());
+
+    setup.check_egld_balance(USER_ADDRESS_EXPR, &(egld(INITIAL_BALANCE - 10) + expected_egld));
+    assert!(setup.invariant_violations().is_empty());
+}
@@ -0,0 +1,47 @@
+mod setup;
+
+use liquid_staking::{errors::*, model::UndelegationMode};
+use multiversx_sc::types::BigUint;
+use multiversx_sc_scenario::scenario_model::TxExpect;
+use setup::*;
+
+#[test]
+fn stale_contracts_do_not_receive_delegations() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    let stale_contract = setup.add_delegation_contract(100, 900, 1_000);
+    setup.set_max_contract_data_age(5);
+
+    setup.set_block_epoch(7);
+    setup.change_delegation_contract_params(0, 100, 800, 1_000);
+    assert_eq!(setup.stale_delegation_contracts(), vec![stale_contract]);
+
+    // the stale contract has the highest score but it is skipped
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    assert_eq!(setup.contract_data(0).pending_to_delegate, to_managed(&egld(10)));
+    assert_eq!(setup.contract_data(1).pending_to_delegate, BigUint::zero());
+
+    setup.change_delegation_contract_params(1, 100, 900, 1_000);
+    assert!(setup.stale_delegation_contracts().is_empty());
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    assert_eq!(setup.contract_data(1).pending_to_delegate, to_managed(&egld(10)));
+}
+
+#[test]
+fn updating_one_contract_does_not_delay_the_undelegation_algorithm_deactivation() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.add_delegation_contract(100, 900, 1_000);
+
+    setup.set_block_epoch(20);
+    setup.change_delegation_contract_params(0, 100, 800, 1_000);
+    setup.change_delegation_contract_params(1, 100, 900, 1_000);
+
+    setup.set_block_epoch(29);
+    setup.change_delegation_contract_params(0, 100, 800, 1_000);
+    setup.deactivate_undelegation_algorithm(user_error(ERROR_NOT_ENOUGH_ELAPSED_EPOCHS));
+
+    setup.set_block_epoch(30);
+    setup.deactivate_undelegation_algorithm(TxExpect::ok());
+    assert_eq!(setup.undelegation_mode(), UndelegationMode::Open);
+}
@@ -0,0 +1,411 @@
+mod setup;
+
+use liquid_staking::{
+    constants::MAX_TIMELOCK_DELAY,
+    errors::*,
+    governance::ProxyTrait as _,
+    model::{DataBoundsAction, DelegationScoreMethod, InstantUndelegateFeeRecipient, TimelockAction},
+};
+use multiversx_sc::{codec::top_encode_to_vec_u8_or_panic, types::BigUint};
+use multiversx_sc_scenario::{
+    api::StaticApi,
+    scenario_model::{AddressValue, ScCallStep, TxExpect},
+};
+use setup::*;
+
+const TIMELOCK_DELAY: u64 = 3;
+
+fn setup_with_timelock() -> LiquidStakingSetup {
+    let mut setup = LiquidStakingSetup::new();
+    setup.set_timelock_delay(TIMELOCK_DELAY, TxExpect::ok());
+    setup
+}
+
+fn encode_u64(value: u64) -> Vec<u8> {
+    RustBigUint::from(value).to_bytes_be()
+}
+
+#[test]
+fn timelocked_call_waits_for_the_delay() {
+    let mut setup = setup_with_timelock();
+
+    // the call cannot be performed directly anymore
+    let contract_call = setup.ls_contract.set_total_fee(BigUint::<StaticApi>::from(2_000u64));
+    setup.world.sc_call(
+        ScCallStep::new()
+            .from(ADMIN_ADDRESS_EXPR)
+            .call(contract_call)
+            .expect(user_error(ERROR_TIMELOCKED_CALL)),
+    );
+
+    let id = setup.queue_call(TimelockAction::SetTotalFee, &[encode_u64(2_000)]);
+    assert_eq!(setup.queued_timelock_call_ids(), vec![id]);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, user_error(ERROR_TIMELOCK_NOT_ELAPSED));
+
+    setup.set_block_epoch(1 + TIMELOCK_DELAY);
+    setup.execute_call(
+        USER_ADDRESS_EXPR,
+        id,
+        user_error(b"caller is missing the required role"),
+    );
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, TxExpect::ok());
+    assert_eq!(setup.total_fee(), RustBigUint::from(2_000u64));
+    assert!(setup.queued_timelock_call_ids().is_empty());
+
+    // calls can only be executed once
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, user_error(ERROR_UNEXPECTED_TIMELOCK_CALL_ID));
+}
+
+#[test]
+fn timelocked_call_with_optional_arguments() {
+    let mut setup = setup_with_timelock();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(100));
+    setup.delegate_pending_amount(0, None);
+    setup.add_rewards(0, &egld(10));
+    setup.claim_rewards_from(0);
+    let protocol_reserve = setup.protocol_reserve();
+
+    // the beneficiary is omitted, so the reserve goes to the admin
+    let id = setup.queue_call(TimelockAction::WithdrawReserve, &[protocol_reserve.to_bytes_be()]);
+    setup.set_block_epoch(1 + TIMELOCK_DELAY);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, TxExpect::ok());
+    assert_eq!(setup.protocol_reserve(), RustBigUint::default());
+
+    let contract = setup.delegation_contract(0);
+    let id = setup.queue_call(TimelockAction::BlacklistDelegationContract, &[contract.to_vec()]);
+    setup.set_block_epoch(1 + 2 * TIMELOCK_DELAY);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, TxExpect::ok());
+    assert!(setup.contract_data(0).blacklisted);
+}
+
+#[test]
+fn queue_call_validates_arguments() {
+    let mut setup = setup_with_timelock();
+
+    setup.queue_call_expect(
+        ADMIN_ADDRESS_EXPR,
+        TimelockAction::SetDelegationSamplingModelParams,
+        &[encode_u64(100), encode_u64(1_000)],
+        user_error(ERROR_INVALID_TIMELOCK_ARGUMENTS),
+    );
+    setup.queue_call_expect(
+        USER_ADDRESS_EXPR,
+        TimelockAction::SetTotalFee,
+        &[encode_u64(2_000)],
+        user_error(b"caller is missing the required role"),
+    );
+
+    // undecodable arguments are only detected at execution, so the call stays queued until cancelled
+    let id = setup.queue_call(TimelockAction::BlacklistDelegationContract, &[vec![1, 2, 3]]);
+    setup.set_block_epoch(1 + TIMELOCK_DELAY);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, user_error(ERROR_INVALID_TIMELOCK_ARGUMENTS));
+    assert_eq!(setup.queued_timelock_call_ids(), vec![id]);
+}
+
+#[test]
+fn guardian_cancels_timelocked_call() {
+    let mut setup = setup_with_timelock();
+
+    let id = setup.queue_call(TimelockAction::SetTotalFee, &[encode_u64(2_000)]);
+    setup.cancel_call(
+        USER_ADDRESS_EXPR,
+        id,
+        user_error(b"caller is missing the required role"),
+    );
+    setup.cancel_call(ADMIN_ADDRESS_EXPR, id, TxExpect::ok());
+    assert!(setup.queued_timelock_call_ids().is_empty());
+
+    setup.set_block_epoch(1 + TIMELOCK_DELAY);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, user_error(ERROR_UNEXPECTED_TIMELOCK_CALL_ID));
+    assert_eq!(setup.total_fee(), RustBigUint::from(TOTAL_FEE));
+}
+
+#[test]
+fn timelock_delay_decrease_is_timelocked() {
+    let mut setup = setup_with_timelock();
+
+    setup.set_timelock_delay(TIMELOCK_DELAY - 1, user_error(ERROR_TIMELOCKED_CALL));
+    setup.set_timelock_delay(TIMELOCK_DELAY + 1, TxExpect::ok());
+    setup.set_timelock_delay(MAX_TIMELOCK_DELAY + 1, user_error(ERROR_TIMELOCK_DELAY_TOO_LONG));
+
+    let id = setup.queue_call(TimelockAction::SetTimelockDelay, &[encode_u64(0)]);
+    setup.set_block_epoch(1 + TIMELOCK_DELAY + 1);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, TxExpect::ok());
+    assert_eq!(setup.timelock_delay(), 0);
+
+    // the calls can be performed directly again
+    let contract_call = setup.ls_contract.set_total_fee(BigUint::<StaticApi>::from(2_000u64));
+    setup
+        .world
+        .sc_call(ScCallStep::new().from(ADMIN_ADDRESS_EXPR).call(contract_call));
+    assert_eq!(setup.total_fee(), RustBigUint::from(2_000u64));
+}
+
+#[test]
+fn data_reporters_are_timelocked() {
+    let mut setup = setup_with_timelock();
+
+    let reporter = AddressValue::from(OTHER_USER_ADDRESS_EXPR).to_address();
+    let contract_call = setup.ls_contract.add_data_reporter(reporter.clone());
+    setup.world.sc_call(
+        ScCallStep::new()
+            .from(ADMIN_ADDRESS_EXPR)
+            .call(contract_call)
+            .expect(user_error(ERROR_TIMELOCKED_CALL)),
+    );
+
+    setup.queue_call_expect(
+        USER_ADDRESS_EXPR,
+        TimelockAction::AddDataReporter,
+        &[reporter.to_vec()],
+        user_error(b"caller is missing the required role"),
+    );
+    let add_id = setup.queue_call(TimelockAction::AddDataReporter, &[reporter.to_vec()]);
+    let quorum_id = setup.queue_call(TimelockAction::SetDataReportersQuorum, &[encode_u64(2)]);
+
+    setup.set_block_epoch(1 + TIMELOCK_DELAY);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, add_id, TxExpect::ok());
+
+    // a single data reporter has been registered
+    setup.execute_call(
+        ADMIN_ADDRESS_EXPR,
+        quorum_id,
+        user_error(ERROR_INVALID_DATA_REPORTERS_QUORUM),
+    );
+}
+
+#[test]
+fn data_bounds_and_quarantine_releases_are_timelocked() {
+    let mut setup = setup_with_timelock();
+    let delegation_contract = setup.add_delegation_contract(100, 800, 1_000);
+
+    let arguments = [
+        encode_u64(500),
+        encode_u64(2_000),
+        encode_u64(1_500),
+        encode_u64(10),
+        top_encode_to_vec_u8_or_panic(&DataBoundsAction::Quarantine),
+    ];
+    setup.queue_call_expect(
+        ADMIN_ADDRESS_EXPR,
+        TimelockAction::SetDataBounds,
+        &arguments[..4],
+        user_error(ERROR_INVALID_TIMELOCK_ARGUMENTS),
+    );
+    let id = setup.queue_call(TimelockAction::SetDataBounds, &arguments);
+
+    setup.set_block_epoch(1 + TIMELOCK_DELAY);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, TxExpect::ok());
+
+    let bounds = setup.data_bounds();
+    assert_eq!(bounds.max_tvl_change, 500);
+    assert_eq!(bounds.max_apr, BigUint::from(2_000u64));
+    assert_eq!(bounds.max_service_fee, BigUint::from(1_500u64));
+    assert_eq!(bounds.max_nr_nodes_change, 10);
+    assert_eq!(bounds.action, DataBoundsAction::Quarantine);
+
+    setup.change_delegation_contract_params(0, 200, 800, 1_000);
+    assert!(setup.contract_data(0).outdated);
+
+    setup.release_quarantine(0, user_error(ERROR_TIMELOCKED_CALL));
+    let id = setup.queue_call(TimelockAction::ReleaseQuarantine, &[delegation_contract.to_vec()]);
+    setup.set_block_epoch(1 + 2 * TIMELOCK_DELAY);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, TxExpect::ok());
+    assert_eq!(setup.contract_data(0).total_value_locked, to_managed(&egld(200)));
+}
+
+#[test]
+fn max_contract_data_age_is_timelocked() {
+    let mut setup = setup_with_timelock();
+    let delegation_contract = setup.add_delegation_contract(100, 800, 1_000);
+
+    let contract_call = setup.ls_contract.set_max_contract_data_age(2u64);
+    setup.world.sc_call(
+        ScCallStep::new()
+            .from(ADMIN_ADDRESS_EXPR)
+            .call(contract_call)
+            .expect(user_error(ERROR_TIMELOCKED_CALL)),
+    );
+
+    let id = setup.queue_call(TimelockAction::SetMaxContractDataAge, &[encode_u64(2)]);
+    setup.set_block_epoch(1 + TIMELOCK_DELAY);
+    assert!(setup.stale_delegation_contracts().is_empty());
+
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, TxExpect::ok());
+    assert_eq!(setup.stale_delegation_contracts(), vec![delegation_contract]);
+}
+
+#[test]
+fn operators_and_concentration_limits_are_timelocked() {
+    let mut setup = setup_with_timelock();
+    let delegation_contract = setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.set_operator(0, 7, user_error(ERROR_TIMELOCKED_CALL));
+    setup.queue_call_expect(
+        USER_ADDRESS_EXPR,
+        TimelockAction::SetOperator,
+        &[delegation_contract.to_vec(), encode_u64(7)],
+        user_error(b"caller is missing the required role"),
+    );
+    let operator_id = setup.queue_call(
+        TimelockAction::SetOperator,
+        &[delegation_contract.to_vec(), encode_u64(7)],
+    );
+    let limits_id = setup.queue_call(
+        TimelockAction::SetConcentrationLimits,
+        &[encode_u64(5_000), encode_u64(6_000)],
+    );
+
+    setup.set_block_epoch(1 + TIMELOCK_DELAY);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, operator_id, TxExpect::ok());
+    setup.execute_call(ADMIN_ADDRESS_EXPR, limits_id, TxExpect::ok());
+    assert!(setup.has_operator_data(7));
+    assert_eq!(setup.concentration_limits().max_operator_share, 6_000);
+
+    setup.remove_operator(0, user_error(ERROR_TIMELOCKED_CALL));
+    let id = setup.queue_call(TimelockAction::RemoveOperator, &[delegation_contract.to_vec()]);
+    setup.set_block_epoch(1 + 2 * TIMELOCK_DELAY);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, TxExpect::ok());
+    assert!(!setup.has_operator_data(7));
+}
+
+#[test]
+fn score_model_params_keep_their_arguments() {
+    let mut setup = setup_with_timelock();
+
+    let mut arguments = vec![
+        top_encode_to_vec_u8_or_panic(&DelegationScoreMethod::Mixed),
+        egld(0).to_bytes_be(),
+        egld(1_000_000).to_bytes_be(),
+        encode_u64(0),
+        encode_u64(2_000),
+        top_encode_to_vec_u8_or_panic(&true),
+    ];
+    setup.queue_call_expect(
+        ADMIN_ADDRESS_EXPR,
+        TimelockAction::SetDelegationScoreModelParams,
+        &arguments[..5],
+        user_error(ERROR_INVALID_TIMELOCK_ARGUMENTS),
+    );
+    arguments.push(encode_u64(5_000));
+    let id = setup.queue_call(TimelockAction::SetDelegationScoreModelParams, &arguments);
+
+    setup.set_block_epoch(1 + TIMELOCK_DELAY);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, TxExpect::ok());
+    assert_eq!(setup.delegation_score_model().method, DelegationScoreMethod::Mixed);
+}
+
+#[test]
+fn tvl_per_node_domain_is_timelocked() {
+    let mut setup = setup_with_timelock();
+
+    setup.set_tvl_per_node_domain(0, 1_000, user_error(ERROR_TIMELOCKED_CALL));
+    let domain_id = setup.queue_call(
+        TimelockAction::SetTvlPerNodeDomain,
+        &[
+            egld(0).to_bytes_be(),
+            egld(1_000).to_bytes_be(),
+            top_encode_to_vec_u8_or_panic(&true),
+        ],
+    );
+    let model_id = setup.queue_call(
+        TimelockAction::SetDelegationScoreModelParams,
+        &[
+            top_encode_to_vec_u8_or_panic(&DelegationScoreMethod::TvlPerNode),
+            egld(0).to_bytes_be(),
+            egld(0).to_bytes_be(),
+            encode_u64(0),
+            encode_u64(0),
+            top_encode_to_vec_u8_or_panic(&true),
+        ],
+    );
+
+    // the TvlPerNode method requires its domain to be set beforehand
+    setup.set_block_epoch(1 + TIMELOCK_DELAY);
+    setup.execute_call(
+        ADMIN_ADDRESS_EXPR,
+        model_id,
+        user_error(ERROR_MISSING_TVL_PER_NODE_DOMAIN),
+    );
+    setup.execute_call(ADMIN_ADDRESS_EXPR, domain_id, TxExpect::ok());
+    setup.execute_call(ADMIN_ADDRESS_EXPR, model_id, TxExpect::ok());
+    assert_eq!(setup.delegation_score_model().method, DelegationScoreMethod::TvlPerNode);
+}
+
+#[test]
+fn fees_buffer_and_vesting_are_timelocked() {
+    let mut setup = setup_with_timelock();
+
+    for contract_call in [
+        setup
+            .ls_contract
+            .set_liquidity_buffer_target(BigUint::<StaticApi>::from(1_000u64)),
+        setup.ls_contract.set_rewards_vesting_period(100u64),
+        setup.ls_contract.set_instant_undelegate_fee(
+            BigUint::<StaticApi>::from(10_000u64),
+            InstantUndelegateFeeRecipient::ProtocolReserve,
+        ),
+    ] {
+        setup.world.sc_call(
+            ScCallStep::new()
+                .from(ADMIN_ADDRESS_EXPR)
+                .call(contract_call)
+                .expect(user_error(ERROR_TIMELOCKED_CALL)),
+        );
+    }
+
+    let buffer_id = setup.queue_call(TimelockAction::SetLiquidityBufferTarget, &[encode_u64(1_000)]);
+    let vesting_id = setup.queue_call(TimelockAction::SetRewardsVestingPeriod, &[encode_u64(100)]);
+    let fee_id = setup.queue_call(
+        TimelockAction::SetInstantUndelegateFee,
+        &[
+            encode_u64(10_000),
+            top_encode_to_vec_u8_or_panic(&InstantUndelegateFeeRecipient::ProtocolReserve),
+        ],
+    );
+
+    setup.set_block_epoch(1 + TIMELOCK_DELAY);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, buffer_id, TxExpect::ok());
+    setup.execute_call(ADMIN_ADDRESS_EXPR, vesting_id, TxExpect::ok());
+    setup.execute_call(ADMIN_ADDRESS_EXPR, fee_id, TxExpect::ok());
+    assert_eq!(setup.liquidity_buffer_target(), RustBigUint::from(1_000u64));
+    assert_eq!(setup.rewards_vesting_period(), 100);
+    assert_eq!(setup.instant_undelegate_fee(), RustBigUint::from(10_000u64));
+    assert_eq!(
+        setup.instant_undelegate_fee_recipient(),
+        InstantUndelegateFeeRecipient::ProtocolReserve
+    );
+}
+
+#[test]
+fn clearing_the_sampling_model_is_timelocked() {
+    let mut setup = setup_with_timelock();
+
+    let id = setup.queue_call(
+        TimelockAction::SetDelegationSamplingModelParams,
+        &[encode_u64(500), encode_u64(1_500), encode_u64(100)],
+    );
+    setup.set_block_epoch(1 + TIMELOCK_DELAY);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, TxExpect::ok());
+    assert!(setup.has_delegation_sampling_model());
+
+    let contract_call = setup.ls_contract.clear_delegation_sampling_model();
+    setup.world.sc_call(
+        ScCallStep::new()
+            .from(ADMIN_ADDRESS_EXPR)
+            .call(contract_call)
+            .expect(user_error(ERROR_TIMELOCKED_CALL)),
+    );
+    setup.queue_call_expect(
+        ADMIN_ADDRESS_EXPR,
+        TimelockAction::ClearDelegationSamplingModel,
+        &[encode_u64(0)],
+        user_error(ERROR_INVALID_TIMELOCK_ARGUMENTS),
+    );
+    let id = setup.queue_call(TimelockAction::ClearDelegationSamplingModel, &[]);
+
+    setup.set_block_epoch(1 + 2 * TIMELOCK_DELAY);
+    setup.execute_call(ADMIN_ADDRESS_EXPR, id, TxExpect::ok());
+    assert!(!setup.has_delegation_sampling_model());
+}
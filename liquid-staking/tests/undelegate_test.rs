@@ -0,0 +1,327 @@
+mod setup;
+
+use delegation_mock::MockEndpoint;
+use liquid_staking::{errors::*, model::UndelegationMode};
+use multiversx_sc_scenario::scenario_model::TxExpect;
+use setup::*;
+
+#[test]
+fn undelegate_mints_undelegate_nft() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+
+    let payment = setup.undelegate(USER_ADDRESS_EXPR, &egld(4));
+    assert_eq!(payment.token_nonce, 1);
+
+    let attributes = setup.undelegate_attributes(1);
+    assert_eq!(attributes.delegation_contract, setup.delegation_contract(0).into());
+    assert_eq!(attributes.egld_amount, to_managed(&egld(4)));
+    assert_eq!(attributes.shares, to_managed(&egld(4)));
+    assert_eq!(attributes.undelegate_epoch, 1);
+    assert_eq!(attributes.unbond_epoch, 1 + UNBOND_PERIOD);
+
+    assert_eq!(setup.cash_reserve(), egld(6));
+    assert_eq!(setup.ls_token_supply(), egld(6));
+    setup.check_ls_token_balance(USER_ADDRESS_EXPR, &egld(6));
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &egld(6), &egld(4), &zero, &zero);
+}
+
+#[test]
+fn undelegate_requires_minimum_amount() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+
+    let shares = egld(1) - RustBigUint::from(1u64);
+    setup.undelegate_expect_err(USER_ADDRESS_EXPR, &shares, ERROR_INSUFFICIENT_EGLD_AMOUNT);
+}
+
+#[test]
+fn undelegate_requires_delegated_amount() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    // nothing has been delegated to the staking provider yet
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.undelegate_expect_err(USER_ADDRESS_EXPR, &egld(5), ERROR_DELEGATION_CONTRACT_NOT_AVAILABLE);
+
+    // the remaining amount would be dust
+    setup.delegate_pending_amount(0, None);
+    let shares = egld(10) - RustBigUint::from(1u64);
+    setup.undelegate_expect_err(USER_ADDRESS_EXPR, &shares, ERROR_DELEGATION_CONTRACT_NOT_AVAILABLE);
+}
+
+#[test]
+fn undelegate_selects_the_lowest_score() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.add_delegation_contract(500, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+    setup.set_delegated_amount(1, &egld(10));
+
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(3));
+
+    assert_eq!(setup.contract_data(0).pending_to_undelegate, to_managed(&egld(0)));
+    assert_eq!(setup.contract_data(1).pending_to_undelegate, to_managed(&egld(3)));
+}
+
+#[test]
+fn undelegate_from_given_contract_requires_open_mode() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+
+    setup.undelegate_from(
+        USER_ADDRESS_EXPR,
+        &egld(4),
+        0,
+        user_error(ERROR_ONLY_FREE_UNDELEGATION_MODE),
+    );
+
+    setup.set_undelegation_mode(UndelegationMode::Open);
+    setup.undelegate_from(USER_ADDRESS_EXPR, &egld(4), 0, TxExpect::ok());
+
+    assert_eq!(setup.contract_data(0).pending_to_undelegate, to_managed(&egld(4)));
+}
+
+#[test]
+fn undelegate_pending_amount_success() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(4));
+
+    setup.set_block_epoch(30);
+    setup.undelegate_pending_amount(0);
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &egld(6), &zero, &egld(4), &zero);
+    assert!(!contract_data.outdated);
+    assert_eq!(setup.total_undelegated(), egld(4));
+    assert_eq!(setup.delegated_at(0), egld(6));
+    assert_eq!(setup.last_undelegate_epoch(), 30);
+}
+
+#[test]
+fn undelegate_pending_amount_without_pending_amount() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.undelegate_pending_amount_expect(0, user_error(ERROR_NO_PENDING_TO_UNDELEGATE));
+}
+
+#[test]
+fn undelegate_pending_amount_failure_reverts_pending_amount() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(4));
+
+    setup.set_failure(0, MockEndpoint::Undelegate, true);
+    setup.undelegate_pending_amount(0);
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &egld(6), &egld(4), &zero, &zero);
+    assert!(contract_data.outdated);
+    assert_eq!(setup.total_undelegated(), zero);
+    assert_eq!(setup.delegated_at(0), egld(10));
+
+    setup.set_failure(0, MockEndpoint::Undelegate, false);
+    setup.undelegate_pending_amount(0);
+
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &egld(6), &zero, &egld(4), &zero);
+}
+
+#[test]
+fn split_undelegate_nft_success() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+    setup.add_rewards(0, &egld(2));
+    setup.claim_rewards_from(0);
+    setup.delegate_rewards(None);
+
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(6));
+    let attributes = setup.undelegate_attributes(1);
+    let egld_amount = to_rust(&attributes.egld_amount);
+    let shares = to_rust(&attributes.shares);
+
+    let first_amount = egld(1);
+    let second_amount = egld(2) + 1u64;
+    let third_amount = &egld_amount - &first_amount - &second_amount;
+    let amounts = [first_amount, second_amount, third_amount];
+    setup.split_undelegate_nft(USER_ADDRESS_EXPR, 1, &amounts, TxExpect::ok());
+
+    // shares are proportional, while the last NFT takes the rounding leftovers
+    let mut total_shares = RustBigUint::default();
+    for (index, amount) in amounts.iter().enumerate() {
+        let new_attributes = setup.undelegate_attributes(2 + index as u64);
+        assert_eq!(&to_rust(&new_attributes.egld_amount), amount);
+        assert_eq!(new_attributes.delegation_contract, attributes.delegation_contract);
+        assert_eq!(new_attributes.unbond_epoch, attributes.unbond_epoch);
+        if index < 2 {
+            assert_eq!(to_rust(&new_attributes.shares), &shares * amount / &egld_amount);
+        }
+        total_shares += to_rust(&new_attributes.shares);
+    }
+    assert_eq!(total_shares, shares);
+
+    // the accounting is unchanged, so the new NFTs can be withdrawn as usual
+    setup.undelegate_pending_amount(0);
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.withdraw_from(0);
+    assert_eq!(setup.withdraw_many(USER_ADDRESS_EXPR, &[2, 3, 4]), egld_amount);
+
+    // the original NFT has been burned
+    let burned = TxExpect::err(10, "str:insufficient funds");
+    setup.split_undelegate_nft(USER_ADDRESS_EXPR, 1, &amounts, burned);
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn split_undelegate_nft_requires_valid_amounts() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(4));
+
+    let err = user_error(ERROR_INVALID_SPLIT_AMOUNTS);
+    setup.split_undelegate_nft(USER_ADDRESS_EXPR, 1, &[egld(4)], err.clone());
+    setup.split_undelegate_nft(USER_ADDRESS_EXPR, 1, &[egld(4), RustBigUint::default()], err.clone());
+    setup.split_undelegate_nft(USER_ADDRESS_EXPR, 1, &[egld(3), egld(2)], err.clone());
+    setup.split_undelegate_nft(USER_ADDRESS_EXPR, 1, &[egld(1), egld(2)], err);
+}
+
+#[test]
+fn merge_undelegate_nfts_success() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(1));
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(2));
+    setup.set_block_epoch(2);
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(3));
+
+    // different unbond epochs
+    setup.merge_undelegate_nfts(USER_ADDRESS_EXPR, &[1, 3], user_error(ERROR_NFTS_CANNOT_BE_MERGED));
+    setup.merge_undelegate_nfts(USER_ADDRESS_EXPR, &[1], user_error(ERROR_NOT_ENOUGH_NFTS_TO_MERGE));
+
+    setup.merge_undelegate_nfts(USER_ADDRESS_EXPR, &[1, 2], TxExpect::ok());
+
+    let attributes = setup.undelegate_attributes(4);
+    assert_eq!(to_rust(&attributes.egld_amount), egld(3));
+    assert_eq!(to_rust(&attributes.shares), egld(3));
+    assert_eq!(attributes.undelegate_epoch, 1);
+    assert_eq!(attributes.unbond_epoch, 1 + UNBOND_PERIOD);
+
+    // the merged NFTs have been burned
+    let burned = TxExpect::err(10, "str:insufficient funds");
+    setup.merge_undelegate_nfts(USER_ADDRESS_EXPR, &[1, 2], burned);
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn cancel_undelegate_success() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(4));
+    setup.cancel_undelegate(USER_ADDRESS_EXPR, 1, TxExpect::ok());
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &egld(10), &zero, &zero, &zero);
+    assert_eq!(setup.cash_reserve(), egld(10));
+    assert_eq!(setup.ls_token_supply(), egld(10));
+    setup.check_ls_token_balance(USER_ADDRESS_EXPR, &egld(10));
+    setup.cancel_undelegate(USER_ADDRESS_EXPR, 1, TxExpect::err(10, "str:insufficient funds"));
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn cancel_undelegate_after_undelegate_pending_amount() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+
+    // the amount is back as pending after the failure, but it could have been undelegated
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(4));
+    setup.set_failure(0, MockEndpoint::Undelegate, true);
+    setup.undelegate_pending_amount(0);
+    assert_eq!(to_rust(&setup.contract_data(0).pending_to_undelegate), egld(4));
+    setup.cancel_undelegate(USER_ADDRESS_EXPR, 1, user_error(ERROR_UNDELEGATION_ALREADY_SENT));
+    setup.change_delegation_contract_params(0, 100, 800, 1_000);
+
+    // later undelegations can still be cancelled, also after being split
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(2));
+    setup.split_undelegate_nft(USER_ADDRESS_EXPR, 2, &[egld(1), egld(1)], TxExpect::ok());
+    setup.merge_undelegate_nfts(USER_ADDRESS_EXPR, &[1, 3], TxExpect::ok());
+    setup.cancel_undelegate(USER_ADDRESS_EXPR, 4, TxExpect::ok());
+    setup.cancel_undelegate(USER_ADDRESS_EXPR, 5, user_error(ERROR_UNDELEGATION_ALREADY_SENT));
+
+    setup.set_failure(0, MockEndpoint::Undelegate, false);
+    setup.undelegate_pending_amount(0);
+    assert_eq!(to_rust(&setup.contract_data(0).total_undelegated), egld(5));
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn undelegate_all_pending_isolates_failures() {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.add_delegation_contract(50, 800, 1_000);
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_all_pending(TxExpect::ok());
+
+    setup.set_undelegation_mode(UndelegationMode::Open);
+    setup.undelegate_from(USER_ADDRESS_EXPR, &egld(4), 0, TxExpect::ok());
+    setup.undelegate_from(USER_ADDRESS_EXPR, &egld(3), 1, TxExpect::ok());
+
+    setup.set_failure(1, MockEndpoint::Undelegate, true);
+    setup.undelegate_all_pending(TxExpect::ok());
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &egld(6), &zero, &egld(4), &zero);
+    assert!(!contract_data.outdated);
+
+    let contract_data = setup.contract_data(1);
+    assert_contract_amounts(&contract_data, &zero, &egld(7), &egld(3), &zero, &zero);
+    assert!(contract_data.outdated);
+
+    assert_eq!(setup.total_undelegated(), egld(4));
+    assert!(setup.invariant_violations().is_empty());
+
+    // only the failed undelegation is left
+    setup.set_failure(1, MockEndpoint::Undelegate, false);
+    setup.undelegate_all_pending(TxExpect::ok());
+    assert_eq!(setup.total_undelegated(), egld(7));
+    setup.undelegate_all_pending(user_error(ERROR_NO_PENDING_TO_UNDELEGATE));
+}
@@ -0,0 +1,134 @@
+mod setup;
+
+use delegation_mock::MockEndpoint;
+use liquid_staking::errors::*;
+use setup::*;
+
+/// Delegates 10 EGLD, undelegates 4 EGLD and performs the undelegation at the staking provider
+fn setup_with_undelegation() -> LiquidStakingSetup {
+    let mut setup = LiquidStakingSetup::new();
+    setup.add_delegation_contract(100, 800, 1_000);
+
+    setup.delegate(USER_ADDRESS_EXPR, &egld(10));
+    setup.delegate_pending_amount(0, None);
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(4));
+    setup.undelegate_pending_amount(0);
+
+    setup
+}
+
+#[test]
+fn withdraw_from_success() {
+    let mut setup = setup_with_undelegation();
+
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.withdraw_from(0);
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &egld(6), &zero, &zero, &egld(4));
+    assert_eq!(setup.total_undelegated(), zero);
+    assert_eq!(setup.total_withdrawable(), egld(4));
+    setup.check_egld_balance(LIQUID_STAKING_ADDRESS_EXPR, &egld(4));
+}
+
+#[test]
+fn withdraw_from_failure() {
+    let mut setup = setup_with_undelegation();
+
+    // the unbond period has not passed yet, so the staking provider fails
+    setup.withdraw_from(0);
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &egld(6), &zero, &egld(4), &zero);
+    assert!(!contract_data.outdated);
+
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.set_failure(0, MockEndpoint::Withdraw, true);
+    setup.withdraw_from(0);
+
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &egld(6), &zero, &egld(4), &zero);
+    assert_eq!(setup.total_withdrawable(), zero);
+}
+
+#[test]
+fn withdraw_success() {
+    let mut setup = setup_with_undelegation();
+
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.withdraw_from(0);
+
+    let egld_amount = setup.withdraw(USER_ADDRESS_EXPR, 1);
+    assert_eq!(egld_amount, egld(4));
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &egld(6), &zero, &zero, &zero);
+    assert_eq!(setup.total_withdrawable(), zero);
+    setup.check_egld_balance(USER_ADDRESS_EXPR, &egld(INITIAL_BALANCE - 6));
+    setup.check_egld_balance(LIQUID_STAKING_ADDRESS_EXPR, &zero);
+}
+
+#[test]
+fn withdraw_before_unbond_period() {
+    let mut setup = setup_with_undelegation();
+
+    setup.withdraw_expect_err(USER_ADDRESS_EXPR, 1, ERROR_UNBOND_PERIOD_NOT_ENDED);
+}
+
+#[test]
+fn withdraw_before_withdraw_from() {
+    let mut setup = setup_with_undelegation();
+
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.withdraw_expect_err(USER_ADDRESS_EXPR, 1, ERROR_TOO_MUCH_EGLD_AMOUNT);
+}
+
+#[test]
+fn withdraw_requires_undelegate_nft() {
+    let mut setup = setup_with_undelegation();
+
+    setup.withdraw_with_ls_token_expect_err(USER_ADDRESS_EXPR, &egld(1), ERROR_UNEXPECTED_PAYMENT);
+}
+
+#[test]
+fn withdraw_many_success() {
+    let mut setup = setup_with_undelegation();
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(1));
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(2));
+    setup.undelegate_pending_amount(0);
+
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.withdraw_from(0);
+
+    // a single aggregated transfer for the three undelegate NFTs
+    let egld_amount = setup.withdraw_many(USER_ADDRESS_EXPR, &[1, 2, 3]);
+    assert_eq!(egld_amount, egld(7));
+
+    let zero = RustBigUint::default();
+    let contract_data = setup.contract_data(0);
+    assert_contract_amounts(&contract_data, &zero, &egld(3), &zero, &zero, &zero);
+    assert_eq!(setup.total_withdrawable(), zero);
+    setup.check_egld_balance(USER_ADDRESS_EXPR, &egld(INITIAL_BALANCE - 3));
+    assert!(setup.invariant_violations().is_empty());
+}
+
+#[test]
+fn withdraw_many_fails_if_any_nft_is_not_withdrawable() {
+    let mut setup = setup_with_undelegation();
+    setup.set_block_epoch(1 + UNBOND_PERIOD);
+    setup.undelegate(USER_ADDRESS_EXPR, &egld(1));
+    setup.undelegate_pending_amount(0);
+    setup.withdraw_from(0);
+
+    // the second undelegation has not reached its unbond epoch yet
+    setup.withdraw_many_expect_err(USER_ADDRESS_EXPR, &[1, 2], ERROR_UNBOND_PERIOD_NOT_ENDED);
+
+    setup.set_block_epoch(1 + 2 * UNBOND_PERIOD);
+    setup.withdraw_many_expect_err(USER_ADDRESS_EXPR, &[1, 2], ERROR_TOO_MUCH_EGLD_AMOUNT);
+
+    setup.withdraw_from(0);
+    assert_eq!(setup.withdraw_many(USER_ADDRESS_EXPR, &[2, 1]), egld(5));
+}
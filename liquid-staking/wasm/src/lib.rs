@@ -5,9 +5,10 @@
 ////////////////////////////////////////////////////
 
 // Init:                                 1
-// Endpoints:                           67
+// Endpoints:                          158
 // Async Callback:                       1
-// Total number of exported functions:  69
+// Promise callbacks:                    6
+// Total number of exported functions: 166
 
 #![no_std]
 #![allow(internal_features)]
@@ -25,27 +26,66 @@ multiversx_sc_wasm_adapter::endpoints! {
         getPendingAdmin => get_pending_admin
         setPendingAdmin => set_pending_admin
         acceptAdmin => accept_admin
+        grantRole => grant_role
+        revokeRole => revoke_role
+        hasRole => has_role
+        getRoleMembers => get_role_members
+        instantUnDelegate => instant_undelegate
+        refillLiquidityBuffer => refill_liquidity_buffer
+        withdrawBufferRefill => withdraw_buffer_refill
+        previewInstantUnDelegate => preview_instant_undelegate
+        getLiquidityBufferTargetAmount => get_liquidity_buffer_target_amount
+        getLiquidityBufferDeficit => get_liquidity_buffer_deficit
         isLiquidStaking => is_liquid_staking
-        isActive => is_active
+        isPaused => is_paused
+        getPauseStatus => get_pause_status
+        getStaleDelegationContracts => get_stale_delegation_contracts
+        getAllocation => get_allocation
+        getOperatorAllocation => get_operator_allocation
+        getConcentration => get_concentration
+        getOperatorConcentration => get_operator_concentration
         getLsTokenId => get_ls_token_id
         getExchangeRate => get_exchange_rate
+        getUnvestedRewards => get_unvested_rewards
+        previewDelegate => preview_delegate
+        previewUnDelegate => preview_undelegate
         delegate => delegate
         delegatePendingAmount => delegate_pending_amount
+        delegateAllPending => delegate_all_pending
         registerLsToken => register_ls_token
         setLsTokenRoles => set_ls_token_roles
         registerUndelegateToken => register_undelegate_token
         setUndelegateTokenRoles => set_undelegate_token_roles
         setDataManager => set_data_manager
-        setStateActive => set_state_active
-        setStateInactive => set_state_inactive
+        addDataReporter => add_data_reporter
+        removeDataReporter => remove_data_reporter
+        setDataReportersQuorum => set_data_reporters_quorum
+        setDataBounds => set_data_bounds
+        setMaxContractDataAge => set_max_contract_data_age
+        setConcentrationLimits => set_concentration_limits
+        setOperator => set_operator
+        removeOperator => remove_operator
+        setMaxDataReportDeviation => set_max_data_report_deviation
+        unpause => unpause
+        pause => pause
         whitelistDelegationContract => whitelist_delegation_contract
         blacklistDelegationContract => blacklist_delegation_contract
         changeDelegationContractParams => change_delegation_contract_params
+        releaseQuarantine => release_quarantine
         withdrawReserve => withdraw_reserve
         setTotalFee => set_total_fee
+        setLiquidityBufferTarget => set_liquidity_buffer_target
+        setRewardsVestingPeriod => set_rewards_vesting_period
+        setInstantUndelegateFee => set_instant_undelegate_fee
         setDelegationScoreModelParams => set_delegation_score_model_params
+        setDelegationScoreCurve => set_delegation_score_curve
+        setDelegationScoreTerms => set_delegation_score_terms
+        setTvlPerNodeDomain => set_tvl_per_node_domain
+        setRebalanceParams => set_rebalance_params
         setDelegationSamplingModelParams => set_delegation_sampling_model_params
         clearDelegationSamplingModel => clear_delegation_sampling_model
+        setTimelockDelay => set_timelock_delay
+        sortDelegationContractsList => sort_delegation_contracts_list_endpoint
         deactivateUndelegationAlgorithm => deactivate_undelegation_algorithm
         reactivateUndelegationAlgorithm => reactivate_undelegation_algorithm
         addToMigrationWhitelist => add_to_migration_whitelist
@@ -53,18 +93,24 @@ multiversx_sc_wasm_adapter::endpoints! {
         removeMeFromMigrationWhitelist => remove_me_from_migration_whitelist
         claimRewardsFrom => claim_rewards_from
         delegateRewards => delegate_rewards
-        getState => state
+        claimAllAndDelegateRewards => claim_all_and_delegate_rewards
+        getPausedOperations => paused_operations
         getLsSupply => ls_token_supply
         getUndelegateTokenId => undelegate_token
         getUndelegateTokenName => undelegate_token_name
         getCashReserve => cash_reserve
         getRewardsReserve => rewards_reserve
+        getRewardsVestingPeriod => rewards_vesting_period
+        getRewardsStream => rewards_stream
         getProtocolReserves => protocol_reserve
         getTotalUndelegated => total_undelegated
         getTotalWithdrawable => total_withdrawable
         getPenaltyById => penalties
         getNextPenaltyId => next_penalty_id
         getDelegationContractsList => delegation_contracts_list
+        getListPosition => list_position
+        getSortCursor => sort_cursor
+        getTotalDelegationScore => total_delegation_score
         getMigrationWhitelist => migration_whitelist
         getNumWhitelistedUsers => num_whitelisted_users
         getBlacklistedDelegationContracts => blacklisted_delegation_contracts
@@ -72,21 +118,73 @@ multiversx_sc_wasm_adapter::endpoints! {
         getUndelegationMode => undelegation_mode
         getLastUndelegateEpoch => last_undelegate_epoch
         getLastContractDataUpdateEpoch => last_contract_data_update_epoch
+        getContractDataUpdateEpoch => contract_data_update_epoch
+        getMaxContractDataAge => max_contract_data_age
         getLastClaimRewardsEpoch => last_rewards_claim_epoch
         getUnbondPeriod => unbond_period
         getTotalFee => total_fee
         getDelegationScoreModel => delegation_score_model
+        getDelegationScoreTerms => delegation_score_terms
+        getTvlPerNodeDomain => tvl_per_node_domain
         getDelegationSamplingModel => delegation_sampling_model
         getDataManager => data_manager
+        getDataReporters => data_reporters
+        getDataReportersQuorum => data_reporters_quorum
+        getMaxDataReportDeviation => max_data_report_deviation
+        getDataBounds => data_bounds
+        getQuarantinedData => quarantined_data
         getRandomOracle => random_oracle
+        getLiquidityBuffer => liquidity_buffer
+        getLiquidityBufferTarget => liquidity_buffer_target
+        getInstantUndelegateFee => instant_undelegate_fee
+        getInstantUndelegateFeeRecipient => instant_undelegate_fee_recipient
+        getBufferRefillById => buffer_refills
+        getNextBufferRefillId => next_buffer_refill_id
+        getPendingBufferRefill => pending_buffer_refill
+        getConcentrationLimits => concentration_limits
+        getOperatorId => operator_id
+        getOperatorContracts => operator_contracts
+        getOperatorData => operator_data
+        getRebalanceById => rebalances
+        getRebalanceParams => rebalance_params
+        getLastRebalanceEpoch => last_rebalance_epoch
+        getNextRebalanceId => next_rebalance_id
+        getPendingRebalance => pending_rebalance
+        getIncomingRebalance => incoming_rebalance
+        getTimelockDelay => timelock_delay
+        getTimelockCall => timelock_calls
+        getQueuedTimelockCallIds => queued_timelock_call_ids
+        getNextTimelockCallId => next_timelock_call_id
+        getUndelegationBatch => undelegation_batch
+        getUndelegateNftBatch => undelegate_nft_batch
+        queueCall => queue_call
+        executeCall => execute_call
+        cancelCall => cancel_call
         unDelegate => undelegate
+        splitUndelegateNft => split_undelegate_nft
+        mergeUndelegateNfts => merge_undelegate_nfts
+        cancelUnDelegate => cancel_undelegate
         unDelegatePendingAmount => undelegate_pending_amount
+        unDelegateAllPending => undelegate_all_pending
         withdraw => withdraw
         withdrawFrom => withdraw_from
         penalize => penalize
         withdrawPenalty => withdraw_penalty
         delegatePenalty => delegate_penalty
         withdrawFromPenalty => withdraw_from_penalty
+        rebalance => rebalance
+        completeRebalance => complete_rebalance
+        getMaxRebalanceAmount => get_max_rebalance_amount
+        getTargetAllocation => get_target_allocation
+        refreshDelegationContractData => refresh_delegation_contract_data
+        submitDelegationContractData => submit_delegation_contract_data
+        getDataReports => get_data_reports
+        delegate_all_pending_cb => delegate_all_pending_cb
+        claim_all_rewards_cb => claim_all_rewards_cb
+        claim_all_delegate_rewards_cb => claim_all_delegate_rewards_cb
+        undelegate_all_pending_cb => undelegate_all_pending_cb
+        refresh_contract_config_cb => refresh_contract_config_cb
+        refresh_total_active_stake_cb => refresh_total_active_stake_cb
     )
 }
 
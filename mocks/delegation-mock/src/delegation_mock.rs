@@ -0,0 +1,215 @@
+#![no_std]
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+/// The Delegation smart contract endpoints that can be forced to fail.
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Copy, Clone, Debug)]
+pub enum MockEndpoint {
+    Delegate,
+    Undelegate,
+    Withdraw,
+    ClaimRewards,
+    ContractConfig,
+}
+
+#[derive(TypeAbi, TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Eq, Clone, Debug)]
+pub struct Undelegation<M: ManagedTypeApi> {
+    pub amount: BigUint<M>,
+    pub unbond_epoch: u64,
+}
+
+/// A mock of the MultiversX Staking Provider Delegation smart contract. It implements the same endpoints used by the
+/// Liquid Staking smart contract through `delegation_mod::Proxy` and allows to inject failures on each one of them, such
+/// that both branches of the Liquid Staking callbacks can be exercised.
+#[multiversx_sc::contract]
+pub trait DelegationMock {
+    /// Initializes the mock.
+    ///
+    /// # Arguments
+    ///
+    /// - `unbond_period` - the number of epochs an undelegated amount needs to wait before it can be withdrawn
+    ///
+    #[init]
+    fn init(&self, unbond_period: u64) {
+        self.unbond_period().set(unbond_period);
+    }
+
+    #[payable("EGLD")]
+    #[endpoint(delegate)]
+    fn delegate(&self) {
+        self.require_no_failure(MockEndpoint::Delegate);
+
+        let egld_amount = self.call_value().egld_value().clone_value();
+        require!(egld_amount > BigUint::zero(), "delegate value must be higher than zero");
+
+        let caller = self.blockchain().get_caller();
+        self.delegated(&caller).update(|amount| *amount += &egld_amount);
+        self.total_active_stake().update(|amount| *amount += &egld_amount);
+    }
+
+    #[endpoint(unDelegate)]
+    fn undelegate(&self, egld_amount: BigUint) {
+        self.require_no_failure(MockEndpoint::Undelegate);
+
+        let caller = self.blockchain().get_caller();
+        let delegated_mapper = self.delegated(&caller);
+        require!(egld_amount <= delegated_mapper.get(), "invalid value to undelegate");
+
+        delegated_mapper.update(|amount| *amount -= &egld_amount);
+        self.total_active_stake().update(|amount| *amount -= &egld_amount);
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        let undelegation = Undelegation {
+            amount: egld_amount,
+            unbond_epoch: current_epoch + self.unbond_period().get(),
+        };
+        self.undelegations(&caller).push(&undelegation);
+    }
+
+    #[endpoint(withdraw)]
+    fn withdraw(&self) {
+        self.require_no_failure(MockEndpoint::Withdraw);
+
+        let caller = self.blockchain().get_caller();
+        let current_epoch = self.blockchain().get_block_epoch();
+
+        let mut undelegations_mapper = self.undelegations(&caller);
+        let mut withdrawn_amount = BigUint::zero();
+        let mut index = 1usize;
+        while index <= undelegations_mapper.len() {
+            let undelegation = undelegations_mapper.get(index);
+            if undelegation.unbond_epoch <= current_epoch {
+                withdrawn_amount += undelegation.amount;
+                undelegations_mapper.swap_remove(index);
+            } else {
+                index += 1;
+            }
+        }
+
+        require!(withdrawn_amount > BigUint::zero(), "nothing to unBond");
+
+        self.send().direct_egld(&caller, &withdrawn_amount);
+    }
+
+    #[endpoint(claimRewards)]
+    fn claim_rewards(&self) {
+        self.require_no_failure(MockEndpoint::ClaimRewards);
+
+        let caller = self.blockchain().get_caller();
+        let rewards = self.rewards(&caller).take();
+        if rewards > BigUint::zero() {
+            self.send().direct_egld(&caller, &rewards);
+        }
+    }
+
+    /// Funds rewards for a given delegator, which can be later claimed using `claimRewards`.
+    ///
+    #[payable("EGLD")]
+    #[endpoint(addRewards)]
+    fn add_rewards(&self, delegator: ManagedAddress) {
+        let egld_amount = self.call_value().egld_value().clone_value();
+        self.rewards(&delegator).update(|amount| *amount += &egld_amount);
+    }
+
+    /// Sets the service fee (in hundredths of percent) and the delegation cap returned by `getContractConfig`, where a
+    /// zero cap means uncapped.
+    ///
+    #[endpoint(setContractConfig)]
+    fn set_contract_config(&self, service_fee: BigUint, max_delegation_cap: BigUint) {
+        self.service_fee().set(service_fee);
+        self.max_delegation_cap().set(max_delegation_cap);
+    }
+
+    /// Overrides the total active stake, i.e. simulates delegations from other delegators.
+    ///
+    #[endpoint(setTotalActiveStake)]
+    fn set_total_active_stake(&self, total_active_stake: BigUint) {
+        self.total_active_stake().set(total_active_stake);
+    }
+
+    /// Returns the contract configuration with the same layout as the Staking Provider Delegation smart contract: owner,
+    /// service fee, max delegation cap, initial owner funds, automatic activation, with delegation cap, changeable
+    /// service fee, check cap on redelegate, created nonce and unbond period.
+    ///
+    #[view(getContractConfig)]
+    fn get_contract_config(&self) -> MultiValueEncoded<ManagedBuffer> {
+        self.require_no_failure(MockEndpoint::ContractConfig);
+
+        let max_delegation_cap = self.max_delegation_cap().get();
+        let with_delegation_cap = max_delegation_cap > 0;
+
+        let mut config = MultiValueEncoded::new();
+        config.push(self.blockchain().get_owner_address().as_managed_buffer().clone());
+        config.push(self.service_fee().get().to_bytes_be_buffer());
+        config.push(max_delegation_cap.to_bytes_be_buffer());
+        config.push(ManagedBuffer::new());
+        config.push(ManagedBuffer::from(b"true"));
+        config.push(self.bool_buffer(with_delegation_cap));
+        config.push(ManagedBuffer::from(b"true"));
+        config.push(ManagedBuffer::from(b"true"));
+        config.push(ManagedBuffer::new());
+        config.push(ManagedBuffer::from(&self.unbond_period().get().to_be_bytes()[..]));
+        config
+    }
+
+    fn bool_buffer(&self, value: bool) -> ManagedBuffer {
+        if value {
+            ManagedBuffer::from(b"true")
+        } else {
+            ManagedBuffer::from(b"false")
+        }
+    }
+
+    /// Forces the given endpoint to fail (or to succeed again) from this point onwards.
+    ///
+    #[endpoint(setFailure)]
+    fn set_failure(&self, endpoint: MockEndpoint, fail: bool) {
+        self.failure(endpoint).set(fail);
+    }
+
+    /// Returns the undelegated amount that can already be withdrawn by a given delegator.
+    ///
+    #[view(getUserUnBondable)]
+    fn get_user_unbondable(&self, delegator: ManagedAddress) -> BigUint {
+        let current_epoch = self.blockchain().get_block_epoch();
+        let mut unbondable = BigUint::zero();
+        for undelegation in self.undelegations(&delegator).iter() {
+            if undelegation.unbond_epoch <= current_epoch {
+                unbondable += undelegation.amount;
+            }
+        }
+        unbondable
+    }
+
+    fn require_no_failure(&self, endpoint: MockEndpoint) {
+        require!(!self.failure(endpoint).get(), "mock failure");
+    }
+
+    #[view(getUserActiveStake)]
+    #[storage_mapper("delegated")]
+    fn delegated(&self, delegator: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    #[view(getTotalActiveStake)]
+    #[storage_mapper("totalActiveStake")]
+    fn total_active_stake(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("undelegations")]
+    fn undelegations(&self, delegator: &ManagedAddress) -> VecMapper<Undelegation<Self::Api>>;
+
+    #[view(getClaimableRewards)]
+    #[storage_mapper("rewards")]
+    fn rewards(&self, delegator: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("serviceFee")]
+    fn service_fee(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("maxDelegationCap")]
+    fn max_delegation_cap(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("unbondPeriod")]
+    fn unbond_period(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("failure")]
+    fn failure(&self, endpoint: MockEndpoint) -> SingleValueMapper<bool>;
+}
@@ -0,0 +1,34 @@
+#![no_std]
+
+multiversx_sc::imports!();
+
+/// A mock of an integrator smart contract, such as a router, that receives the tokens resulting from Liquid Staking
+/// operations through a transfer and execute and credits them to a given account.
+#[multiversx_sc::contract]
+pub trait RouterMock {
+    #[init]
+    fn init(&self) {}
+
+    /// Credits the received payment to the given account.
+    ///
+    /// # Arguments
+    ///
+    /// - `account` - the account to be credited
+    ///
+    #[payable("*")]
+    #[endpoint(deposit)]
+    fn deposit(&self, account: ManagedAddress) {
+        let (token_id, token_nonce, amount) = self.call_value().egld_or_single_esdt().into_tuple();
+        self.deposits(&account, &token_id, token_nonce)
+            .update(|deposit| *deposit += amount);
+    }
+
+    #[view(getDeposit)]
+    #[storage_mapper("deposits")]
+    fn deposits(
+        &self,
+        account: &ManagedAddress,
+        token_id: &EgldOrEsdtTokenIdentifier,
+        token_nonce: u64,
+    ) -> SingleValueMapper<BigUint>;
+}